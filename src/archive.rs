@@ -0,0 +1,739 @@
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::edit_league_error::handle_inquire_error;
+use crate::edit_league_error::handle_serde_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::free_agent::load_free_agents;
+use crate::free_agent::save_free_agent;
+use crate::free_agent::FreeAgent;
+use crate::in_transaction;
+use crate::injury::save_injury;
+use crate::inquire_check;
+use crate::league::check_name_vec;
+use crate::league::insert_new_league;
+use crate::league::load_teams_from_sql;
+use crate::league::save_league;
+use crate::league::League;
+use crate::minor_leaguer::fill_farm;
+use crate::player::Player;
+use crate::team::Team;
+use crate::team::TeamSpot;
+use inquire::validator::MinLengthValidator;
+use inquire::{Confirm, Select, Text};
+use rand::Rng;
+use rusqlite::Connection;
+
+/* Archives are copies of a league saved in the league_archive table via League::create_json_archives.
+An archive can be used to roll a league back to the state it was in when the archive was saved, or imported as a new league.
+When a league is rolled back, teams keep their ids so that the league's schedule still points to the right teams, and players keep their ids when possible so the draft history, trade log and note log still point to the right players.*/
+
+/// A copy of a league that was saved in the league_archive table.
+pub struct ArchiveEntry {
+    pub archive_id: i64,
+    pub date_saved: String,
+    pub league: League,
+    /// The league's free agent pool, or None if the archive was saved before free agents were archived.
+    pub free_agents: Option<Vec<FreeAgent>>,
+    /// The rows of the league's farm systems as a JSON array, or None if the archive was saved before farm systems were archived.
+    pub prospects: Option<String>,
+    /// The rows of the league's retired players as a JSON array, or None if the archive was saved before retired players were archived.
+    pub retired_players: Option<String>,
+}
+
+impl fmt::Display for ArchiveEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Archive {} saved on {}, {} teams",
+            self.archive_id,
+            self.date_saved,
+            self.league.teams.len()
+        )
+    }
+}
+
+/// The choices a user has after picking an archive.
+enum ArchiveAction {
+    RollBack,
+    ImportAsNew,
+    Cancel,
+}
+
+impl fmt::Display for ArchiveAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Self::RollBack => "Roll the league back to this archive.",
+            Self::ImportAsNew => "Import this archive as a new league.",
+            Self::Cancel => "Cancel",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// The id, save date, league json, free agent json, prospect json and retired player json of an archive, as stored in the database.
+type ArchiveRow = (
+    i64,
+    String,
+    String,
+    Option<String>,
+    Option<String>,
+    Option<String>,
+);
+
+/// Loads every archive that has been saved for a league, oldest first.
+pub fn get_league_archives(
+    conn: &Connection,
+    league_id: i64,
+) -> Result<Vec<ArchiveEntry>, EditLeagueError> {
+    let mut stmt = handle_sql_error(conn.prepare(
+        "SELECT archive_id,date_saved,league_data,free_agent_data,prospect_data,retired_data FROM league_archive WHERE league_id = ?1 ORDER BY archive_id",
+    ))?;
+    let rows: Vec<Result<ArchiveRow, rusqlite::Error>> =
+        handle_sql_error(stmt.query_map([league_id], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                row.get(3)?,
+                row.get(4)?,
+                row.get(5)?,
+            ))
+        }))?
+        .collect();
+    let mut result = Vec::new();
+    for row in rows {
+        let (archive_id, date_saved, league_data, free_agent_data, prospects, retired_players) =
+            handle_sql_error(row)?;
+        let free_agents = match free_agent_data {
+            Some(data) => Some(handle_serde_error(serde_json::from_str(&data))?),
+            None => None,
+        };
+        result.push(ArchiveEntry {
+            archive_id,
+            date_saved,
+            league: handle_serde_error(serde_json::from_str(&league_data))?,
+            free_agents,
+            prospects,
+            retired_players,
+        });
+    }
+    Ok(result)
+}
+
+/// Returns every player on a team.
+fn team_players(team: &Team) -> Vec<&Player> {
+    let mut players: Vec<&Player> = team
+        .lineup
+        .iter()
+        .chain(team.bench.iter())
+        .chain(team.starting_pitching.iter())
+        .collect();
+    if let Some(pen) = &team.bullpen {
+        players.extend(pen.iter());
+    }
+    players
+}
+
+/// Compares the live version of a league with an archived copy, and returns a line describing each change rolling back to the archive would make.
+pub fn archive_differences(live: &League, archived: &League) -> Vec<String> {
+    let mut result = Vec::new();
+    let live_teams: HashMap<i64, &Team> = live.new_team_hash();
+    let archived_teams: HashMap<i64, &Team> = archived.new_team_hash();
+    for team in live.teams.iter() {
+        if !archived_teams.contains_key(&team.team_id) {
+            result.push(format!("{} would be removed from the league.", team.name));
+        }
+    }
+    for archived_team in archived.teams.iter() {
+        let live_team = match live_teams.get(&archived_team.team_id) {
+            Some(team) => team,
+            None => {
+                result.push(format!(
+                    "{} would be added back to the league.",
+                    archived_team.name
+                ));
+                continue;
+            }
+        };
+        if live_team.name != archived_team.name || live_team.abrv != archived_team.abrv {
+            result.push(format!(
+                "{} {} would be renamed to {} {}.",
+                live_team.abrv, live_team.name, archived_team.abrv, archived_team.name
+            ));
+        }
+        if (live_team.wins, live_team.losses) != (archived_team.wins, archived_team.losses) {
+            result.push(format!(
+                "{}'s record would change from {}-{} to {}-{}.",
+                archived_team.name,
+                live_team.wins,
+                live_team.losses,
+                archived_team.wins,
+                archived_team.losses
+            ));
+        }
+        let live_players: HashMap<i64, &Player> = team_players(live_team)
+            .into_iter()
+            .map(|player| (player.player_id, player))
+            .collect();
+        let archived_players: HashMap<i64, &Player> = team_players(archived_team)
+            .into_iter()
+            .map(|player| (player.player_id, player))
+            .collect();
+        for player in team_players(live_team) {
+            if !archived_players.contains_key(&player.player_id) {
+                result.push(format!(
+                    "{} would be removed from {}.",
+                    player, archived_team.name
+                ));
+            }
+        }
+        for player in team_players(archived_team) {
+            match live_players.get(&player.player_id) {
+                None => result.push(format!(
+                    "{} would be added back to {}.",
+                    player, archived_team.name
+                )),
+                Some(live_player) => {
+                    let (live_string, archived_string) =
+                        (live_player.to_string(), player.to_string());
+                    if live_string != archived_string {
+                        result.push(format!(
+                            "{}: {} would become {}.",
+                            archived_team.name, live_string, archived_string
+                        ));
+                    }
+                }
+            }
+        }
+    }
+    result
+}
+
+/// Saves a team to the database. If keep_id is true the team is saved with its current id, otherwise the team is given a new id.
+/// Returns the id of the team.
+fn insert_team(
+    conn: &Connection,
+    league_id: i64,
+    team: &Team,
+    keep_id: bool,
+) -> Result<i64, EditLeagueError> {
+    let team_id = match keep_id {
+        true => Some(team.team_id),
+        false => None,
+    };
+    let note = handle_serde_error(serde_json::to_value(&team.note))?;
+    handle_sql_error(conn.execute(
+        "INSERT OR REPLACE INTO teams(team_id,league_id,team_name,abrv,wins,losses,team_note) VALUES(?1,?2,?3,?4,?5,?6,?7)",
+        (team_id, league_id, &team.name, &team.abrv, team.wins, team.losses, note),
+    ))?;
+    Ok(conn.last_insert_rowid())
+}
+
+/// Saves a player to the database, and then gives them back the id they had in the archive, so the league's draft history, trade log and note log still point to them.
+/// If another player is using the id, the player keeps their new id and those logs are pointed to it instead.
+fn restore_player(
+    conn: &mut Connection,
+    league_id: i64,
+    player: &mut Player,
+    team_id: i64,
+    team_spot: TeamSpot,
+) -> Result<(), EditLeagueError> {
+    let old_id = player.player_id;
+    player.save_sql(conn, team_id, team_spot)?;
+    let new_id = player.player_id;
+    let updated = handle_sql_error(conn.execute(
+        "UPDATE players SET player_id = ?1 WHERE player_id = ?2
+        AND NOT EXISTS (SELECT 1 FROM players WHERE player_id = ?1)",
+        [old_id, new_id],
+    ))?;
+    if updated > 0 {
+        player.player_id = old_id;
+        return Ok(());
+    }
+    for sql in [
+        "UPDATE draft_picks SET player_id = ?1 WHERE player_id = ?2
+        AND draft_id IN (SELECT draft_id FROM drafts WHERE league_id = ?3)",
+        "UPDATE trade_players SET player_id = ?1 WHERE player_id = ?2
+        AND trade_id IN (SELECT trade_id FROM trades WHERE league_id = ?3)",
+        "UPDATE note_log SET subject_id = ?1 WHERE subject_type = 'Player' AND subject_id = ?2 AND league_id = ?3",
+    ] {
+        handle_sql_error(conn.execute(sql, [new_id, old_id, league_id]))?;
+    }
+    Ok(())
+}
+
+/// Saves the players on a team to the database with the ids they had in the archive.
+/// The team's archived injured list is saved again, pointing to the ids the players end up with.
+fn restore_players(
+    conn: &mut Connection,
+    league_id: i64,
+    team: &mut Team,
+) -> Result<(), EditLeagueError> {
+    let team_id = team.team_id;
+    let mut rosters = vec![
+        (&mut team.lineup, TeamSpot::StartingLineup),
+        (&mut team.bench, TeamSpot::BenchHitter),
+        (&mut team.starting_pitching, TeamSpot::StartingPitcher),
+    ];
+    if let Some(pen) = team.bullpen.as_mut() {
+        rosters.push((pen, TeamSpot::Bullpen));
+    }
+    let mut player_ids = HashMap::new();
+    for (players, team_spot) in rosters {
+        for player in players.iter_mut() {
+            let old_id = player.player_id;
+            restore_player(conn, league_id, player, team_id, team_spot)?;
+            player_ids.insert(old_id, player.player_id);
+        }
+    }
+    for injury in team.injured_list.iter_mut() {
+        if let Some(player_id) = player_ids.get(&injury.player_id) {
+            injury.player_id = *player_id;
+            injury.injury_id = save_injury(conn, injury)?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the rows of a league's farm systems as a JSON array, so they can be saved with an archive.
+pub fn archive_prospects(conn: &Connection, league_id: i64) -> Result<String, EditLeagueError> {
+    handle_sql_error(conn.query_row(
+        "SELECT json_group_array(json_object('prospect_id', prospects.prospect_id, 'team_id', prospects.team_id,
+            'prospect_name', prospect_name, 'hand', hand, 'is_pitcher', is_pitcher, 'potential', potential,
+            'player_data', player_data))
+        FROM prospects INNER JOIN teams ON teams.team_id = prospects.team_id WHERE teams.league_id = ?1",
+        [league_id],
+        |row| row.get(0),
+    ))
+}
+
+/// Returns the rows of a league's retired players as a JSON array, so they can be saved with an archive.
+pub fn archive_retired_players(
+    conn: &Connection,
+    league_id: i64,
+) -> Result<String, EditLeagueError> {
+    handle_sql_error(conn.query_row(
+        "SELECT json_group_array(json_object('retired_id', retired_id, 'team_id', team_id, 'player_id', player_id,
+            'player_name', player_name, 'age', age, 'pos', pos, 'team_spot', team_spot, 'tier', tier,
+            'trade_value', trade_value, 'date_retired', date_retired, 'player_data', player_data))
+        FROM retired_players WHERE league_id = ?1",
+        [league_id],
+        |row| row.get(0),
+    ))
+}
+
+/// Saves the archived farm system and retired players of a team again. Rows keep the id they had in the archive unless another row is using it.
+fn restore_team_history(
+    conn: &Connection,
+    league_id: i64,
+    old_team_id: i64,
+    team_id: i64,
+    prospects: &str,
+    retired_players: &str,
+) -> Result<(), EditLeagueError> {
+    handle_sql_error(conn.execute(
+        "INSERT INTO prospects(prospect_id, team_id, prospect_name, hand, is_pitcher, potential, player_data)
+        SELECT CASE WHEN EXISTS (SELECT 1 FROM prospects WHERE prospect_id = json_extract(value, '$.prospect_id'))
+            THEN NULL ELSE json_extract(value, '$.prospect_id') END,
+            ?2, json_extract(value, '$.prospect_name'), json_extract(value, '$.hand'),
+            json_extract(value, '$.is_pitcher'), json_extract(value, '$.potential'),
+            json_extract(value, '$.player_data')
+        FROM json_each(?1) WHERE json_extract(value, '$.team_id') = ?3",
+        (prospects, team_id, old_team_id),
+    ))?;
+    handle_sql_error(conn.execute(
+        "INSERT INTO retired_players(retired_id, league_id, team_id, player_id, player_name, age, pos, team_spot, tier, trade_value, date_retired, player_data)
+        SELECT CASE WHEN EXISTS (SELECT 1 FROM retired_players WHERE retired_id = json_extract(value, '$.retired_id'))
+            THEN NULL ELSE json_extract(value, '$.retired_id') END,
+            ?2, ?3, json_extract(value, '$.player_id'), json_extract(value, '$.player_name'),
+            json_extract(value, '$.age'), json_extract(value, '$.pos'), json_extract(value, '$.team_spot'),
+            json_extract(value, '$.tier'), json_extract(value, '$.trade_value'),
+            json_extract(value, '$.date_retired'), json_extract(value, '$.player_data')
+        FROM json_each(?1) WHERE json_extract(value, '$.team_id') = ?4",
+        (retired_players, league_id, team_id, old_team_id),
+    ))?;
+    Ok(())
+}
+
+/// Replaces a league's teams, players, free agents, farm systems and retired players in the database with the ones saved in an archive.
+/// Teams that were added after the archive was saved are removed, unless they are on the league's schedule, in which case an error is returned.
+/// An archive saved before free agents were archived can only be rolled back to while the league's free agent pool is empty.
+/// An archive saved before farm systems and retired players were archived leaves them as they are for the teams it restores.
+pub fn roll_back_league(
+    conn: &mut Connection,
+    live: &League,
+    entry: ArchiveEntry,
+) -> Result<(), EditLeagueError> {
+    let ArchiveEntry {
+        league: archived,
+        free_agents,
+        prospects,
+        retired_players,
+        ..
+    } = entry;
+    let archived_teams = archived.new_team_hash();
+    let removed_teams: Vec<&Team> = live
+        .teams
+        .iter()
+        .filter(|team| !archived_teams.contains_key(&team.team_id))
+        .collect();
+    for team in removed_teams.iter() {
+        let scheduled: i64 = handle_sql_error(conn.query_row(
+            "SELECT COUNT(*) FROM series WHERE home_team_id = ?1 OR away_team_id = ?1",
+            [team.team_id],
+            |row| row.get(0),
+        ))?;
+        if scheduled > 0 {
+            return Err(EditLeagueError::GameError(format!(
+                "Error: {} was added after the archive was saved and is on the league's schedule, so the league can not be rolled back. Please import the archive as a new league instead.",
+                team.name
+            )));
+        }
+    }
+    if free_agents.is_none() && !load_free_agents(conn, live.league_id)?.is_empty() {
+        return Err(EditLeagueError::GameError(
+            "Error: The archive was saved before free agents were archived, so the league can not be rolled back while it has free agents. Please import the archive as a new league instead.".to_string(),
+        ));
+    }
+    let removed_ids: Vec<i64> = removed_teams.iter().map(|team| team.team_id).collect();
+    let live_ids: Vec<i64> = live.teams.iter().map(|team| team.team_id).collect();
+    let league_id = live.league_id;
+    let League {
+        teams: mut archived_teams,
+        note,
+        ..
+    } = archived;
+    in_transaction(conn, |conn| {
+        // Injuries are removed along with the players they belong to, and the archived injured lists are saved again below.
+        handle_sql_error(conn.execute(
+            "DELETE FROM injuries WHERE player_id IN
+            (SELECT player_id FROM players WHERE team_id IN (SELECT team_id FROM teams WHERE league_id = ?1))",
+            [league_id],
+        ))?;
+        handle_sql_error(conn.execute(
+            "DELETE FROM players WHERE team_id IN (SELECT team_id FROM teams WHERE league_id = ?1)",
+            [league_id],
+        ))?;
+        for team_id in removed_ids {
+            // A team's farm system, retired players and draft picks are removed along with the team.
+            for table in ["prospects", "retired_players", "draft_picks"] {
+                handle_sql_error(conn.execute(
+                    &format!("DELETE FROM {} WHERE team_id = ?1", table),
+                    [team_id],
+                ))?;
+            }
+            handle_sql_error(conn.execute("DELETE FROM teams WHERE team_id = ?1", [team_id]))?;
+        }
+        // Archives saved before farm systems and retired players were archived have neither, so they are kept as they are.
+        if prospects.is_some() && retired_players.is_some() {
+            handle_sql_error(conn.execute(
+                "DELETE FROM prospects WHERE team_id IN (SELECT team_id FROM teams WHERE league_id = ?1)",
+                [league_id],
+            ))?;
+            handle_sql_error(conn.execute(
+                "DELETE FROM retired_players WHERE league_id = ?1",
+                [league_id],
+            ))?;
+        }
+        for team in archived_teams.iter_mut() {
+            // A team that was removed from the league may have had it's id reused by another league, in which case the team is given a new id.
+            let id_in_use: bool = handle_sql_error(conn.query_row(
+                "SELECT EXISTS(SELECT 1 FROM teams WHERE team_id = ?1)",
+                [team.team_id],
+                |row| row.get(0),
+            ))?;
+            let keep_id = live_ids.contains(&team.team_id) || !id_in_use;
+            let old_team_id = team.team_id;
+            team.team_id = insert_team(conn, league_id, team, keep_id)?;
+            restore_players(conn, league_id, team)?;
+            if let (Some(prospects), Some(retired_players)) = (&prospects, &retired_players) {
+                restore_team_history(
+                    conn,
+                    league_id,
+                    old_team_id,
+                    team.team_id,
+                    prospects,
+                    retired_players,
+                )?;
+            }
+        }
+        // Players released after the archive was saved are back on their team, so the pool is replaced with the archived one.
+        handle_sql_error(
+            conn.execute("DELETE FROM free_agents WHERE league_id = ?1", [league_id]),
+        )?;
+        for free_agent in free_agents.iter().flatten() {
+            save_free_agent(
+                conn,
+                league_id,
+                free_agent.former_team_id,
+                &free_agent.player,
+            )?;
+        }
+        let league_note = handle_serde_error(serde_json::to_value(&note))?;
+        handle_sql_error(conn.execute(
+            "UPDATE leagues SET league_note = ?1 WHERE league_id = ?2",
+            (league_note, league_id),
+        ))?;
+        Ok(())
+    })
+}
+
+/// Saves a copy of a league to the database as a new league with the given name. Every team and player in the copy is given a new id, and each team's farm system is filled.
+/// Returns the new league.
+pub fn insert_league_copy(
+    conn: &mut Connection,
+    league: League,
+    name: &String,
+    seed: u64,
+) -> Result<League, EditLeagueError> {
+    in_transaction(conn, |conn| {
+        let mut new_league = insert_new_league(conn, name, league.era, league.gender, seed)?;
+        let league_note = handle_serde_error(serde_json::to_value(&league.note))?;
+        handle_sql_error(conn.execute(
+            "UPDATE leagues SET league_note = ?1 WHERE league_id = ?2",
+            (league_note, new_league.league_id),
+        ))?;
+        new_league.note = league.note;
+        for mut team in league.teams.into_iter() {
+            team.team_id = insert_team(conn, new_league.league_id, &team, false)?;
+            let team_id = team.team_id;
+            team.save_players_sql(conn, team_id)?;
+            let mut team_thread = new_league.team_rng();
+            fill_farm(
+                conn,
+                team_id,
+                new_league.gender,
+                new_league.era,
+                &mut team_thread,
+            )?;
+            new_league.add_team(team);
+        }
+        Ok(new_league)
+    })
+}
+
+/// Prompts the user for the name of a new league, and loops until a name that is not taken is entered.
+pub fn new_league_name(conn: &Connection, default: &str) -> Result<String, EditLeagueError> {
+    let taken_names = handle_sql_error(check_name_vec(conn))?;
+    loop {
+        let name = handle_inquire_error(
+            Text::new("Please enter a name for the new league.")
+                .with_default(default)
+                .with_validator(MinLengthValidator::new(1))
+                .prompt(),
+        )?;
+        match taken_names.contains(&name) {
+            true => println!("There is already a league named {}.", name),
+            false => return Ok(name),
+        }
+    }
+}
+
+/// Prompts the user to pick one of a league's archives, shows what has changed since the archive was saved, and then lets the user roll the league back to the archive or import it as a new league.
+pub fn restore_archive_from_input<R: Rng>(
+    league: &League,
+    conn: &mut Connection,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    let archives = get_league_archives(conn, league.league_id)?;
+    if archives.is_empty() {
+        println!("{} does not have any archives yet.", league.name);
+        return Ok(());
+    }
+    let entry = match Select::new("Which archive would you like to use?", archives).prompt() {
+        Ok(entry) => entry,
+        Err(message) => return inquire_check(message),
+    };
+    let differences = archive_differences(league, &entry.league);
+    match differences.is_empty() {
+        true => println!("\nThe archive matches the league as it is now."),
+        false => {
+            println!("\nChanges since the archive was saved:");
+            for line in differences.iter() {
+                println!("    {}", line);
+            }
+        }
+    }
+    let options = vec![
+        ArchiveAction::RollBack,
+        ArchiveAction::ImportAsNew,
+        ArchiveAction::Cancel,
+    ];
+    let action = match Select::new("What would you like to do?", options).prompt() {
+        Ok(action) => action,
+        Err(message) => return inquire_check(message),
+    };
+    match action {
+        ArchiveAction::RollBack => {
+            let confirmed = match Confirm::new(
+                "Rolling back will replace the league's teams and players, would you like to continue?",
+            )
+            .with_default(false)
+            .prompt()
+            {
+                Ok(confirmed) => confirmed,
+                Err(message) => return inquire_check(message),
+            };
+            if !confirmed {
+                return Ok(());
+            }
+            let note = entry.league.note.clone();
+            let archive_id = entry.archive_id;
+            roll_back_league(conn, league, entry)?;
+            // We load the league from the database again, so the saved folder matches the database.
+            let mut restored = League::new(
+                &league.name,
+                league.gender,
+                league.era,
+                league.league_id,
+                league.seed,
+            );
+            restored.note = note;
+            load_teams_from_sql(league.league_id, &mut restored, conn)?;
+            println!(
+                "{} has been rolled back to archive {}.",
+                league.name, archive_id
+            );
+            save_league(&restored);
+        }
+        ArchiveAction::ImportAsNew => {
+            let default_name = format!("{} {}", entry.league.name, entry.date_saved);
+            let name = match new_league_name(conn, &default_name) {
+                Ok(name) => name,
+                Err(EditLeagueError::Inquire(message)) => return inquire_check(message),
+                Err(message) => return Err(message),
+            };
+            let seed = entry.league.seed.unwrap_or_else(|| thread.gen());
+            let new_league = insert_league_copy(conn, entry.league, &name, seed)?;
+            println!(
+                "{} has been created from archive {}.",
+                name, entry.archive_id
+            );
+            save_league(&new_league);
+        }
+        ArchiveAction::Cancel => {}
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::era::Era;
+    use crate::free_agent::release_player;
+    use crate::injury::injure_player;
+    use crate::player::PlayerGender;
+    use crate::test_league;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn archive_restore() {
+        let (mut conn, mut league) = test_league(Era::Modern, PlayerGender::Male, 7);
+        let count = |conn: &Connection, sql: &str| -> i64 {
+            conn.query_row(sql, [], |row| row.get(0)).unwrap()
+        };
+        // An injury in the archive is restored by a roll back, while one after the archive is removed.
+        let mut seeded = StdRng::seed_from_u64(10);
+        let injured = league.teams[0].lineup[0].clone();
+        let injury = injure_player(&conn, &injured, &mut seeded).unwrap();
+        league.teams[0].injured_list.push(injury);
+        league.create_json_archives(&mut conn).unwrap();
+        let archived_players = count(&conn, "SELECT COUNT(*) FROM players");
+        let archived_prospects = count(&conn, "SELECT COUNT(*) FROM prospects");
+        // A prospect that leaves the farm system after the archive is returned to it by a roll back.
+        conn.execute(
+            "DELETE FROM prospects WHERE prospect_id = (SELECT MIN(prospect_id) FROM prospects)",
+            [],
+        )
+        .unwrap();
+        let injury = injure_player(&conn, &league.teams[0].lineup[1], &mut seeded).unwrap();
+        league.teams[0].injured_list.push(injury);
+        // A team added after the archive would be removed by a roll back.
+        let mut thread = league.team_rng();
+        let league_id = league.league_id;
+        league
+            .new_team(
+                &"POR".to_string(),
+                &"Portland Beavers".to_string(),
+                &mut thread,
+                league_id,
+                &mut conn,
+            )
+            .unwrap();
+        // A player released after the archive returns to their team instead of staying in the free agent pool.
+        let released = league.teams[0].bench[0].player_id;
+        release_player(&mut conn, &mut league, 0, released).unwrap();
+        assert_eq!(load_free_agents(&conn, league.league_id).unwrap().len(), 1);
+        let mut archives = get_league_archives(&conn, league.league_id).unwrap();
+        assert_eq!(archives.len(), 1);
+        let entry = archives.remove(0);
+        assert_eq!(entry.free_agents.as_ref().map(|x| x.len()), Some(0));
+        assert_eq!(archive_differences(&league, &entry.league).len(), 2);
+        let archived_ids: Vec<i64> = entry
+            .league
+            .teams
+            .iter()
+            .flat_map(team_players)
+            .map(|player| player.player_id)
+            .collect();
+        roll_back_league(&mut conn, &league, entry).unwrap();
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM players"),
+            archived_players
+        );
+        // Every player is restored with the id they had in the archive.
+        for player_id in archived_ids {
+            let sql = format!(
+                "SELECT COUNT(*) FROM players WHERE player_id = {}",
+                player_id
+            );
+            assert_eq!(count(&conn, &sql), 1);
+        }
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM prospects"),
+            archived_prospects
+        );
+        assert!(load_free_agents(&conn, league.league_id)
+            .unwrap()
+            .is_empty());
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM injuries"), 1);
+        let orphans =
+            "SELECT COUNT(*) FROM injuries WHERE player_id NOT IN (SELECT player_id FROM players)";
+        assert_eq!(count(&conn, orphans), 0);
+        let mut restored = League::new(
+            &league.name,
+            league.gender,
+            league.era,
+            league.league_id,
+            league.seed,
+        );
+        load_teams_from_sql(league.league_id, &mut restored, &mut conn).unwrap();
+        let injured_list = &restored.teams[0].injured_list;
+        assert_eq!(injured_list.len(), 1);
+        assert_eq!(injured_list[0].player_name, injured.name);
+        let mut archived = get_league_archives(&conn, league.league_id)
+            .unwrap()
+            .remove(0)
+            .league;
+        assert!(archive_differences(&restored, &archived).is_empty());
+        // An archive imported as a new league gets new ids, and a farm system for each team.
+        let old_team_id = archived.teams[0].team_id;
+        archived.name = "Imported".to_string();
+        let copy = insert_league_copy(&mut conn, archived, &"Imported".to_string(), 7).unwrap();
+        assert_ne!(copy.league_id, league.league_id);
+        assert_ne!(copy.teams[0].team_id, old_team_id);
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM players"),
+            archived_players * 2
+        );
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM prospects"),
+            archived_prospects * 2
+        );
+        for path in glob::glob("Test League_*_Archive*.json").unwrap().flatten() {
+            std::fs::remove_file(path).ok();
+        }
+    }
+}
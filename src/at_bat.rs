@@ -0,0 +1,269 @@
+use crate::player::Hand;
+use crate::player::Player;
+use crate::traits::PitcherTrait;
+use crate::traits::PlayerTrait;
+use core::fmt;
+use rand::Rng;
+
+/* In Deadball, every plate appearance is settled by a single swing.
+The batter rolls a d100, and the pitcher rolls their pitch die, which is added to the d100 to create the swing.
+A low swing is good for the batter, while a high swing is good for the pitcher.
+The swing is compared against the batters BT and OBT to see what happened:
+
+    1 - 5           Critical hit, roll on the hit table and bump the result up a level.
+    6 - BT          Hit, roll on the hit table.
+    BT+1 - OBT      Walk.
+    OBT+1 - OBT+5   Possible error, the defense rolls a d12 and makes an error on a 1-4.
+    OBT+6 - 49      Productive out, runners may advance.
+    50+             Out.
+
+The last digit of the swing determines if an out is a strikeout, a ground ball or a fly ball.
+*/
+
+/// The lowest swing that is not a critical hit.
+const CRITICAL_HIT_MAX: i32 = 5;
+/// How far past a batters OBT a swing can land and still give the defense a chance to make an error.
+const POSSIBLE_ERROR_RANGE: i32 = 5;
+/// The highest swing that results in a productive out.
+const PRODUCTIVE_OUT_MAX: i32 = 49;
+/// When the defense rolls a d12 on a possible error, this is the highest roll that results in an error.
+const ERROR_MAX: i32 = 4;
+
+/// The types of hits a batter can get, ordered from least to most bases.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum HitType {
+    Single,
+    Double,
+    Triple,
+    HomeRun,
+}
+
+impl HitType {
+    /// Returns how many bases the batter takes on the hit.
+    pub fn bases(&self) -> i32 {
+        match self {
+            Self::Single => 1,
+            Self::Double => 2,
+            Self::Triple => 3,
+            Self::HomeRun => 4,
+        }
+    }
+    /// Critical hits bump the result of the hit table up a level. A home run can not be improved upon.
+    fn upgrade(&self) -> Self {
+        match self {
+            Self::Single => Self::Double,
+            Self::Double => Self::Triple,
+            Self::Triple | Self::HomeRun => Self::HomeRun,
+        }
+    }
+}
+
+impl fmt::Display for HitType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chars = match self {
+            Self::Single => "Single",
+            Self::Double => "Double",
+            Self::Triple => "Triple",
+            Self::HomeRun => "Home Run",
+        };
+        write!(f, "{}", chars)
+    }
+}
+
+/// The type of out that is recorded, which matters when there are runners on base.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum OutType {
+    Strikeout,
+    GroundOut,
+    FlyOut,
+}
+
+impl fmt::Display for OutType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chars = match self {
+            Self::Strikeout => "Strikeout",
+            Self::GroundOut => "Ground Out",
+            Self::FlyOut => "Fly Out",
+        };
+        write!(f, "{}", chars)
+    }
+}
+
+/// Where on the swing result table a swing landed, before any dice are rolled for hits or errors.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SwingResult {
+    CriticalHit,
+    Hit,
+    Walk,
+    PossibleError,
+    ProductiveOut(OutType),
+    Out(OutType),
+}
+
+/// The final result of a plate appearance.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AtBatOutcome {
+    Hit {
+        hit: HitType,
+        critical: bool,
+    },
+    Walk,
+    /// The batter reaches base on an error by the defense.
+    Error,
+    /// The batter is out, but runners on base are allowed to advance.
+    ProductiveOut(OutType),
+    Out(OutType),
+}
+
+impl fmt::Display for AtBatOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chars = match self {
+            Self::Hit {
+                hit,
+                critical: true,
+            } => format!("Critical Hit, {}", hit),
+            Self::Hit { hit, .. } => hit.to_string(),
+            Self::Walk => "Walk".to_string(),
+            Self::Error => "Reached on Error".to_string(),
+            Self::ProductiveOut(out) => format!("Productive Out, {}", out),
+            Self::Out(out) => out.to_string(),
+        };
+        write!(f, "{}", chars)
+    }
+}
+
+/// Contains the dice that were rolled during a plate appearance, as well as the outcome.
+#[derive(Copy, Clone, Debug)]
+pub struct AtBatResult {
+    pub pitch_roll: i32,
+    pub swing: i32,
+    pub outcome: AtBatOutcome,
+}
+
+impl fmt::Display for AtBatResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Swing {} (Pitch die {}): {}",
+            self.swing, self.pitch_roll, self.outcome
+        )
+    }
+}
+
+/// Returns true if the batter has the platoon advantage over the pitcher.
+/// Switch hitters always have the advantage, otherwise the batter and pitcher must throw and hit from opposite sides.
+fn has_platoon_advantage(batter: &Player, pitcher: &Player) -> bool {
+    matches!(
+        (batter.hand, pitcher.hand),
+        (Hand::S, _) | (Hand::L, Hand::R) | (Hand::R, Hand::L)
+    )
+}
+
+/// Returns the BT and OBT the batter has against the pitcher.
+/// The platoon advantage adds 1 to both targets, while a pitcher with good control (CN+) shrinks the range a batter can walk in, and a wild pitcher (CN-) increases it.
+pub fn get_targets(batter: &Player, pitcher: &Player) -> (i32, i32) {
+    let platoon_bonus = match has_platoon_advantage(batter, pitcher) {
+        true => 1,
+        false => 0,
+    };
+    let bt = batter.bt + platoon_bonus;
+    let control_mod = match pitcher.pitcher_trait {
+        Some(PitcherTrait::CN) => -2,
+        Some(PitcherTrait::CNM) => 2,
+        _ => 0,
+    };
+    // Good control can not take away a batters ability to get a hit, so OBT can never drop below BT.
+    let obt = (batter.obt + platoon_bonus + control_mod).max(bt);
+    (bt, obt)
+}
+
+/// Uses the last digit of a swing to determine what type of out was made.
+/// Strikeouts happen on a 0-2, ground outs on a 3-6, and fly outs on a 7-9.
+/// K+ pitchers and C- batters add to the strikeout range, while C+ batters take away from it.
+/// GB+ pitchers turn 7's and 8's into ground outs.
+pub fn get_out_type(batter: &Player, pitcher: &Player, swing: i32) -> OutType {
+    let last_digit = swing.rem_euclid(10);
+    let pitcher_k_mod = match pitcher.pitcher_trait {
+        Some(PitcherTrait::K) => 1,
+        _ => 0,
+    };
+    let strikeout_max = 2 + pitcher_k_mod - batter.b_traits.contact.to_int();
+    let ground_out_max = match pitcher.pitcher_trait {
+        Some(PitcherTrait::GB) => 8,
+        _ => 6,
+    };
+    if last_digit <= strikeout_max {
+        OutType::Strikeout
+    } else if last_digit <= ground_out_max {
+        OutType::GroundOut
+    } else {
+        OutType::FlyOut
+    }
+}
+
+/// Reads the swing result table, and determines where on the table a swing landed.
+pub fn read_swing(batter: &Player, pitcher: &Player, swing: i32) -> SwingResult {
+    let (bt, obt) = get_targets(batter, pitcher);
+    match swing {
+        ..=CRITICAL_HIT_MAX => SwingResult::CriticalHit,
+        _ if swing <= bt => SwingResult::Hit,
+        _ if swing <= obt => SwingResult::Walk,
+        _ if swing <= obt + POSSIBLE_ERROR_RANGE => SwingResult::PossibleError,
+        _ => {
+            let out_type = get_out_type(batter, pitcher, swing);
+            match (swing <= PRODUCTIVE_OUT_MAX, out_type) {
+                // A strikeout is never productive, as the ball is not put into play.
+                (true, OutType::Strikeout) | (false, _) => SwingResult::Out(out_type),
+                (true, _) => SwingResult::ProductiveOut(out_type),
+            }
+        }
+    }
+}
+
+/// Reads the hit table. The roll is a d20 that has already been modified by the batters power.
+/// S+ batters stretch a 17 into a triple, S++ batters stretch a 16 as well, while S- batters are never able to leg out a triple.
+pub fn read_hit_table(batter: &Player, roll: i32) -> HitType {
+    let speed = batter.b_traits.speed.to_int();
+    let triple_min = 18 - speed.max(0);
+    match roll {
+        ..=14 => HitType::Single,
+        19.. => HitType::HomeRun,
+        _ if roll >= triple_min && speed >= 0 => HitType::Triple,
+        _ => HitType::Double,
+    }
+}
+
+/// Rolls on the hit table for the batter. P+ and P++ batters add to the roll, while P- and P-- batters subtract from it.
+fn roll_hit<R: Rng>(batter: &Player, thread: &mut R) -> HitType {
+    let roll = thread.gen_range(1..=20) + batter.b_traits.power.to_int();
+    read_hit_table(batter, roll)
+}
+
+/// Resolves a single plate appearance between a batter and a pitcher.
+pub fn resolve_at_bat<R: Rng>(batter: &Player, pitcher: &Player, thread: &mut R) -> AtBatResult {
+    // The pitcher rolls their pitch die, which is added to the batters d100 roll.
+    let pitch_roll = pitcher.get_base_pd().roll(thread);
+    let swing = thread.gen_range(1..=100) + pitch_roll;
+    let outcome = match read_swing(batter, pitcher, swing) {
+        SwingResult::CriticalHit => AtBatOutcome::Hit {
+            hit: roll_hit(batter, thread).upgrade(),
+            critical: true,
+        },
+        SwingResult::Hit => AtBatOutcome::Hit {
+            hit: roll_hit(batter, thread),
+            critical: false,
+        },
+        SwingResult::Walk => AtBatOutcome::Walk,
+        SwingResult::PossibleError => match thread.gen_range(1..=12) {
+            ..=ERROR_MAX => AtBatOutcome::Error,
+            _ => AtBatOutcome::Out(OutType::GroundOut),
+        },
+        SwingResult::ProductiveOut(out_type) => AtBatOutcome::ProductiveOut(out_type),
+        SwingResult::Out(out_type) => AtBatOutcome::Out(out_type),
+    };
+    AtBatResult {
+        pitch_roll,
+        swing,
+        outcome,
+    }
+}
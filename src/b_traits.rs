@@ -8,7 +8,6 @@ use crate::Deserialize;
 use crate::Power;
 use crate::Serialize;
 use crate::Speed;
-use crate::ThreadRng;
 use crate::Toughness;
 use core::fmt;
 use rand::seq::SliceRandom;
@@ -158,7 +157,7 @@ impl BTraits {
         self.contact.get_rbi_score() + self.power.get_rbi_score()
     }
 
-    pub fn generate(&mut self, thread: &mut ThreadRng) -> bool {
+    pub fn generate<R: Rng>(&mut self, thread: &mut R) -> bool {
         let roll = thread.gen_range(1..=10) + thread.gen_range(1..=10);
 
         match roll {
@@ -220,22 +219,42 @@ impl BTraits {
 
     fn get_non_max_batter_traits(&self) -> Vec<UpgradableTraits> {
         let mut result = vec![];
-        if self.contact.is_max() {
+        if !self.contact.is_max() {
             result.push(UpgradableTraits::Contact);
         }
-        if self.power.is_max() {
+        if !self.power.is_max() {
             result.push(UpgradableTraits::Power);
         }
 
-        if self.speed.is_max() {
+        if !self.speed.is_max() {
             result.push(UpgradableTraits::Speed)
         }
 
-        if self.toughness.is_max() {
+        if !self.toughness.is_max() {
             result.push(UpgradableTraits::Toughness);
         }
 
-        if self.defense.is_max() {
+        if !self.defense.is_max() {
+            result.push(UpgradableTraits::Defense)
+        }
+        result
+    }
+    /// Returns every trait that can still be downgraded.
+    fn get_non_min_batter_traits(&self) -> Vec<UpgradableTraits> {
+        let mut result = vec![];
+        if !self.contact.is_min() {
+            result.push(UpgradableTraits::Contact);
+        }
+        if !self.power.is_min() {
+            result.push(UpgradableTraits::Power);
+        }
+        if !self.speed.is_min() {
+            result.push(UpgradableTraits::Speed)
+        }
+        if !self.toughness.is_min() {
+            result.push(UpgradableTraits::Toughness);
+        }
+        if !self.defense.is_min() {
             result.push(UpgradableTraits::Defense)
         }
         result
@@ -252,7 +271,7 @@ impl BTraits {
         upgrade_option.unwrap_or(self)
     }
     // Upgrades a random player trait. Returns self if all traits are maxed out.
-    pub fn upgrade_random_traits(self, thread: &mut ThreadRng) -> BTraits {
+    pub fn upgrade_random_traits<R: Rng>(self, thread: &mut R) -> BTraits {
         let non_max_traits = self.get_non_max_batter_traits();
         match non_max_traits.is_empty() {
             true => self,
@@ -263,6 +282,25 @@ impl BTraits {
         }
     }
 
+    pub fn downgradable_from_trait(self, down_trait: UpgradableTraits) -> BTraits {
+        let downgrade_option = match down_trait {
+            UpgradableTraits::Contact => self.contact.downgrade(&self),
+            UpgradableTraits::Defense => self.defense.downgrade(&self),
+            UpgradableTraits::Power => self.power.downgrade(&self),
+            UpgradableTraits::Speed => self.speed.downgrade(&self),
+            UpgradableTraits::Toughness => self.toughness.downgrade(&self),
+        };
+        downgrade_option.unwrap_or(self)
+    }
+    // Downgrades a random player trait. Returns self if all traits are at their lowest level.
+    pub fn downgrade_random_traits<R: Rng>(self, thread: &mut R) -> BTraits {
+        let non_min_traits = self.get_non_min_batter_traits();
+        match non_min_traits.choose(thread) {
+            Some(picked_trait) => self.downgradable_from_trait(*picked_trait),
+            None => self,
+        }
+    }
+
     pub fn maxed_out() -> Self {
         BTraits {
             contact: Contact::C1,
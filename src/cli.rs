@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+use std::fs;
+
+use crate::edit_league_error::EditLeagueError;
+use crate::era::Era;
+use crate::in_transaction;
+use crate::league::check_name_vec;
+use crate::league::get_all_leagues_from_db;
+use crate::league::insert_new_league;
+use crate::league::load_teams_from_sql;
+use crate::league::save_league;
+use crate::league::BatterPosType;
+use crate::league::League;
+use crate::player::PlayerGender;
+use rand::Rng;
+use rusqlite::Connection;
+
+/* Besides the interactive menus, the program can be run with a command so that leagues can be managed from a script.
+Each command is the first argument, followed by options in the form of --option value, or flags in the form of --flag.
+If a command fails, the program exits with a status code based off the EditLeagueError that caused it.*/
+
+const USAGE: &str = "Usage: deadball [command] [options]
+Running the program without a command opens the main menu.
+
+Commands:
+    create-league --name NAME --era ancient|modern --gender male|female|coed [--seed SEED] [--teams FILE]
+        Creates a new league. FILE contains one team per line, written as ABRV,Team Name.
+    add-team --league NAME --name TEAM --abrv ABRV
+        Adds a new team to a league.
+    refresh --league NAME
+        Saves a league to it's folder.
+    pennant --league NAME --games GAMES
+        Generates a pennant race after GAMES games have been played.
+    archive --league NAME
+        Archives a copy of the league in the database as well as a json file.
+    leaders --league NAME (--batters [--pos catchers|infield|outfield] | --pitchers)
+        Displays the top 10 batters or pitchers in a league.
+    help
+        Displays this message.";
+
+/// The arguments passed to the program, split into a command, options that have a value, and flags that do not.
+pub struct CliArgs {
+    command: String,
+    options: HashMap<String, String>,
+    flags: Vec<String>,
+}
+
+impl CliArgs {
+    /// Parses the arguments passed to the program, not including the program name.
+    pub fn parse(args: &[String]) -> Result<CliArgs, EditLeagueError> {
+        let command = match args.first() {
+            Some(command) => command.to_string(),
+            None => return Err(EditLeagueError::CommandError(USAGE.to_string())),
+        };
+        let mut options = HashMap::new();
+        let mut flags = Vec::new();
+        let mut iter = args[1..].iter().peekable();
+        while let Some(arg) = iter.next() {
+            let key = match arg.strip_prefix("--") {
+                Some(key) => key.to_string(),
+                None => {
+                    return Err(EditLeagueError::CommandError(format!(
+                        "Unexpected argument {}.\n{}",
+                        arg, USAGE
+                    )))
+                }
+            };
+            // If the next argument is not an option, it is the value for this option.
+            match iter.next_if(|next| !next.starts_with("--")) {
+                Some(value) => {
+                    options.insert(key, value.to_string());
+                }
+                None => flags.push(key),
+            }
+        }
+        Ok(CliArgs {
+            command,
+            options,
+            flags,
+        })
+    }
+
+    /// Returns the value of an option, or an error if the option was not passed.
+    fn required(&self, key: &str) -> Result<&str, EditLeagueError> {
+        match self.options.get(key) {
+            Some(value) => Ok(value),
+            None => Err(EditLeagueError::CommandError(format!(
+                "The {} command requires --{}.",
+                self.command, key
+            ))),
+        }
+    }
+
+    fn has_flag(&self, key: &str) -> bool {
+        self.flags.iter().any(|flag| flag == key)
+    }
+
+    /// Returns the value of an option parsed into a number, or an error if the option is missing or not a number.
+    fn required_num<T: std::str::FromStr>(&self, key: &str) -> Result<T, EditLeagueError> {
+        let value = self.required(key)?;
+        value.parse().map_err(|_| {
+            EditLeagueError::CommandError(format!(
+                "--{} must be a whole number, not {}.",
+                key, value
+            ))
+        })
+    }
+}
+
+fn parse_era(input: &str) -> Result<Era, EditLeagueError> {
+    match input.to_lowercase().as_str() {
+        "ancient" => Ok(Era::Ancient),
+        "modern" => Ok(Era::Modern),
+        _ => Err(EditLeagueError::CommandError(format!(
+            "{} is not a valid era, please use ancient or modern.",
+            input
+        ))),
+    }
+}
+
+fn parse_gender(input: &str) -> Result<PlayerGender, EditLeagueError> {
+    match input.to_lowercase().as_str() {
+        "male" => Ok(PlayerGender::Male),
+        "female" => Ok(PlayerGender::Female),
+        "coed" => Ok(PlayerGender::Coed),
+        _ => Err(EditLeagueError::CommandError(format!(
+            "{} is not a valid gender, please use male, female or coed.",
+            input
+        ))),
+    }
+}
+
+fn parse_pos(input: &str) -> Result<BatterPosType, EditLeagueError> {
+    match input.to_lowercase().as_str() {
+        "catchers" | "catcher" => Ok(BatterPosType::Catchers),
+        "infield" | "infielders" => Ok(BatterPosType::Infielders),
+        "outfield" | "outfielders" => Ok(BatterPosType::Outfielders),
+        "all" => Ok(BatterPosType::All),
+        _ => Err(EditLeagueError::CommandError(format!(
+            "{} is not a valid position type, please use catchers, infield or outfield.",
+            input
+        ))),
+    }
+}
+
+/// Reads a file of teams, where each line is a team written as ABRV,Team Name. Blank lines are skipped.
+/// Returns a vector of tuples of the abbreviation and name of each team.
+fn read_teams_file(path: &str) -> Result<Vec<(String, String)>, EditLeagueError> {
+    let contents = fs::read_to_string(path).map_err(|message| {
+        EditLeagueError::CommandError(format!(
+            "Unable to read {}. The error was {}",
+            path, message
+        ))
+    })?;
+    let mut result = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match line.split_once(',') {
+            Some((abrv, name)) => result.push((abrv.trim().to_string(), name.trim().to_string())),
+            None => {
+                return Err(EditLeagueError::CommandError(format!(
+                    "Line {} of {} must be written as ABRV,Team Name.",
+                    index + 1,
+                    path
+                )))
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Finds a league in the database by name, and loads it's teams.
+fn load_league_by_name(conn: &mut Connection, name: &str) -> Result<League, EditLeagueError> {
+    let wrappers = match get_all_leagues_from_db(conn) {
+        Ok(wrappers) => wrappers,
+        Err(message) => return Err(EditLeagueError::DatabaseError(message)),
+    };
+    let wrapper = match wrappers
+        .into_iter()
+        .find(|wrapper| wrapper.league.name == name)
+    {
+        Some(wrapper) => wrapper,
+        None => {
+            return Err(EditLeagueError::CommandError(format!(
+                "There is no league named {}.",
+                name
+            )))
+        }
+    };
+    let mut league = wrapper.league;
+    load_teams_from_sql(wrapper.league_id, &mut league, conn)?;
+    Ok(league)
+}
+
+/// Adds a team to a league, using the league's random number generator for the new team.
+fn add_team(
+    league: &mut League,
+    conn: &mut Connection,
+    abrv: &String,
+    name: &String,
+) -> Result<(), EditLeagueError> {
+    let mut team_thread = league.team_rng();
+    let league_id = league.league_id;
+    league.new_team(abrv, name, &mut team_thread, league_id, conn)?;
+    println!("{} {} added to {}", abrv, name, league.name);
+    Ok(())
+}
+
+fn create_league<R: Rng>(
+    args: &CliArgs,
+    conn: &mut Connection,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    let name = args.required("name")?.to_string();
+    let era = parse_era(args.required("era")?)?;
+    let gender = parse_gender(args.required("gender")?)?;
+    let seed = match args.options.contains_key("seed") {
+        true => args.required_num("seed")?,
+        false => thread.gen(),
+    };
+    // We read the teams file before the league is created, so a bad file does not leave an empty league behind.
+    let teams = match args.options.get("teams") {
+        Some(path) => read_teams_file(path)?,
+        None => Vec::new(),
+    };
+    let taken_names = match check_name_vec(conn) {
+        Ok(names) => names,
+        Err(message) => return Err(EditLeagueError::DatabaseError(message)),
+    };
+    if taken_names.contains(&name) {
+        return Err(EditLeagueError::CommandError(format!(
+            "There is already a league named {}.",
+            name
+        )));
+    }
+    // The league and its teams are saved in a single transaction, so a failed team does not leave a partial league behind.
+    let league = in_transaction(conn, |conn| {
+        let mut league = insert_new_league(conn, &name, era, gender, seed)?;
+        println!("{} created with seed {}", name, seed);
+        for (abrv, team_name) in teams.iter() {
+            add_team(&mut league, conn, abrv, team_name)?;
+        }
+        Ok(league)
+    })?;
+    save_league(&league);
+    Ok(())
+}
+
+fn show_leaders(args: &CliArgs, conn: &mut Connection) -> Result<(), EditLeagueError> {
+    let league = load_league_by_name(conn, args.required("league")?)?;
+    let result = match (args.has_flag("batters"), args.has_flag("pitchers")) {
+        (true, false) => {
+            let filter_choice = match args.options.get("pos") {
+                Some(pos) => Some(parse_pos(pos)?),
+                None => None,
+            };
+            league.display_top_hitters(conn, filter_choice)
+        }
+        (false, true) => league.display_top_pitchers(conn),
+        _ => {
+            return Err(EditLeagueError::CommandError(
+                "The leaders command requires either --batters or --pitchers.".to_string(),
+            ))
+        }
+    };
+    match result {
+        Ok(()) => Ok(()),
+        Err(message) => Err(EditLeagueError::DatabaseError(message)),
+    }
+}
+
+/// Runs the command passed to the program.
+pub fn run_command<R: Rng>(
+    args: &CliArgs,
+    conn: &mut Connection,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    match args.command.as_str() {
+        "create-league" => create_league(args, conn, thread),
+        "add-team" => {
+            let mut league = load_league_by_name(conn, args.required("league")?)?;
+            let name = args.required("name")?.to_string();
+            let abrv = args.required("abrv")?.to_string();
+            add_team(&mut league, conn, &abrv, &name)?;
+            save_league(&league);
+            Ok(())
+        }
+        "refresh" => {
+            let league = load_league_by_name(conn, args.required("league")?)?;
+            save_league(&league);
+            Ok(())
+        }
+        "pennant" => {
+            let league = load_league_by_name(conn, args.required("league")?)?;
+            let games_played = args.required_num("games")?;
+            league.pennant_team_check()?;
+            league.create_pennant_race(thread, conn, games_played)
+        }
+        "archive" => {
+            let league = load_league_by_name(conn, args.required("league")?)?;
+            league.create_json_archives(conn)
+        }
+        "leaders" => show_leaders(args, conn),
+        "help" | "--help" | "-h" => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        _ => Err(EditLeagueError::CommandError(format!(
+            "Unknown command {}.\n{}",
+            args.command, USAGE
+        ))),
+    }
+}
+
+/// Parses and runs the arguments passed to the program. Returns the status code the program should exit with.
+pub fn run_cli<R: Rng>(args: &[String], conn: &mut Connection, thread: &mut R) -> i32 {
+    let result = match CliArgs::parse(args) {
+        Ok(parsed) => run_command(&parsed, conn, thread),
+        Err(message) => Err(message),
+    };
+    match result {
+        Ok(()) => 0,
+        Err(message) => {
+            eprintln!("{}", message);
+            message.exit_code()
+        }
+    }
+}
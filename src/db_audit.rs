@@ -0,0 +1,618 @@
+use core::fmt;
+use std::collections::HashMap;
+
+use crate::edit_league_error::handle_serde_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::era::Era;
+use crate::in_transaction;
+use crate::inquire_check;
+use crate::pd::PD;
+use crate::player::Hand;
+use crate::player::Player;
+use crate::position::PlayerPosition;
+use crate::position::PositionCategory;
+use crate::team::TeamSpot;
+use inquire::Confirm;
+use rusqlite::Connection;
+
+/* Players are only checked for errors when their team is loaded, and some problems, such as an obt that does not match bt + obt_mod, are never checked at all.
+The audit reads every row in the database directly, so it can report every problem at once without stopping at the first one.
+Problems with a pitch die, hand or position are fixed with the same prompts that are used when a team is loaded, while the rest are fixed in batches.*/
+
+/// The positions that must be filled in every starting lineup.
+pub const LINEUP_POSITIONS: [PlayerPosition; 8] = [
+    PlayerPosition::C,
+    PlayerPosition::FirstBase,
+    PlayerPosition::SecondBase,
+    PlayerPosition::ThirdBase,
+    PlayerPosition::SS,
+    PlayerPosition::LF,
+    PlayerPosition::CF,
+    PlayerPosition::RF,
+];
+
+/// A player found by the audit.
+#[derive(Debug)]
+pub struct AuditPlayer {
+    pub player_id: i64,
+    pub name: String,
+    pub team_name: String,
+}
+
+impl fmt::Display for AuditPlayer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} ({}), player ID {}",
+            self.name, self.team_name, self.player_id
+        )
+    }
+}
+
+/// A player with a pitch die, hand or position that can not be deserialized.
+/// The saved text of each column is kept, so the player can be fixed with the same prompts used when a team is loaded.
+#[derive(Debug)]
+pub struct InvalidJsonPlayer {
+    pub player: AuditPlayer,
+    era: Era,
+    pd: Option<String>,
+    hand: String,
+    pos: String,
+    problems: Vec<String>,
+}
+
+/// Every problem found in the database.
+#[derive(Debug, Default)]
+pub struct AuditReport {
+    /// The warnings from Player::get_player_error, E.G an age of 0 or an obt that does not equal bt + obt_mod.
+    pub player_errors: Vec<String>,
+    /// The ids of players whose obt does not equal bt + obt_mod.
+    pub obt_mismatches: Vec<i64>,
+    pub invalid_json: Vec<InvalidJsonPlayer>,
+    /// Players with a team spot that can not be deserialized. These can not be fixed by the audit.
+    pub invalid_team_spots: Vec<AuditPlayer>,
+    /// Relievers on Ancient Era teams, which do not have bullpens.
+    pub ancient_relievers: Vec<AuditPlayer>,
+    /// Players whose team, or whose team's league, does not exist.
+    pub orphan_players: Vec<AuditPlayer>,
+    /// A tuple of the id and name of each team whose league does not exist.
+    pub orphan_teams: Vec<(i64, String)>,
+    /// A tuple of the id and name of each team that does not have any players.
+    pub empty_teams: Vec<(i64, String)>,
+    /// A line for each team whose league has an era that can not be deserialized. The players on these teams are not checked.
+    pub invalid_eras: Vec<String>,
+    /// A line for each team that is missing a position in it's lineup, or has no starting pitchers.
+    pub missing_positions: Vec<String>,
+}
+
+impl AuditReport {
+    /// Returns how many problems were found.
+    pub fn problem_count(&self) -> usize {
+        // Obt mismatches are already counted in the player errors.
+        self.player_errors.len()
+            + self.invalid_json.len()
+            + self.invalid_team_spots.len()
+            + self.ancient_relievers.len()
+            + self.orphan_players.len()
+            + self.orphan_teams.len()
+            + self.empty_teams.len()
+            + self.invalid_eras.len()
+            + self.missing_positions.len()
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.problem_count() == 0
+    }
+}
+
+fn write_section<T: fmt::Display>(
+    f: &mut fmt::Formatter,
+    header: &str,
+    lines: &[T],
+) -> fmt::Result {
+    if lines.is_empty() {
+        return Ok(());
+    }
+    writeln!(f, "{} ({}):", header, lines.len())?;
+    for line in lines.iter() {
+        writeln!(f, "    {}", line)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for InvalidJsonPlayer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.player, self.problems.join(", "))
+    }
+}
+
+impl fmt::Display for AuditReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_clean() {
+            return write!(f, "No problems were found in the database.");
+        }
+        writeln!(
+            f,
+            "{} problems were found in the database.",
+            self.problem_count()
+        )?;
+        write_section(f, "Players with invalid values", &self.player_errors)?;
+        write_section(
+            f,
+            "Players with an invalid pitch die, hand or position",
+            &self.invalid_json,
+        )?;
+        write_section(
+            f,
+            "Players with an invalid team spot",
+            &self.invalid_team_spots,
+        )?;
+        write_section(f, "Relievers on Ancient Era teams", &self.ancient_relievers)?;
+        write_section(f, "Players without a team or league", &self.orphan_players)?;
+        let orphan_teams: Vec<String> = self
+            .orphan_teams
+            .iter()
+            .map(|(team_id, name)| format!("{}, team ID {}", name, team_id))
+            .collect();
+        write_section(f, "Teams without a league", &orphan_teams)?;
+        let empty_teams: Vec<String> = self
+            .empty_teams
+            .iter()
+            .map(|(team_id, name)| format!("{}, team ID {}", name, team_id))
+            .collect();
+        write_section(f, "Teams without players", &empty_teams)?;
+        write_section(
+            f,
+            "Teams in a league with an invalid era",
+            &self.invalid_eras,
+        )?;
+        write_section(f, "Teams missing positions", &self.missing_positions)
+    }
+}
+
+/// A team's name, and a tuple of the team spot and position of each of it's players.
+type TeamRoster = (String, Vec<(TeamSpot, PlayerPosition)>);
+
+/// The columns of a player that are checked by the audit.
+struct PlayerColumns {
+    player: Player,
+    pd: Option<String>,
+    pd_int: Option<i32>,
+    hand: String,
+    pos: String,
+    team_spot: String,
+    team_name: String,
+    era: String,
+}
+
+/// Deserializes the text saved in the pitch die column, where NULL is a player without a pitch die.
+fn parse_pd(input: &Option<String>) -> Result<Option<PD>, serde_json::Error> {
+    match input {
+        None => Ok(None),
+        Some(text) => serde_json::from_str(text),
+    }
+}
+
+fn get_player_columns(conn: &Connection) -> Result<Vec<PlayerColumns>, EditLeagueError> {
+    let mut stmt = handle_sql_error(conn.prepare(
+        "SELECT
+            players.player_id, players.player_name, players.age, players.bt, players.obt_mod, players.obt,
+            players.PD, pitch_die.die_int, players.hand, players.pos, players.team_spot, players.team_id,
+            teams.team_name, leagues.era
+        FROM players
+        JOIN teams ON teams.team_id = players.team_id
+        JOIN leagues ON leagues.league_id = teams.league_id
+        LEFT JOIN pitch_die ON pitch_die.die_text = players.PD
+        ORDER BY players.player_id",
+    ))?;
+    let rows = stmt.query_map([], |row| {
+        Ok(PlayerColumns {
+            player: Player {
+                player_id: row.get(0)?,
+                name: row.get(1)?,
+                age: row.get(2)?,
+                bt: row.get(3)?,
+                obt_mod: row.get(4)?,
+                obt: row.get(5)?,
+                team_id: row.get(11)?,
+                ..Player::default()
+            },
+            pd: row.get(6)?,
+            pd_int: row.get(7)?,
+            hand: row.get(8)?,
+            pos: row.get(9)?,
+            team_spot: row.get(10)?,
+            team_name: row.get(12)?,
+            era: row.get(13)?,
+        })
+    });
+    let mut result_vec = Vec::new();
+    for columns in handle_sql_error(rows)? {
+        result_vec.push(handle_sql_error(columns)?)
+    }
+    Ok(result_vec)
+}
+
+fn get_orphan_players(conn: &Connection) -> Result<Vec<AuditPlayer>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT players.player_id, players.player_name, players.team_id
+        FROM players
+        WHERE players.team_id NOT IN (
+            SELECT teams.team_id FROM teams JOIN leagues ON leagues.league_id = teams.league_id
+        )
+        ORDER BY players.player_id",
+    )?;
+    let rows = stmt.query_map([], |row| {
+        let team_id: i64 = row.get(2)?;
+        Ok(AuditPlayer {
+            player_id: row.get(0)?,
+            name: row.get(1)?,
+            team_name: format!("team ID {}", team_id),
+        })
+    })?;
+    rows.collect()
+}
+
+fn get_orphan_teams(conn: &Connection) -> Result<Vec<(i64, String)>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT teams.team_id, teams.team_name
+        FROM teams
+        WHERE teams.league_id NOT IN (SELECT leagues.league_id FROM leagues)
+        ORDER BY teams.team_id",
+    )?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// Returns the teams in a league that exist that do not have any players. Teams without a league are reported as orphans instead.
+fn get_empty_teams(conn: &Connection) -> Result<Vec<(i64, String)>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT teams.team_id, teams.team_name
+        FROM teams
+        JOIN leagues ON leagues.league_id = teams.league_id
+        WHERE teams.team_id NOT IN (SELECT players.team_id FROM players)
+        ORDER BY teams.team_id",
+    )?;
+    let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    rows.collect()
+}
+
+/// Checks the positions a team has in it's lineup and rotation, and returns a line describing what is missing.
+pub fn check_team_positions(
+    team_name: &str,
+    spots: &[(TeamSpot, PlayerPosition)],
+) -> Option<String> {
+    let lineup: Vec<&PlayerPosition> = spots
+        .iter()
+        .filter(|(spot, _)| matches!(spot, TeamSpot::StartingLineup))
+        .map(|(_, pos)| match pos {
+            PlayerPosition::TwoWay(info) => info.get_fielder_type(),
+            _ => pos,
+        })
+        .collect();
+    let mut missing: Vec<String> = LINEUP_POSITIONS
+        .iter()
+        .filter(|pos| !lineup.contains(pos))
+        .map(|pos| pos.to_string().trim_matches('"').to_string())
+        .collect();
+    let has_starter = spots.iter().any(|(spot, pos)| {
+        matches!(spot, TeamSpot::StartingPitcher) && pos.matches_cat(PositionCategory::Pitcher)
+    });
+    if !has_starter {
+        missing.push("a starting pitcher".to_string());
+    }
+    match missing.is_empty() {
+        true => None,
+        false => Some(format!("{} is missing {}", team_name, missing.join(", "))),
+    }
+}
+
+/// Reads every player and team in the database, and returns a report of every problem that is found.
+pub fn audit_database(conn: &Connection) -> Result<AuditReport, EditLeagueError> {
+    let mut report = AuditReport::default();
+    // Each team's spots and positions are collected to check the team's roster after every player has been read.
+    let mut team_spots: HashMap<i64, TeamRoster> = HashMap::new();
+    for columns in get_player_columns(conn)? {
+        let PlayerColumns {
+            mut player,
+            pd,
+            pd_int,
+            hand,
+            pos,
+            team_spot,
+            team_name,
+            era,
+        } = columns;
+        // Every check depends on the era of the player's league, so if it can not be read the player is skipped.
+        let era: Era = match serde_json::from_str(&era) {
+            Ok(value) => value,
+            Err(message) => {
+                let line = format!("{}: {}", team_name, message);
+                if !report.invalid_eras.contains(&line) {
+                    report.invalid_eras.push(line);
+                }
+                continue;
+            }
+        };
+        let audit_player = || AuditPlayer {
+            player_id: player.player_id,
+            name: player.name.to_string(),
+            team_name: team_name.to_string(),
+        };
+        let mut problems = Vec::new();
+        match parse_pd(&pd) {
+            Ok(value) => player.pd = value,
+            Err(message) => problems.push(format!("pitch die: {}", message)),
+        };
+        if let Err(message) = serde_json::from_str::<Hand>(&hand) {
+            problems.push(format!("hand: {}", message));
+        }
+        let parsed_pos = match serde_json::from_str::<PlayerPosition>(&pos) {
+            Ok(value) => Some(value),
+            Err(message) => {
+                problems.push(format!("position: {}", message));
+                None
+            }
+        };
+        let parsed_spot = serde_json::from_str::<TeamSpot>(&team_spot).ok();
+        // Pitch dice that are not in the pitch die table are reported as a pd_int of 0.
+        if let Some(player_error) = player.get_player_error(pd_int.unwrap_or_default()) {
+            report.player_errors.push(player_error.to_string());
+        }
+        if player.obt != player.bt + player.obt_mod {
+            report.obt_mismatches.push(player.player_id);
+        }
+        let is_reliever = matches!(parsed_spot, Some(TeamSpot::Bullpen))
+            || matches!(parsed_pos, Some(PlayerPosition::RP));
+        if matches!(era, Era::Ancient) && is_reliever {
+            report.ancient_relievers.push(audit_player());
+        }
+        let entry = team_spots
+            .entry(player.team_id)
+            .or_insert_with(|| (team_name.to_string(), Vec::new()));
+        match (parsed_spot, parsed_pos) {
+            (Some(spot), Some(pos)) => entry.1.push((spot, pos)),
+            (None, _) => report.invalid_team_spots.push(audit_player()),
+            _ => {}
+        }
+        if !problems.is_empty() {
+            report.invalid_json.push(InvalidJsonPlayer {
+                player: audit_player(),
+                era,
+                pd,
+                hand,
+                pos,
+                problems,
+            });
+        }
+    }
+    let mut teams: Vec<(i64, TeamRoster)> = team_spots.into_iter().collect();
+    teams.sort_by_key(|(team_id, _)| *team_id);
+    report.missing_positions = teams
+        .iter()
+        .filter_map(|(_, (team_name, spots))| check_team_positions(team_name, spots))
+        .collect();
+    report.orphan_players = handle_sql_error(get_orphan_players(conn))?;
+    report.orphan_teams = handle_sql_error(get_orphan_teams(conn))?;
+    report.empty_teams = handle_sql_error(get_empty_teams(conn))?;
+    Ok(report)
+}
+
+/// Sets the obt of each player to their bt + obt_mod. Returns how many players were updated.
+pub fn fix_obt_sums(conn: &mut Connection, player_ids: &[i64]) -> Result<usize, EditLeagueError> {
+    in_transaction(conn, |conn| {
+        let mut updated = 0;
+        for player_id in player_ids.iter() {
+            updated += handle_sql_error(conn.execute(
+                "UPDATE players SET obt = bt + obt_mod WHERE player_id = ?1",
+                [player_id],
+            ))?;
+        }
+        Ok(updated)
+    })
+}
+
+/// Moves relievers on Ancient Era teams into the starting rotation, and changes their position from RP to P.
+pub fn fix_ancient_relievers(
+    conn: &mut Connection,
+    players: &[AuditPlayer],
+) -> Result<usize, EditLeagueError> {
+    let rotation = handle_serde_error(serde_json::to_string(&TeamSpot::StartingPitcher))?;
+    let reliever = handle_serde_error(serde_json::to_string(&PlayerPosition::RP))?;
+    let pitcher = handle_serde_error(serde_json::to_string(&PlayerPosition::P))?;
+    in_transaction(conn, |conn| {
+        for player in players.iter() {
+            handle_sql_error(conn.execute(
+                "UPDATE players SET team_spot = ?1 WHERE player_id = ?2",
+                (&rotation, player.player_id),
+            ))?;
+            handle_sql_error(conn.execute(
+                "UPDATE players SET pos = ?1 WHERE player_id = ?2 AND pos = ?3",
+                (&pitcher, player.player_id, &reliever),
+            ))?;
+        }
+        Ok(players.len())
+    })
+}
+
+/// Deletes players without a team or league, and teams without a league.
+fn delete_orphans(conn: &mut Connection, report: &AuditReport) -> Result<(), EditLeagueError> {
+    in_transaction(conn, |conn| {
+        for player in report.orphan_players.iter() {
+            handle_sql_error(conn.execute(
+                "DELETE FROM players WHERE player_id = ?1",
+                [player.player_id],
+            ))?;
+        }
+        for (team_id, _) in report.orphan_teams.iter() {
+            // A team's farm system, retired players and draft picks are removed along with the team.
+            for table in ["prospects", "retired_players", "draft_picks"] {
+                handle_sql_error(conn.execute(
+                    &format!("DELETE FROM {} WHERE team_id = ?1", table),
+                    [team_id],
+                ))?;
+            }
+            handle_sql_error(conn.execute("DELETE FROM teams WHERE team_id = ?1", [team_id]))?;
+        }
+        Ok(())
+    })
+}
+
+/// Fixes a player's pitch die, hand and position with the same prompts used when a team is loaded.
+/// Returns false if the user chose not to fix the player.
+fn fix_invalid_json(
+    conn: &mut Connection,
+    invalid: &InvalidJsonPlayer,
+) -> Result<bool, EditLeagueError> {
+    let player_id = invalid.player.player_id;
+    let name = &invalid.player.name;
+    let pd = match PD::fix_db(parse_pd(&invalid.pd), conn, player_id, name, invalid.era) {
+        Ok(pd) => pd,
+        Err(_) => return Ok(false),
+    };
+    if Hand::fix_hand_db(&invalid.hand, pd, conn, name, player_id).is_err() {
+        return Ok(false);
+    }
+    if let Err(message) = serde_json::from_str::<PlayerPosition>(&invalid.pos) {
+        let pos = match PlayerPosition::fix_pos(conn, player_id, name, invalid.era, pd, &message) {
+            Some(pos) => pos,
+            None => return Ok(false),
+        };
+        // fix_pos does not save the position when only one position is possible, so it is saved here.
+        let pos_text = handle_serde_error(serde_json::to_string(&pos))?;
+        handle_sql_error(conn.execute(
+            "UPDATE players SET pos = ?1 WHERE player_id = ?2",
+            (pos_text, player_id),
+        ))?;
+    }
+    Ok(true)
+}
+
+/// Asks the user a yes or no question, where None means the user cancelled.
+fn confirm_fix(message: &str) -> Result<Option<bool>, EditLeagueError> {
+    match Confirm::new(message).with_default(true).prompt() {
+        Ok(value) => Ok(Some(value)),
+        Err(message) => inquire_check(message).map(|_| None),
+    }
+}
+
+/// Audits the database and prints the report, then offers to fix each type of problem that was found.
+pub fn audit_from_input(conn: &mut Connection) -> Result<(), EditLeagueError> {
+    let report = audit_database(conn)?;
+    println!("{}", report);
+    if report.is_clean() {
+        return Ok(());
+    }
+    if !report.invalid_json.is_empty() {
+        let message = format!(
+            "Would you like to fix the {} players with an invalid pitch die, hand or position?",
+            report.invalid_json.len()
+        );
+        match confirm_fix(&message)? {
+            None => return Ok(()),
+            Some(false) => {}
+            Some(true) => {
+                let mut fixed = 0;
+                for invalid in report.invalid_json.iter() {
+                    if fix_invalid_json(conn, invalid)? {
+                        fixed += 1;
+                    }
+                }
+                println!("{} players fixed.", fixed);
+            }
+        }
+    }
+    if !report.obt_mismatches.is_empty() {
+        let message = format!(
+            "Would you like to set the obt of the {} players whose obt does not equal bt + obt_mod?",
+            report.obt_mismatches.len()
+        );
+        match confirm_fix(&message)? {
+            None => return Ok(()),
+            Some(false) => {}
+            Some(true) => {
+                let updated = fix_obt_sums(conn, &report.obt_mismatches)?;
+                println!("{} players updated.", updated);
+            }
+        }
+    }
+    if !report.ancient_relievers.is_empty() {
+        let message = format!(
+            "Would you like to move the {} relievers on Ancient Era teams to the starting rotation?",
+            report.ancient_relievers.len()
+        );
+        match confirm_fix(&message)? {
+            None => return Ok(()),
+            Some(false) => {}
+            Some(true) => {
+                let moved = fix_ancient_relievers(conn, &report.ancient_relievers)?;
+                println!("{} players moved.", moved);
+            }
+        }
+    }
+    if !report.orphan_players.is_empty() || !report.orphan_teams.is_empty() {
+        let message = format!(
+            "Would you like to delete the {} players and {} teams without a league?",
+            report.orphan_players.len(),
+            report.orphan_teams.len()
+        );
+        match confirm_fix(&message)? {
+            None => return Ok(()),
+            Some(false) => {}
+            Some(true) => {
+                delete_orphans(conn, &report)?;
+                println!("Players and teams deleted.");
+            }
+        }
+    }
+    if !report.missing_positions.is_empty() || !report.invalid_team_spots.is_empty() {
+        println!("Missing positions and invalid team spots can not be fixed by the audit.");
+    }
+    let remaining = audit_database(conn)?.problem_count();
+    println!("{} problems remain in the database.", remaining);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::player::PlayerGender;
+    use crate::test_league;
+
+    #[test]
+    fn database_audit() {
+        let (mut conn, _league) = test_league(Era::Ancient, PlayerGender::Male, 5);
+        assert!(audit_database(&conn).unwrap().is_clean());
+        conn.execute_batch(
+            "UPDATE players SET obt = obt + 3 WHERE team_spot = '\"StartingLineup\"';
+            UPDATE players SET team_spot = '\"Bullpen\"', pos = '\"RP\"' WHERE player_id = (SELECT MAX(player_id) FROM players);
+            UPDATE players SET hand = 'L' WHERE player_id = 1;
+            PRAGMA foreign_keys = OFF;
+            INSERT INTO teams(league_id, team_name, abrv) VALUES(99, 'Lost', 'LST');
+            PRAGMA foreign_keys = ON;
+            INSERT INTO teams(league_id, team_name, abrv) VALUES(1, 'Empty', 'EMP');",
+        )
+        .unwrap();
+        let report = audit_database(&conn).unwrap();
+        assert_eq!(report.obt_mismatches.len(), 16);
+        assert_eq!(report.ancient_relievers.len(), 1);
+        assert_eq!(report.invalid_json.len(), 1);
+        assert_eq!(report.orphan_teams.len(), 1);
+        assert_eq!(report.empty_teams.len(), 1);
+        // The reliever was the team's last starting pitcher, so the rotation is not missing.
+        assert!(report.missing_positions.is_empty());
+        fix_obt_sums(&mut conn, &report.obt_mismatches).unwrap();
+        fix_ancient_relievers(&mut conn, &report.ancient_relievers).unwrap();
+        let report = audit_database(&conn).unwrap();
+        assert!(report.obt_mismatches.is_empty());
+        assert!(report.ancient_relievers.is_empty());
+        assert_eq!(report.problem_count(), 3);
+        // A league with an invalid era is reported once for each of it's teams, without stopping the audit.
+        conn.execute("UPDATE leagues SET era = 'Medieval'", [])
+            .unwrap();
+        let report = audit_database(&conn).unwrap();
+        assert_eq!(report.invalid_eras.len(), 2);
+        assert!(report.invalid_json.is_empty());
+    }
+}
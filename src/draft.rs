@@ -0,0 +1,507 @@
+use core::fmt;
+use std::cmp::Reverse;
+use std::fs;
+
+use crate::edit_league_error::handle_inquire_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::era::Era;
+use crate::in_transaction;
+use crate::inquire_check;
+use crate::league::save_league;
+use crate::league::League;
+use crate::minor_leaguer::push_to_roster;
+use crate::minor_leaguer::roster_spot;
+use crate::minor_leaguer::save_drafted_player;
+use crate::minor_leaguer::Prospect;
+use crate::player::AgeCat;
+use crate::player::Player;
+use crate::position::PlayerPosition;
+use crate::settings::output_path;
+use crate::team::Team;
+use crate::team::TeamSpot;
+use crate::trade::incoming_spot;
+use chrono::{Datelike, Local};
+use inquire::{CustomType, MultiSelect, Select};
+use rand::Rng;
+use rusqlite::Connection;
+
+/* Between seasons, a league can hold an amateur draft to bring new players into the league.
+The draft pool is a mix of batters and pitchers, generated with the same potential as the prospects in a team's farm system.
+Teams pick in reverse order of their record, either from the wins and losses saved for each team or from the league's latest pennant race, and the same order is used in every round.
+Each pick either joins the team's roster, or is sent to the farm system, where the player is kept as they were drafted so they are the same player when they are called up.
+Every draft is saved in the drafts table, and each pick in the draft_picks table.*/
+
+/// The positions a drafted batter can play.
+const DRAFT_POSITIONS: [PlayerPosition; 8] = [
+    PlayerPosition::C,
+    PlayerPosition::FirstBase,
+    PlayerPosition::SecondBase,
+    PlayerPosition::ThirdBase,
+    PlayerPosition::SS,
+    PlayerPosition::LF,
+    PlayerPosition::CF,
+    PlayerPosition::RF,
+];
+
+/// How the order of a draft is decided.
+#[derive(Clone, Copy, Debug)]
+pub enum DraftOrder {
+    Record,
+    Pennant,
+}
+
+impl fmt::Display for DraftOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Self::Record => "Reverse order of each team's record",
+            Self::Pennant => "Reverse order of the latest pennant race",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+impl DraftOrder {
+    fn to_db_string(self) -> &'static str {
+        match self {
+            Self::Record => "Record",
+            Self::Pennant => "Pennant",
+        }
+    }
+}
+
+/// Where a drafted player goes after they are picked.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PickDestination {
+    Roster,
+    Farm,
+}
+
+impl fmt::Display for PickDestination {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Self::Roster => "Roster",
+            Self::Farm => "Farm System",
+        };
+        write!(f, "{}", text)
+    }
+}
+
+/// A player available in the draft. The prospect has the same name, hand and potential as the player, and is saved instead of the player if they are sent to the farm system.
+pub struct Draftee {
+    pub player: Player,
+    prospect: Prospect,
+}
+
+impl fmt::Display for Draftee {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let player = &self.player;
+        write!(
+            f,
+            "Tier {}: {} {} Age {}",
+            player.get_tier(),
+            player.name,
+            player.pos,
+            player.age
+        )?;
+        match player.pd {
+            Some(pd) => write!(f, " Throws {} PD {}", player.hand, pd),
+            None => {
+                write!(
+                    f,
+                    " Bats {} BT {} OBT {}",
+                    player.hand, player.bt, player.obt
+                )?;
+                match player.b_traits.to_string().as_str() {
+                    "" => Ok(()),
+                    traits => write!(f, " {}", traits),
+                }
+            }
+        }
+    }
+}
+
+/// A pick made during a draft.
+pub struct DraftPick {
+    pub round: usize,
+    pub pick_number: usize,
+    pub team_name: String,
+    pub draftee: String,
+    pub destination: PickDestination,
+}
+
+impl fmt::Display for DraftPick {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "Round {}, Pick {}: {} select {} ({})",
+            self.round, self.pick_number, self.team_name, self.draftee, self.destination
+        )
+    }
+}
+
+fn win_pct(wins: i32, losses: i32) -> f64 {
+    match wins + losses {
+        0 => 0.5,
+        games => wins as f64 / games as f64,
+    }
+}
+
+/// Returns the index of each team in the league in the order they pick, starting with the team with the worst record.
+/// Ties are broken by team score, with the weaker team picking first.
+pub fn draft_order(
+    conn: &Connection,
+    league: &League,
+    order: DraftOrder,
+) -> Result<Vec<usize>, EditLeagueError> {
+    let records: Vec<(i32, i32)> = match order {
+        DraftOrder::Record => league
+            .teams
+            .iter()
+            .map(|team| (team.wins, team.losses))
+            .collect(),
+        DraftOrder::Pennant => {
+            let mut stmt = handle_sql_error(conn.prepare(
+                "SELECT team_id, wins, losses FROM pennants_standings WHERE pennant_id = (SELECT MAX(pennant_id) FROM pennants WHERE league_id = ?1)",
+            ))?;
+            let rows = handle_sql_error(stmt.query_map([league.league_id], |row| {
+                Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?))
+            }))?;
+            let mut standings = Vec::new();
+            for row in rows {
+                standings.push(handle_sql_error(row)?);
+            }
+            if standings.is_empty() {
+                return Err(EditLeagueError::PennantError(format!(
+                    "Error: {} does not have a pennant race to base the draft order off of.",
+                    league.name
+                )));
+            }
+            // Teams added after the pennant race are treated as having a .500 record.
+            league
+                .teams
+                .iter()
+                .map(|team| {
+                    standings
+                        .iter()
+                        .find(|(team_id, _, _)| *team_id == team.team_id)
+                        .map_or((0, 0), |(_, wins, losses)| (*wins, *losses))
+                })
+                .collect()
+        }
+    };
+    let mut indexes: Vec<usize> = (0..league.teams.len()).collect();
+    indexes.sort_by(|a, b| {
+        let (a_wins, a_losses) = records[*a];
+        let (b_wins, b_losses) = records[*b];
+        win_pct(a_wins, a_losses)
+            .total_cmp(&win_pct(b_wins, b_losses))
+            .then(
+                league.teams[*a]
+                    .team_score
+                    .cmp(&league.teams[*b].team_score),
+            )
+    });
+    Ok(indexes)
+}
+
+/// Generates a pool of players for a draft, sorted from the best tier to the worst.
+pub fn new_draft_pool<R: Rng>(league: &League, size: usize, thread: &mut R) -> Vec<Draftee> {
+    let mut pool: Vec<Draftee> = (0..size)
+        .map(|_| {
+            let for_pitcher = thread.gen_bool(0.5);
+            let prospect = Prospect::new(for_pitcher, league.gender, league.era, thread);
+            let mut player = prospect.unveil(thread);
+            player.pos = match (for_pitcher, league.era) {
+                (true, Era::Modern) => PlayerPosition::RP,
+                (true, Era::Ancient) => PlayerPosition::P,
+                (false, _) => DRAFT_POSITIONS[thread.gen_range(0..DRAFT_POSITIONS.len())].clone(),
+            };
+            player.age = AgeCat::Prospect.new_age(thread);
+            Draftee { player, prospect }
+        })
+        .collect();
+    pool.sort_by_key(|draftee| Reverse(draftee.player.get_tier()));
+    pool
+}
+
+/// Picks the best available player and sends them to the farm system. Used for teams that do not pick by hand.
+/// The pool is sorted from the best tier to the worst, so the best available player is always first.
+pub fn auto_pick(pool: &[Draftee]) -> Result<(usize, PickDestination), EditLeagueError> {
+    match pool.is_empty() {
+        true => Err(EditLeagueError::CommandError(
+            "There are no players left in the draft pool.".to_string(),
+        )),
+        false => Ok((0, PickDestination::Farm)),
+    }
+}
+
+/// Holds a draft for a league. For each pick, choose is passed the team that is picking as well as the players remaining in the pool, and returns the index of the player picked and where they go.
+/// Every pick is saved in a single transaction, so if a pick fails or is canceled, the whole draft is undone.
+pub fn run_draft<R: Rng>(
+    conn: &mut Connection,
+    league: &mut League,
+    order: DraftOrder,
+    rounds: usize,
+    mut choose: impl FnMut(&Team, &[Draftee]) -> Result<(usize, PickDestination), EditLeagueError>,
+    thread: &mut R,
+) -> Result<Vec<DraftPick>, EditLeagueError> {
+    let pick_order = draft_order(conn, league, order)?;
+    // The pool has twice as many players as picks, so the last team to pick still has a choice.
+    let mut pool = new_draft_pool(league, pick_order.len() * rounds * 2, thread);
+    let era = league.era;
+    let league_id = league.league_id;
+    in_transaction(conn, |conn| {
+        let now = Local::now();
+        let date_held = format!("{}_{}_{}", now.year(), now.month(), now.day());
+        handle_sql_error(conn.execute(
+            "INSERT INTO drafts(league_id, date_held, draft_order) VALUES(?1, ?2, ?3)",
+            (league_id, date_held, order.to_db_string()),
+        ))?;
+        let draft_id = conn.last_insert_rowid();
+        let mut picks = Vec::new();
+        for round in 1..=rounds {
+            for team_index in pick_order.iter() {
+                let team = &mut league.teams[*team_index];
+                let (choice, destination) = choose(team, &pool)?;
+                let draftee = pool[choice].to_string();
+                let Draftee {
+                    mut player,
+                    mut prospect,
+                } = pool.remove(choice);
+                let (player_id, prospect_id) = match destination {
+                    PickDestination::Roster => {
+                        // Batters keep the position they were drafted at, and start if the lineup is missing it.
+                        let team_spot = match player.is_pitcher() {
+                            true => roster_spot(true, era, "").0,
+                            false => incoming_spot(team, &player, TeamSpot::BenchHitter, era),
+                        };
+                        player.save_sql(conn, team.team_id, team_spot)?;
+                        let player_id = player.player_id;
+                        push_to_roster(team, player.clone(), team_spot);
+                        (Some(player_id), None)
+                    }
+                    PickDestination::Farm => {
+                        prospect.save_sql(conn, team.team_id)?;
+                        save_drafted_player(conn, prospect.prospect_id(), &player)?;
+                        (None, Some(prospect.prospect_id()))
+                    }
+                };
+                let pick_number = picks.len() + 1;
+                let mut stmt = handle_sql_error(conn.prepare_cached(
+                    "INSERT INTO draft_picks(draft_id, round, pick_number, team_id, player_name, pos, tier, destination, player_id, prospect_id)
+                    VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                ))?;
+                handle_sql_error(stmt.execute((
+                    draft_id,
+                    round,
+                    pick_number,
+                    team.team_id,
+                    &player.name,
+                    player.pos.to_string(),
+                    player.get_tier().to_string(),
+                    destination.to_string(),
+                    player_id,
+                    prospect_id,
+                )))?;
+                picks.push(DraftPick {
+                    round,
+                    pick_number,
+                    team_name: team.name.clone(),
+                    draftee,
+                    destination,
+                });
+            }
+        }
+        Ok(picks)
+    })
+}
+
+/// Prints the picks from a draft, saves them to a file, and refreshes the league's folder.
+pub fn save_draft_results(league: &League, picks: &[DraftPick]) {
+    let text = picks
+        .iter()
+        .map(|pick| pick.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    println!("{}", text);
+    let now = Local::now();
+    let file_name = output_path(&format!(
+        "{}_Draft_{}_{}_{}.txt",
+        league.name,
+        now.year(),
+        now.month(),
+        now.day()
+    ));
+    match fs::write(&file_name, text) {
+        Ok(()) => println!("Draft results saved to {}", file_name.display()),
+        Err(message) => println!(
+            "Unable to save the draft results to a file.\nThe error was {}",
+            message
+        ),
+    }
+    save_league(league);
+}
+
+/// Prompts the team that is picking to choose a player from the pool, and where the player goes.
+fn pick_from_input(
+    team: &Team,
+    pool: &[Draftee],
+) -> Result<(usize, PickDestination), EditLeagueError> {
+    let options: Vec<String> = pool.iter().map(|draftee| draftee.to_string()).collect();
+    let message = format!(
+        "{} are on the clock. Who would you like to draft?",
+        team.name
+    );
+    let choice = handle_inquire_error(
+        Select::new(&message, options)
+            .with_page_size(10)
+            .raw_prompt(),
+    )?;
+    let destinations = vec![PickDestination::Farm, PickDestination::Roster];
+    let destination =
+        handle_inquire_error(Select::new("Where will the player go?", destinations).prompt())?;
+    Ok((choice.index, destination))
+}
+
+/// Prompts the user for how the draft order is decided, the number of rounds and which teams pick by hand, then holds the draft.
+/// Teams that do not pick by hand take the best available player and send them to their farm system.
+pub fn draft_from_input<R: Rng>(
+    league: &mut League,
+    conn: &mut Connection,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    let orders = vec![DraftOrder::Record, DraftOrder::Pennant];
+    let order = match Select::new("How should the draft order be decided?", orders).prompt() {
+        Ok(order) => order,
+        Err(message) => return inquire_check(message),
+    };
+    let rounds = match CustomType::<usize>::new("How many rounds will the draft have?")
+        .with_default(2)
+        .with_error_message("Please enter a valid whole number.")
+        .prompt()
+    {
+        Ok(rounds) => rounds,
+        Err(message) => return inquire_check(message),
+    };
+    let team_names: Vec<String> = league.teams.iter().map(|team| team.name.clone()).collect();
+    let hand_picked = match MultiSelect::new(
+        "Which teams will pick by hand? The rest pick automatically.",
+        team_names,
+    )
+    .prompt()
+    {
+        Ok(names) => names,
+        Err(message) => return inquire_check(message),
+    };
+    let choose = |team: &Team, pool: &[Draftee]| match hand_picked.contains(&team.name) {
+        true => pick_from_input(team, pool),
+        false => auto_pick(pool),
+    };
+    match run_draft(conn, league, order, rounds, choose, thread) {
+        Ok(picks) => {
+            save_draft_results(league, &picks);
+            Ok(())
+        }
+        Err(EditLeagueError::Inquire(message)) => inquire_check(message),
+        Err(message) => Err(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::league::load_teams_from_sql;
+    use crate::minor_leaguer::drafted_player;
+    use crate::player::PlayerGender;
+    use crate::test_league;
+
+    #[test]
+    fn amateur_draft() {
+        let (mut conn, mut league) = test_league(Era::Ancient, PlayerGender::Male, 8);
+        for (abrv, name) in [("SD", "San Diego Padres"), ("OAK", "Oakland Oaks")] {
+            let mut thread = league.team_rng();
+            let league_id = league.league_id;
+            league
+                .new_team(
+                    &abrv.to_string(),
+                    &name.to_string(),
+                    &mut thread,
+                    league_id,
+                    &mut conn,
+                )
+                .unwrap();
+        }
+        let mut thread = league.team_rng();
+        // The last team added has the worst record, so it picks first.
+        for (index, team) in league.teams.iter_mut().enumerate() {
+            team.wins = 30 - index as i32 * 5;
+            team.losses = index as i32 * 5;
+        }
+        let order = draft_order(&conn, &league, DraftOrder::Record).unwrap();
+        assert_eq!(order, vec![3, 2, 1, 0]);
+        // A league without a pennant race can not use it for the draft order.
+        assert!(draft_order(&conn, &league, DraftOrder::Pennant).is_err());
+        let pool = new_draft_pool(&league, 20, &mut thread);
+        assert!(pool
+            .windows(2)
+            .all(|x| x[0].player.get_tier() >= x[1].player.get_tier()));
+        // The first pick joins the roster, while the rest are sent to the farm system.
+        let choose = |team: &Team, pool: &[Draftee]| match team.abrv == "OAK" {
+            true => Ok((0, PickDestination::Roster)),
+            false => auto_pick(pool),
+        };
+        let rotation_len = league.teams[3].starting_pitching.len();
+        let bench_len = league.teams[3].bench.len();
+        let picks = run_draft(
+            &mut conn,
+            &mut league,
+            DraftOrder::Record,
+            2,
+            choose,
+            &mut thread,
+        )
+        .unwrap();
+        assert_eq!(picks.len(), 8);
+        assert_eq!(picks[0].team_name, "Oakland Oaks");
+        assert_eq!(picks[4].team_name, "Oakland Oaks");
+        assert_eq!(picks[1].destination, PickDestination::Farm);
+        let count = |conn: &Connection, sql: &str| -> i64 {
+            conn.query_row(sql, [], |row| row.get(0)).unwrap()
+        };
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM drafts"), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM draft_picks"), 8);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM prospects"), 4 * 6 + 6);
+        // Players sent to the farm system are kept as they were drafted.
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM prospects WHERE player_data IS NOT NULL"
+            ),
+            6
+        );
+        let (prospect_id, name): (i64, String) = conn
+            .query_row(
+                "SELECT prospect_id, player_name FROM draft_picks WHERE prospect_id IS NOT NULL",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        let drafted = drafted_player(&conn, prospect_id).unwrap().unwrap();
+        assert_eq!(drafted.name, name);
+        let mut loaded = League::new(
+            &league.name,
+            league.gender,
+            league.era,
+            league.league_id,
+            None,
+        );
+        load_teams_from_sql(league.league_id, &mut loaded, &mut conn).unwrap();
+        let oaks = &loaded.teams[3];
+        assert_eq!(
+            oaks.starting_pitching.len() + oaks.bench.len(),
+            rotation_len + bench_len + 2
+        );
+        assert_eq!(oaks.team_score, league.teams[3].team_score);
+    }
+}
@@ -14,6 +14,41 @@ pub enum EditLeagueError {
     ///Error using Inquire
     Inquire(InquireError),
     PennantError(String),
+    /// Error when a game can not be played, E.G a team is missing players.
+    GameError(String),
+    /// Error when a command line argument is missing or invalid.
+    CommandError(String),
+    /// Error when a league file can not be imported, E.G a player has invalid values.
+    ImportError(String),
+    /// Error when the database can not be upgraded, or was created by a newer version of the program.
+    SchemaError(String),
+    /// Error when a roster change would leave a team without the players required by it's era.
+    RosterError(String),
+    /// Error when a player is edited to have a value that is not valid for it's league, E.G a pitch die from another era.
+    InvalidPlayer(String),
+    /// Error when a file, such as a pennant or archive, can not be written.
+    FileError(String),
+    /// Error when a note is written on a team, player or game that is not part of the league.
+    NoteError(String),
+}
+
+impl EditLeagueError {
+    /// Returns the status code the program exits with when a command line command fails.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::CommandError(_) => 2,
+            Self::AbrvTaken | Self::NameTaken => 3,
+            Self::DatabaseError(_) | Self::SchemaError(_) => 4,
+            Self::SerdeError(_)
+            | Self::ImportError(_)
+            | Self::InvalidPlayer(_)
+            | Self::NoteError(_) => 5,
+            Self::FileError(_) => 9,
+            Self::Inquire(_) => 6,
+            Self::PennantError(_) | Self::GameError(_) => 7,
+            Self::RosterError(_) => 8,
+        }
+    }
 }
 
 /// Takes a result that could produce a rusqlite error. If it is an error, it is converted into a EditLeagueError.
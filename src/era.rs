@@ -1,7 +1,6 @@
 use crate::Deserialize;
 use crate::PitcherQuality;
 use crate::Serialize;
-use crate::ThreadRng;
 use crate::PD;
 use core::fmt;
 use inquire::InquireError;
@@ -16,7 +15,7 @@ pub enum Era {
     Modern,
 }
 impl Era {
-    pub fn new_pd(&self, thread: &mut ThreadRng, quality: &PitcherQuality) -> PD {
+    pub fn new_pd<R: Rng>(&self, thread: &mut R, quality: &PitcherQuality) -> PD {
         match self {
             // To simulate the low scoring offense of 1900/s baseball, Ancient Era pitchers have a significantly higher ceiling, as their base pitch die can be as high as a D20
             Self::Ancient => {
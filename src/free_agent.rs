@@ -0,0 +1,566 @@
+use core::fmt;
+
+use crate::db_audit::LINEUP_POSITIONS;
+use crate::edit_league_error::handle_inquire_error;
+use crate::edit_league_error::handle_serde_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::era::Era;
+use crate::in_transaction;
+use crate::injury::delete_player_injuries;
+use crate::inquire_check;
+use crate::league::save_league;
+use crate::league::League;
+use crate::minor_leaguer::push_to_roster;
+use crate::player::Player;
+use crate::player_quality::BatterQuality;
+use crate::player_quality::PitcherQuality;
+use crate::position::PlayerPosition;
+use crate::team::Team;
+use crate::team::TeamSpot;
+use crate::trade::fielding_pos;
+use crate::trade::incoming_spot;
+use crate::trade::roster_problems;
+use crate::trade::roster_sizes;
+use crate::trade::roster_with_spots;
+use crate::trade::take_player;
+use crate::Deserialize;
+use crate::PlayerGender;
+use crate::Serialize;
+use chrono::{Datelike, Local};
+use inquire::{CustomType, Select};
+use rand::Rng;
+use rusqlite::Connection;
+
+/* Each league has a pool of free agents, made up of players released by a team and players generated to fill holes in a team's roster.
+Free agents are saved in the free_agents table instead of the players table, as they do not belong to a team.
+Free agents can be signed by hand, or teams can sign them automatically. When teams sign automatically, free agents are signed in order of their trade value, and each one signs with the team that needs them most.
+A team needs a player if their lineup is missing a position, or if they have fewer bench players, starting pitchers or relievers than their era requires.
+If a team still has holes in their roster once the pool has been signed, a Farmhand is generated to fill each hole.*/
+
+/// A hole in a team's roster that a free agent can fill.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RosterNeed {
+    Starter(PlayerPosition),
+    Bench,
+    Rotation,
+    Bullpen,
+}
+
+impl RosterNeed {
+    /// Returns true if a player can fill the need.
+    fn is_filled_by(&self, player: &Player) -> bool {
+        match self {
+            Self::Starter(pos) => !player.is_pitcher() && fielding_pos(player) == pos,
+            Self::Bench => !player.is_pitcher(),
+            Self::Rotation | Self::Bullpen => player.is_pitcher(),
+        }
+    }
+
+    /// Generates a Farmhand that fills the need.
+    fn new_filler<R: Rng>(
+        &self,
+        gender: PlayerGender,
+        era: Era,
+        thread: &mut R,
+    ) -> Result<Player, EditLeagueError> {
+        let player = match self {
+            Self::Starter(pos) => {
+                let pos_value = handle_serde_error(serde_json::to_value(pos))?;
+                Player::new(pos_value, gender, BatterQuality::Farmhand, thread, era)
+            }
+            Self::Bench => Player::new("UT".into(), gender, BatterQuality::Farmhand, thread, era),
+            Self::Rotation => {
+                let pos = match era {
+                    Era::Ancient => "P",
+                    Era::Modern => "SP",
+                };
+                Player::new(pos.into(), gender, PitcherQuality::Farmhand, thread, era)
+            }
+            Self::Bullpen => {
+                Player::new("RP".into(), gender, PitcherQuality::Farmhand, thread, era)
+            }
+        };
+        Ok(player)
+    }
+}
+
+impl fmt::Display for RosterNeed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Starter(pos) => write!(f, "a starting {}", pos),
+            Self::Bench => write!(f, "a bench player"),
+            Self::Rotation => write!(f, "a starting pitcher"),
+            Self::Bullpen => write!(f, "a reliever"),
+        }
+    }
+}
+
+/// Returns every hole in a team's roster, with missing lineup positions first and relievers last.
+pub fn roster_needs(team: &Team, era: Era) -> Vec<RosterNeed> {
+    let mut needs: Vec<RosterNeed> = LINEUP_POSITIONS
+        .iter()
+        .filter(|pos| !team.lineup.iter().any(|x| fielding_pos(x) == *pos))
+        .map(|pos| RosterNeed::Starter(pos.clone()))
+        .collect();
+    let (bench, rotation, bullpen) = roster_sizes(era);
+    let bullpen_len = team.bullpen.as_ref().map_or(0, |pen| pen.len());
+    let shortages = [
+        (RosterNeed::Bench, bench.saturating_sub(team.bench.len())),
+        (
+            RosterNeed::Rotation,
+            rotation.saturating_sub(team.starting_pitching.len()),
+        ),
+        (RosterNeed::Bullpen, bullpen.saturating_sub(bullpen_len)),
+    ];
+    for (need, missing) in shortages {
+        needs.extend(std::iter::repeat_n(need, missing));
+    }
+    needs
+}
+
+/// A player in a league's free agent pool.
+#[derive(Serialize, Deserialize)]
+pub struct FreeAgent {
+    pub free_agent_id: i64,
+    pub former_team_id: Option<i64>,
+    pub player: Player,
+}
+
+impl fmt::Display for FreeAgent {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let player = &self.player;
+        write!(
+            f,
+            "{} {}, Age {}, Tier {}, Trade Value {}",
+            player.name,
+            player.pos,
+            player.age,
+            player.get_tier(),
+            player.trade_value
+        )
+    }
+}
+
+/// Saves a player to a league's free agent pool, and returns their free agent id.
+pub fn save_free_agent(
+    conn: &Connection,
+    league_id: i64,
+    former_team_id: Option<i64>,
+    player: &Player,
+) -> Result<i64, EditLeagueError> {
+    let now = Local::now();
+    let date_added = format!("{}_{}_{}", now.year(), now.month(), now.day());
+    let player_data = handle_serde_error(serde_json::to_string(player))?;
+    let mut stmt = handle_sql_error(conn.prepare_cached(
+        "INSERT INTO free_agents(league_id, former_team_id, player_name, pos, tier, trade_value, date_added, player_data)
+        VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+    ))?;
+    handle_sql_error(stmt.insert((
+        league_id,
+        former_team_id,
+        &player.name,
+        player.pos.to_string(),
+        player.get_tier().to_string(),
+        player.trade_value,
+        date_added,
+        player_data,
+    )))
+}
+
+/// Loads a league's free agent pool, sorted by trade value from highest to lowest.
+pub fn load_free_agents(
+    conn: &Connection,
+    league_id: i64,
+) -> Result<Vec<FreeAgent>, EditLeagueError> {
+    let mut stmt = handle_sql_error(conn.prepare_cached(
+        "SELECT free_agent_id, former_team_id, player_data FROM free_agents WHERE league_id = ?1 ORDER BY trade_value DESC, free_agent_id",
+    ))?;
+    let rows = handle_sql_error(stmt.query_map([league_id], |row| {
+        Ok((row.get(0)?, row.get(1)?, row.get::<_, String>(2)?))
+    }))?;
+    let mut pool = Vec::new();
+    for row in rows {
+        let (free_agent_id, former_team_id, player_data) = handle_sql_error(row)?;
+        pool.push(FreeAgent {
+            free_agent_id,
+            former_team_id,
+            player: handle_serde_error(serde_json::from_str(&player_data))?,
+        });
+    }
+    Ok(pool)
+}
+
+/// Generates Farmhand free agents and adds them to a league's free agent pool. Half are batters, and half are pitchers.
+pub fn generate_free_agents<R: Rng>(
+    conn: &mut Connection,
+    league: &League,
+    count: usize,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    in_transaction(conn, |conn| {
+        for index in 0..count {
+            let need = match (index % 2, league.era) {
+                (0, _) => {
+                    let pos = &LINEUP_POSITIONS[thread.gen_range(0..LINEUP_POSITIONS.len())];
+                    RosterNeed::Starter(pos.clone())
+                }
+                (_, Era::Modern) if thread.gen_bool(0.5) => RosterNeed::Bullpen,
+                _ => RosterNeed::Rotation,
+            };
+            let player = need.new_filler(league.gender, league.era, thread)?;
+            save_free_agent(conn, league.league_id, None, &player)?;
+        }
+        Ok(())
+    })
+}
+
+/// Releases a player from a team into the league's free agent pool, and returns the player.
+pub fn release_player(
+    conn: &mut Connection,
+    league: &mut League,
+    team_index: usize,
+    player_id: i64,
+) -> Result<Player, EditLeagueError> {
+    let mut team = league.teams[team_index].clone();
+    let (player, _) = match take_player(&mut team, player_id) {
+        Some(taken) => taken,
+        None => {
+            return Err(EditLeagueError::RosterError(format!(
+                "There is no player with the id {} on {}.",
+                player_id, team.name
+            )))
+        }
+    };
+    in_transaction(conn, |conn| {
+        let deleted = handle_sql_error(conn.execute(
+            "DELETE FROM players WHERE player_id = ?1 AND team_id = ?2",
+            (player.player_id, team.team_id),
+        ))?;
+        if deleted == 0 {
+            return Err(EditLeagueError::RosterError(format!(
+                "{} is not on {} in the database.",
+                player.name, team.name
+            )));
+        }
+        delete_player_injuries(conn, player.player_id)?;
+        save_free_agent(conn, league.league_id, Some(team.team_id), &player)
+    })?;
+    team.injured_list
+        .retain(|x| x.player_id != player.player_id);
+    team.calc_team_score();
+    league.teams[team_index] = team;
+    Ok(player)
+}
+
+/// Signs a free agent to a team, and returns the player along with the team spot they join.
+/// Batters start if the team's lineup is missing their position, and pitchers join the rotation if it is short a starter.
+pub fn sign_free_agent(
+    conn: &mut Connection,
+    league: &mut League,
+    team_index: usize,
+    free_agent: &FreeAgent,
+) -> Result<(Player, TeamSpot), EditLeagueError> {
+    let (league_id, era) = (league.league_id, league.era);
+    let team = &mut league.teams[team_index];
+    let mut player = free_agent.player.clone();
+    let old_spot = match player.is_pitcher() {
+        true => TeamSpot::StartingPitcher,
+        false => TeamSpot::StartingLineup,
+    };
+    let team_spot = incoming_spot(team, &player, old_spot, era);
+    in_transaction(conn, |conn| {
+        let deleted = handle_sql_error(conn.execute(
+            "DELETE FROM free_agents WHERE free_agent_id = ?1 AND league_id = ?2",
+            (free_agent.free_agent_id, league_id),
+        ))?;
+        if deleted == 0 {
+            return Err(EditLeagueError::RosterError(format!(
+                "{} is not a free agent in this league.",
+                player.name
+            )));
+        }
+        player.save_sql(conn, team.team_id, team_spot)
+    })?;
+    push_to_roster(team, player.clone(), team_spot);
+    Ok((player, team_spot))
+}
+
+/// Returns the index of the team that wins the bidding for a free agent, or None if no team needs them.
+/// Every team with a need the free agent fills makes a bid. The team with the most needs wins, and ties go to the team with the lowest team score.
+fn winning_bid(league: &League, player: &Player) -> Option<usize> {
+    league
+        .teams
+        .iter()
+        .enumerate()
+        .filter_map(|(index, team)| {
+            let needs = roster_needs(team, league.era);
+            match needs.iter().any(|need| need.is_filled_by(player)) {
+                true => Some((index, needs.len(), team.team_score)),
+                false => None,
+            }
+        })
+        .max_by_key(|(_, needs, team_score)| (*needs, -team_score))
+        .map(|(index, _, _)| index)
+}
+
+/// Signs free agents to every team with a hole in their roster. Free agents are signed in order of their trade value, with each signing with the team that bids the most for them.
+/// Once no team needs a player in the pool, Farmhands are generated to fill any remaining holes. Every signing is saved in a single transaction.
+/// Returns a description of each signing.
+pub fn run_signings<R: Rng>(
+    conn: &mut Connection,
+    league: &mut League,
+    thread: &mut R,
+) -> Result<Vec<String>, EditLeagueError> {
+    in_transaction(conn, |conn| {
+        let mut signings = Vec::new();
+        for free_agent in load_free_agents(conn, league.league_id)? {
+            if let Some(team_index) = winning_bid(league, &free_agent.player) {
+                let (player, team_spot) = sign_free_agent(conn, league, team_index, &free_agent)?;
+                signings.push(format!(
+                    "{} signed {} ({}, Trade Value {}) to {}",
+                    league.teams[team_index].name,
+                    player.name,
+                    player.pos,
+                    player.trade_value,
+                    team_spot
+                ));
+            }
+        }
+        for team_index in 0..league.teams.len() {
+            for need in roster_needs(&league.teams[team_index], league.era) {
+                let player = need.new_filler(league.gender, league.era, thread)?;
+                let free_agent = FreeAgent {
+                    free_agent_id: save_free_agent(conn, league.league_id, None, &player)?,
+                    former_team_id: None,
+                    player,
+                };
+                let (player, _) = sign_free_agent(conn, league, team_index, &free_agent)?;
+                signings.push(format!(
+                    "{} signed {} ({}, Trade Value {}) to fill the need for {}",
+                    league.teams[team_index].name,
+                    player.name,
+                    player.pos,
+                    player.trade_value,
+                    need
+                ));
+            }
+        }
+        Ok(signings)
+    })
+}
+
+/// The actions that can be taken from the free agency menu.
+#[derive(Debug, Clone, Copy)]
+enum FreeAgencyAction {
+    Sign,
+    Release,
+    AutoSign,
+    Generate,
+}
+
+impl fmt::Display for FreeAgencyAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let description = match self {
+            Self::Sign => "Sign a free agent to a team.",
+            Self::Release => "Release a player from a team.",
+            Self::AutoSign => "Have every team fill the holes in their roster.",
+            Self::Generate => "Add new free agents to the pool.",
+        };
+        write!(f, "{}", description)
+    }
+}
+
+/// Prompts the user to choose a team, and returns it's index.
+fn select_team(league: &League, message: &str) -> Result<usize, EditLeagueError> {
+    let team_names: Vec<String> = league.teams.iter().map(|team| team.name.clone()).collect();
+    let choice = handle_inquire_error(Select::new(message, team_names).raw_prompt())?;
+    Ok(choice.index)
+}
+
+/// Prompts the user to choose a team and a free agent, and signs the free agent to the team.
+fn sign_from_input(league: &mut League, conn: &mut Connection) -> Result<(), EditLeagueError> {
+    let team_index = select_team(league, "Which team is signing a free agent?")?;
+    let needs = roster_needs(&league.teams[team_index], league.era);
+    if !needs.is_empty() {
+        let needs: Vec<String> = needs.iter().map(|need| need.to_string()).collect();
+        println!(
+            "{} needs {}.",
+            league.teams[team_index].name,
+            needs.join(", ")
+        );
+    }
+    let mut pool = load_free_agents(conn, league.league_id)?;
+    if pool.is_empty() {
+        println!("There are no free agents in the pool.");
+        return Ok(());
+    }
+    let choice = handle_inquire_error(
+        Select::new(
+            "Which free agent will the team sign?",
+            pool.iter().collect(),
+        )
+        .with_page_size(10)
+        .raw_prompt(),
+    )?;
+    let free_agent = pool.swap_remove(choice.index);
+    let (player, team_spot) = sign_free_agent(conn, league, team_index, &free_agent)?;
+    println!(
+        "{} has signed with {}, and joins the team as {}.",
+        player.name, league.teams[team_index].name, team_spot
+    );
+    Ok(())
+}
+
+/// Prompts the user to choose a team and one of it's players, and releases the player into the free agent pool.
+fn release_from_input(league: &mut League, conn: &mut Connection) -> Result<(), EditLeagueError> {
+    let team_index = select_team(league, "Which team is releasing a player?")?;
+    let team = &league.teams[team_index];
+    let roster = roster_with_spots(team);
+    let options: Vec<String> = roster
+        .iter()
+        .map(|(spot, player)| {
+            format!(
+                "{} {} ({}), Trade Value {}",
+                player.name, player.pos, spot, player.trade_value
+            )
+        })
+        .collect();
+    let choice = handle_inquire_error(
+        Select::new("Which player will be released?", options)
+            .with_page_size(10)
+            .raw_prompt(),
+    )?;
+    let player_id = roster[choice.index].1.player_id;
+    let player = release_player(conn, league, team_index, player_id)?;
+    println!(
+        "{} has been released, and is now a free agent.",
+        player.name
+    );
+    for problem in roster_problems(&league.teams[team_index], league.era) {
+        println!("Warning: {}", problem);
+    }
+    Ok(())
+}
+
+/// Prompts the user for an action in free agency, takes the action, and saves the league's teams.
+pub fn free_agency_from_input<R: Rng>(
+    league: &mut League,
+    conn: &mut Connection,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    let actions = vec![
+        FreeAgencyAction::Sign,
+        FreeAgencyAction::Release,
+        FreeAgencyAction::AutoSign,
+        FreeAgencyAction::Generate,
+    ];
+    let action = match Select::new("What would you like to do?", actions).prompt() {
+        Ok(action) => action,
+        Err(message) => return inquire_check(message),
+    };
+    let result = match action {
+        FreeAgencyAction::Sign => sign_from_input(league, conn),
+        FreeAgencyAction::Release => release_from_input(league, conn),
+        FreeAgencyAction::AutoSign => run_signings(conn, league, thread).map(|signings| {
+            match signings.is_empty() {
+                true => println!("No team has a hole in their roster."),
+                false => println!("{}", signings.join("\n")),
+            };
+        }),
+        FreeAgencyAction::Generate => handle_inquire_error(
+            CustomType::<usize>::new("How many free agents would you like to add?")
+                .with_default(10)
+                .with_error_message("Please enter a valid whole number.")
+                .prompt(),
+        )
+        .and_then(|count| generate_free_agents(conn, league, count, thread)),
+    };
+    match result {
+        Ok(()) => {
+            save_league(league);
+            Ok(())
+        }
+        Err(EditLeagueError::Inquire(message)) => inquire_check(message),
+        Err(message) => Err(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::league::load_teams_from_sql;
+    use crate::test_league;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn free_agency() {
+        let (mut conn, mut league) = test_league(Era::Modern, PlayerGender::Male, 9);
+        let mut thread = StdRng::seed_from_u64(21);
+        let count = |conn: &Connection, sql: &str| -> i64 {
+            conn.query_row(sql, [], |row| row.get(0)).unwrap()
+        };
+        let position_id = |team: &Team, pos: PlayerPosition| {
+            team.lineup.iter().find(|x| x.pos == pos).unwrap().player_id
+        };
+        // Released players leave the players table, and their team now needs someone to replace them.
+        let shortstop_id = position_id(&league.teams[0], PlayerPosition::SS);
+        let reliever_id = league.teams[1].bullpen.as_ref().unwrap()[0].player_id;
+        let shortstop = release_player(&mut conn, &mut league, 0, shortstop_id).unwrap();
+        release_player(&mut conn, &mut league, 1, reliever_id).unwrap();
+        assert!(release_player(&mut conn, &mut league, 1, reliever_id).is_err());
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM free_agents"), 2);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM players"), 48);
+        assert_eq!(
+            roster_needs(&league.teams[0], league.era),
+            vec![RosterNeed::Starter(PlayerPosition::SS)]
+        );
+        assert_eq!(
+            roster_needs(&league.teams[1], league.era),
+            vec![RosterNeed::Bullpen]
+        );
+        // Each free agent signs with the team that needs them.
+        let signings = run_signings(&mut conn, &mut league, &mut thread).unwrap();
+        assert_eq!(signings.len(), 2);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM free_agents"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM players"), 50);
+        let new_id = position_id(&league.teams[0], PlayerPosition::SS);
+        let lineup = &league.teams[0].lineup;
+        let signed = lineup.iter().find(|x| x.player_id == new_id);
+        assert_eq!(signed.unwrap().name, shortstop.name);
+        // A catcher signed by a team that already has one joins the bench, and a Farmhand is generated to replace them.
+        let catcher_id = position_id(&league.teams[0], PlayerPosition::C);
+        release_player(&mut conn, &mut league, 0, catcher_id).unwrap();
+        let pool = load_free_agents(&conn, league.league_id).unwrap();
+        let (_, team_spot) = sign_free_agent(&mut conn, &mut league, 1, &pool[0]).unwrap();
+        assert!(matches!(team_spot, TeamSpot::BenchHitter));
+        assert!(sign_free_agent(&mut conn, &mut league, 0, &pool[0]).is_err());
+        let signings = run_signings(&mut conn, &mut league, &mut thread).unwrap();
+        assert_eq!(signings.len(), 1);
+        let mut loaded = League::new(
+            &league.name,
+            league.gender,
+            league.era,
+            league.league_id,
+            None,
+        );
+        load_teams_from_sql(league.league_id, &mut loaded, &mut conn).unwrap();
+        for team in loaded.teams.iter() {
+            assert!(roster_needs(team, loaded.era).is_empty());
+        }
+        assert_eq!(loaded.teams[1].bench.len(), 6);
+        generate_free_agents(&mut conn, &league, 4, &mut thread).unwrap();
+        let pool = load_free_agents(&conn, league.league_id).unwrap();
+        assert_eq!(pool.len(), 4);
+        assert_eq!(pool.iter().filter(|x| x.player.is_pitcher()).count(), 2);
+        // A player that is no longer in the database is not added to the free agent pool.
+        let bench_id = league.teams[1].bench[0].player_id;
+        conn.execute("DELETE FROM players WHERE player_id = ?1", [bench_id])
+            .unwrap();
+        assert!(matches!(
+            release_player(&mut conn, &mut league, 1, bench_id),
+            Err(EditLeagueError::RosterError(_))
+        ));
+        assert_eq!(load_free_agents(&conn, league.league_id).unwrap().len(), 4);
+    }
+}
@@ -0,0 +1,260 @@
+use core::fmt;
+
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::in_transaction;
+use crate::league::League;
+use crate::note::Notable;
+use crate::note::Note;
+use rusqlite::Connection;
+
+/// A scheduled game, along with it's score.
+/// Games are created with a score of 0 - 0 when a schedule is saved, and since a finished game can never end in a tie, a tied score means that the game has not been played yet.
+#[derive(Debug, Clone)]
+pub struct GameRecord {
+    pub game_id: i64,
+    pub home_team_id: i64,
+    pub away_team_id: i64,
+    pub home_score: i32,
+    pub away_score: i32,
+    pub note: Note,
+}
+
+impl Notable for GameRecord {
+    fn get_note(&self) -> &Note {
+        &self.note
+    }
+
+    fn get_note_input_string(&self) -> String {
+        format!(
+            "Please enter the note you wish to attach to game {}",
+            self.game_id
+        )
+    }
+}
+
+impl GameRecord {
+    pub fn is_played(&self) -> bool {
+        self.home_score != self.away_score
+    }
+    /// Returns a tuple of the winning team id and the losing team id, or None if the game has not been played.
+    pub fn winner_loser(&self) -> Option<(i64, i64)> {
+        match self.home_score.cmp(&self.away_score) {
+            std::cmp::Ordering::Greater => Some((self.home_team_id, self.away_team_id)),
+            std::cmp::Ordering::Less => Some((self.away_team_id, self.home_team_id)),
+            std::cmp::Ordering::Equal => None,
+        }
+    }
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let result = match self.is_played() {
+            true => format!(
+                "Game {}: Away {} - Home {}",
+                self.game_id, self.away_score, self.home_score
+            ),
+            false => format!("Game {}: Not played", self.game_id),
+        };
+        match self.get_note() {
+            Some(note) => write!(f, "{} ({})", result, note),
+            None => write!(f, "{}", result),
+        }
+    }
+}
+
+/// Returns every game in a series, in the order they were scheduled.
+pub fn get_series_games(
+    conn: &Connection,
+    series_id: i64,
+) -> Result<Vec<GameRecord>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "
+        SELECT
+            games.game_id,
+            series.home_team_id,
+            series.away_team_id,
+            games.home_score,
+            games.away_score,
+            games.game_not
+        FROM
+            games
+        INNER JOIN
+            series ON series.series_id = games.series_id
+        WHERE
+            games.series_id = ?1
+        ORDER BY
+            games.game_id ASC
+        ",
+    )?;
+    let game_iter = stmt.query_map([series_id], |row| {
+        Ok(GameRecord {
+            game_id: row.get(0)?,
+            home_team_id: row.get(1)?,
+            away_team_id: row.get(2)?,
+            home_score: row.get(3)?,
+            away_score: row.get(4)?,
+            note: row.get(5)?,
+        })
+    })?;
+    let mut result_vec = Vec::new();
+    for game in game_iter {
+        result_vec.push(game?)
+    }
+    Ok(result_vec)
+}
+
+/// Returns a single game, or None if there is no game with the id.
+pub fn get_game(conn: &Connection, game_id: i64) -> Result<Option<GameRecord>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "
+        SELECT
+            games.game_id,
+            series.home_team_id,
+            series.away_team_id,
+            games.home_score,
+            games.away_score,
+            games.game_not
+        FROM
+            games
+        INNER JOIN
+            series ON series.series_id = games.series_id
+        WHERE
+            games.game_id = ?1
+        ",
+    )?;
+    let mut game_iter = stmt.query_map([game_id], |row| {
+        Ok(GameRecord {
+            game_id: row.get(0)?,
+            home_team_id: row.get(1)?,
+            away_team_id: row.get(2)?,
+            home_score: row.get(3)?,
+            away_score: row.get(4)?,
+            note: row.get(5)?,
+        })
+    })?;
+    game_iter.next().transpose()
+}
+
+/// Returns every game in a season that has been played, in the order they were scheduled.
+pub fn get_played_season_games(
+    conn: &Connection,
+    season_id: i64,
+) -> Result<Vec<GameRecord>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "
+        SELECT
+            games.game_id,
+            series.home_team_id,
+            series.away_team_id,
+            games.home_score,
+            games.away_score,
+            games.game_not
+        FROM
+            games
+        INNER JOIN
+            series ON series.series_id = games.series_id
+        INNER JOIN
+            rounds ON rounds.round_id = series.round_id
+        WHERE
+            rounds.season_id = ?1
+            AND games.home_score != games.away_score
+        ORDER BY
+            rounds.round_id ASC, games.game_id ASC
+        ",
+    )?;
+    let game_iter = stmt.query_map([season_id], |row| {
+        Ok(GameRecord {
+            game_id: row.get(0)?,
+            home_team_id: row.get(1)?,
+            away_team_id: row.get(2)?,
+            home_score: row.get(3)?,
+            away_score: row.get(4)?,
+            note: row.get(5)?,
+        })
+    })?;
+    let mut result_vec = Vec::new();
+    for game in game_iter {
+        result_vec.push(game?)
+    }
+    Ok(result_vec)
+}
+
+/// Returns the first game in a series that has not been played yet.
+pub fn next_unplayed_game(
+    conn: &Connection,
+    series_id: i64,
+) -> Result<Option<GameRecord>, rusqlite::Error> {
+    let games = get_series_games(conn, series_id)?;
+    Ok(games.into_iter().find(|game| !game.is_played()))
+}
+
+/// Adds or takes away a win and a loss for the teams in a game's result. Does nothing for a game that has not been played.
+fn change_team_records(
+    conn: &Connection,
+    game: &GameRecord,
+    change: i32,
+) -> Result<(), EditLeagueError> {
+    if let Some((winner, loser)) = game.winner_loser() {
+        handle_sql_error(conn.execute(
+            "UPDATE teams SET wins = wins + ?1 WHERE team_id = ?2",
+            [change as i64, winner],
+        ))?;
+        handle_sql_error(conn.execute(
+            "UPDATE teams SET losses = losses + ?1 WHERE team_id = ?2",
+            [change as i64, loser],
+        ))?;
+    }
+    Ok(())
+}
+
+/// Saves the score of a game to the database, and updates the wins and losses of both teams.
+/// If the game already had a result, the wins and losses from the old result are taken away first, so a game can be corrected without double counting it.
+pub fn record_game_result(
+    conn: &mut Connection,
+    game: &GameRecord,
+    home_score: i32,
+    away_score: i32,
+) -> Result<GameRecord, EditLeagueError> {
+    let new_record = GameRecord {
+        home_score,
+        away_score,
+        ..game.clone()
+    };
+    in_transaction(conn, |conn| {
+        change_team_records(conn, game, -1)?;
+        handle_sql_error(conn.execute(
+            "UPDATE games SET home_score = ?1, away_score = ?2 WHERE game_id = ?3",
+            [home_score as i64, away_score as i64, game.game_id],
+        ))?;
+        change_team_records(conn, &new_record, 1)
+    })?;
+    Ok(new_record)
+}
+
+/// Updates the wins and losses of the teams in a league to match a game that was saved with record_game_result, so the league's files can be saved without loading the teams again.
+pub fn update_team_records(league: &mut League, old_record: &GameRecord, new_record: &GameRecord) {
+    for (record, change) in [(old_record, -1), (new_record, 1)] {
+        if let Some((winner, loser)) = record.winner_loser() {
+            if let Some(index) = league.team_id_hash.get(&winner) {
+                league.teams[*index].wins += change;
+            }
+            if let Some(index) = league.team_id_hash.get(&loser) {
+                league.teams[*index].losses += change;
+            }
+        }
+    }
+}
+
+/// Saves a note to a game in the database. The note is stored in the game_not column of the games table.
+pub fn save_game_note(
+    conn: &Connection,
+    game: &GameRecord,
+    note: &Note,
+) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "UPDATE games SET game_not = ?1 WHERE game_id = ?2",
+        (note, game.game_id),
+    )?;
+    Ok(())
+}
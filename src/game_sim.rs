@@ -0,0 +1,604 @@
+use core::fmt;
+
+use crate::at_bat::resolve_at_bat;
+use crate::at_bat::AtBatOutcome;
+use crate::at_bat::HitType;
+use crate::at_bat::OutType;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::game_result::next_unplayed_game;
+use crate::game_result::record_game_result;
+use crate::game_result::update_team_records;
+use crate::injury::after_game;
+use crate::injury::healthy_team;
+use crate::inquire_check;
+use crate::league::save_league;
+use crate::league::League;
+use crate::player::Player;
+use crate::sched_view::select_series;
+use crate::team::Team;
+use crate::traits::PitcherTrait;
+use crate::traits::PlayerTrait;
+use inquire::Confirm;
+use rand::Rng;
+use rusqlite::Connection;
+
+/// A regulation game is 9 innings, games that are tied afterward go to extra innings.
+const REGULATION_INNINGS: usize = 9;
+/// How many innings a starting pitcher can throw before they start to tire.
+const STARTER_STAMINA: i32 = 6;
+/// How many innings a relief pitcher can throw before they start to tire.
+const RELIEVER_STAMINA: i32 = 2;
+/// Pitchers with the ST+ trait are able to pitch this many extra innings before tiring.
+const STAMINA_BONUS: i32 = 2;
+/// If a pitcher allows this many runs, they are pulled from the game if there is a reliever available.
+const RUNS_BEFORE_HOOK: i32 = 5;
+
+/// Keeps track of a pitcher that is currently in the game.
+struct PitcherInGame<'a> {
+    player: &'a Player,
+    stamina: i32,
+    innings_pitched: i32,
+    runs_allowed: i32,
+}
+
+impl<'a> PitcherInGame<'a> {
+    fn new(player: &'a Player, base_stamina: i32) -> Self {
+        let stamina = match player.pitcher_trait {
+            Some(PitcherTrait::ST) => base_stamina + STAMINA_BONUS,
+            _ => base_stamina,
+        };
+        PitcherInGame {
+            player,
+            stamina,
+            innings_pitched: 0,
+            runs_allowed: 0,
+        }
+    }
+    /// Returns how many innings a pitcher has thrown past their stamina.
+    fn fatigue(&self) -> i32 {
+        (self.innings_pitched - self.stamina).max(0)
+    }
+    /// A tired pitcher loses a step on their pitch die for every inning they pitch past their stamina.
+    /// This returns a copy of the pitcher with their pitch die lowered to match how tired they are.
+    fn tired_copy(&self) -> Player {
+        let fatigue = self.fatigue();
+        Player {
+            pd: Some(self.player.get_base_pd().step(-fatigue)),
+            ..self.player.clone()
+        }
+    }
+    /// Returns true if the manager should go to the bullpen.
+    fn needs_relief(&self) -> bool {
+        self.fatigue() > 0 || self.runs_allowed >= RUNS_BEFORE_HOOK
+    }
+}
+
+/// Keeps track of everything a team does during a game.
+struct TeamInGame<'a> {
+    team: &'a Team,
+    batting_order: Vec<&'a Player>,
+    batter_index: usize,
+    pitcher: PitcherInGame<'a>,
+    bullpen: Vec<&'a Player>,
+    pitchers_used: Vec<&'a Player>,
+    line: Vec<i32>,
+    hits: i32,
+    errors: i32,
+}
+
+impl<'a> TeamInGame<'a> {
+    fn new(team: &'a Team, rotation_turn: usize) -> Result<Self, EditLeagueError> {
+        if team.lineup.is_empty() || team.starting_pitching.is_empty() {
+            return Err(EditLeagueError::GameError(format!(
+                "{} needs at least one player in it's lineup and rotation to play a game.",
+                team.name
+            )));
+        }
+        // The lineup bats in the same order that is used in the team's text file.
+        let mut batting_order: Vec<&Player> = team.lineup.iter().collect();
+        batting_order.sort_by_key(|player| std::cmp::Reverse(player.get_lineup_score()));
+        // Starting pitchers take turns in the rotation.
+        let starter = &team.starting_pitching[rotation_turn % team.starting_pitching.len()];
+        // The best relievers are used first.
+        let mut bullpen: Vec<&Player> = match &team.bullpen {
+            Some(pen) => pen.iter().collect(),
+            None => Vec::new(),
+        };
+        bullpen.sort_by_key(|player| std::cmp::Reverse(player.get_pitcher_rank_info()));
+        Ok(TeamInGame {
+            team,
+            batting_order,
+            batter_index: 0,
+            pitcher: PitcherInGame::new(starter, STARTER_STAMINA),
+            bullpen,
+            pitchers_used: vec![starter],
+            line: Vec::new(),
+            hits: 0,
+            errors: 0,
+        })
+    }
+
+    fn runs(&self) -> i32 {
+        self.line.iter().sum()
+    }
+    /// Returns the next batter due up. The pitcher bats after the 8 players in the lineup.
+    fn next_batter(&mut self) -> &'a Player {
+        let order_len = self.batting_order.len() + 1;
+        let slot = self.batter_index % order_len;
+        self.batter_index += 1;
+        match self.batting_order.get(slot) {
+            Some(player) => player,
+            None => self.pitcher.player,
+        }
+    }
+    /// Before an inning in the field starts, a tired or struggling pitcher is replaced by the next reliever in the bullpen.
+    fn check_bullpen(&mut self) {
+        if self.pitcher.needs_relief() && !self.bullpen.is_empty() {
+            let reliever = self.bullpen.remove(0);
+            self.pitcher = PitcherInGame::new(reliever, RELIEVER_STAMINA);
+            self.pitchers_used.push(reliever);
+        }
+    }
+}
+
+/// Represents the bases, with the player standing on first, second, and third.
+type Bases<'a> = [Option<&'a Player>; 3];
+
+/// Returns true if a runner is fast enough to take an extra base.
+fn is_fast(runner: &Player) -> bool {
+    runner.b_traits.speed.to_int() > 0
+}
+/// Returns true if a runner is too slow to take an extra base on a single.
+fn is_slow(runner: &Player) -> bool {
+    runner.b_traits.speed.to_int() < 0
+}
+
+/// Moves every runner up a number of bases, and returns how many runners scored.
+fn advance_all(bases: &mut Bases, num_bases: usize) -> i32 {
+    let mut runs = 0;
+    for base in (0..3).rev() {
+        if let Some(runner) = bases[base].take() {
+            let new_base = base + num_bases;
+            match new_base >= 3 {
+                true => runs += 1,
+                false => bases[new_base] = Some(runner),
+            }
+        }
+    }
+    runs
+}
+
+/// Moves runners up only when they are forced to by a batter reaching first base.
+fn force_advance(bases: &mut Bases) -> i32 {
+    match bases {
+        [Some(_), Some(_), Some(_)] => {
+            bases[2] = bases[1].take();
+            bases[1] = bases[0].take();
+            1
+        }
+        [Some(_), Some(_), None] => {
+            bases[2] = bases[1].take();
+            bases[1] = bases[0].take();
+            0
+        }
+        [Some(_), None, _] => {
+            bases[1] = bases[0].take();
+            0
+        }
+        _ => 0,
+    }
+}
+
+/// Updates the bases for the result of a plate appearance.
+/// Returns a tuple of the number of runs scored and the number of outs made.
+fn run_bases<'a>(
+    bases: &mut Bases<'a>,
+    outcome: AtBatOutcome,
+    outs: i32,
+    batter: &'a Player,
+) -> (i32, i32) {
+    match outcome {
+        AtBatOutcome::Hit { hit, .. } => {
+            let runs = match hit {
+                HitType::Single => {
+                    let mut runs = 0;
+                    // Runners on third always score, and runners on second score unless they are slow.
+                    if bases[2].take().is_some() {
+                        runs += 1;
+                    }
+                    if let Some(runner) = bases[1].take() {
+                        match is_slow(runner) {
+                            true => bases[2] = Some(runner),
+                            false => runs += 1,
+                        }
+                    }
+                    if let Some(runner) = bases[0].take() {
+                        match bases[2].is_none() && is_fast(runner) {
+                            true => bases[2] = Some(runner),
+                            false => bases[1] = Some(runner),
+                        }
+                    }
+                    runs
+                }
+                HitType::Double => {
+                    let mut runs = 0;
+                    if bases[2].take().is_some() {
+                        runs += 1;
+                    }
+                    if bases[1].take().is_some() {
+                        runs += 1;
+                    }
+                    // A fast runner is able to score from first on a double.
+                    if let Some(runner) = bases[0].take() {
+                        match is_fast(runner) {
+                            true => runs += 1,
+                            false => bases[2] = Some(runner),
+                        }
+                    }
+                    runs
+                }
+                HitType::Triple | HitType::HomeRun => advance_all(bases, 3),
+            };
+            match hit {
+                HitType::HomeRun => (runs + 1, 0),
+                _ => {
+                    bases[hit.bases() as usize - 1] = Some(batter);
+                    (runs, 0)
+                }
+            }
+        }
+        AtBatOutcome::Walk => {
+            let runs = force_advance(bases);
+            bases[0] = Some(batter);
+            (runs, 0)
+        }
+        AtBatOutcome::Error => {
+            let runs = advance_all(bases, 1);
+            bases[0] = Some(batter);
+            (runs, 0)
+        }
+        // Runs never score on the third out.
+        AtBatOutcome::ProductiveOut(_) if outs == 2 => (0, 1),
+        AtBatOutcome::ProductiveOut(OutType::FlyOut) => {
+            // On a fly ball, the runner on third tags up and scores, and the runner on second moves to third.
+            let mut runs = 0;
+            if bases[2].take().is_some() {
+                runs += 1;
+            }
+            bases[2] = bases[1].take();
+            (runs, 1)
+        }
+        AtBatOutcome::ProductiveOut(_) => (advance_all(bases, 1), 1),
+        // A ground ball with a runner on first and less than 2 outs is a double play.
+        AtBatOutcome::Out(OutType::GroundOut) if bases[0].is_some() && outs < 2 => {
+            bases[0] = None;
+            match outs {
+                0 => (advance_all(bases, 1), 2),
+                _ => (0, 2),
+            }
+        }
+        AtBatOutcome::Out(_) => (0, 1),
+    }
+}
+
+/// Plays half of an inning. If walk_off_target is set, the inning ends as soon as the batting team scores more runs than the target.
+fn play_half_inning<R: Rng>(
+    offense: &mut TeamInGame,
+    defense: &mut TeamInGame,
+    walk_off_target: Option<i32>,
+    thread: &mut R,
+) {
+    defense.check_bullpen();
+    let pitcher = defense.pitcher.tired_copy();
+    let starting_runs = offense.runs();
+    let mut bases: Bases = [None, None, None];
+    let mut outs = 0;
+    let mut runs = 0;
+    while outs < 3 {
+        let batter = offense.next_batter();
+        let result = resolve_at_bat(batter, &pitcher, thread);
+        match result.outcome {
+            AtBatOutcome::Hit { .. } => offense.hits += 1,
+            AtBatOutcome::Error => defense.errors += 1,
+            _ => {}
+        };
+        let (new_runs, new_outs) = run_bases(&mut bases, result.outcome, outs, batter);
+        runs += new_runs;
+        outs += new_outs;
+        if let Some(target) = walk_off_target {
+            if starting_runs + runs > target {
+                break;
+            }
+        }
+    }
+    offense.line.push(runs);
+    defense.pitcher.innings_pitched += 1;
+    defense.pitcher.runs_allowed += runs;
+}
+
+/// The final result of a simulated game.
+#[derive(Debug)]
+pub struct GameSummary {
+    pub home_name: String,
+    pub away_name: String,
+    pub home_score: i32,
+    pub away_score: i32,
+    home_line: Vec<i32>,
+    away_line: Vec<i32>,
+    home_hits: i32,
+    away_hits: i32,
+    home_errors: i32,
+    away_errors: i32,
+    home_pitchers: Vec<String>,
+    away_pitchers: Vec<String>,
+    home_pitcher_ids: Vec<i64>,
+    away_pitcher_ids: Vec<i64>,
+}
+
+/// Formats a line score, with an X for an inning that was not played.
+fn line_string(line: &[i32], innings: usize) -> String {
+    (0..innings)
+        .map(|i| match line.get(i) {
+            Some(runs) => format!("{:>3}", runs),
+            None => format!("{:>3}", "X"),
+        })
+        .collect()
+}
+
+impl fmt::Display for GameSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let innings = self.away_line.len();
+        let name_width = self.home_name.len().max(self.away_name.len());
+        let header: String = (1..=innings).map(|i| format!("{:>3}", i)).collect();
+        writeln!(f, "{:width$}{}    R  H  E", "", header, width = name_width)?;
+        writeln!(
+            f,
+            "{:width$}{} {:>3}{:>3}{:>3}",
+            self.away_name,
+            line_string(&self.away_line, innings),
+            self.away_score,
+            self.away_hits,
+            self.away_errors,
+            width = name_width
+        )?;
+        writeln!(
+            f,
+            "{:width$}{} {:>3}{:>3}{:>3}",
+            self.home_name,
+            line_string(&self.home_line, innings),
+            self.home_score,
+            self.home_hits,
+            self.home_errors,
+            width = name_width
+        )?;
+        writeln!(
+            f,
+            "{} pitchers: {}",
+            self.away_name,
+            self.away_pitchers.join(", ")
+        )?;
+        write!(
+            f,
+            "{} pitchers: {}",
+            self.home_name,
+            self.home_pitchers.join(", ")
+        )
+    }
+}
+
+/// Returns the names of the pitchers used by a team, in the order they took the mound.
+fn pitcher_names(pitchers: &[&Player]) -> Vec<String> {
+    pitchers
+        .iter()
+        .map(|pitcher| pitcher.name.clone())
+        .collect()
+}
+
+/// Simulates a full game between two teams, inning by inning.
+/// The rotation turn for each team is used to pick which starting pitcher takes the mound.
+pub fn simulate_game<R: Rng>(
+    home_team: &Team,
+    away_team: &Team,
+    home_rotation_turn: usize,
+    away_rotation_turn: usize,
+    thread: &mut R,
+) -> Result<GameSummary, EditLeagueError> {
+    let mut home = TeamInGame::new(home_team, home_rotation_turn)?;
+    let mut away = TeamInGame::new(away_team, away_rotation_turn)?;
+    let mut inning = 1;
+    loop {
+        play_half_inning(&mut away, &mut home, None, thread);
+        // If the home team is winning going into the bottom of the last inning, they do not need to bat.
+        let final_inning = inning >= REGULATION_INNINGS;
+        if final_inning && home.runs() > away.runs() {
+            break;
+        }
+        let walk_off_target = match final_inning {
+            true => Some(away.runs()),
+            false => None,
+        };
+        play_half_inning(&mut home, &mut away, walk_off_target, thread);
+        if final_inning && home.runs() != away.runs() {
+            break;
+        }
+        inning += 1;
+    }
+    Ok(GameSummary {
+        home_name: home.team.name.clone(),
+        away_name: away.team.name.clone(),
+        home_score: home.runs(),
+        away_score: away.runs(),
+        home_hits: home.hits,
+        away_hits: away.hits,
+        home_errors: home.errors,
+        away_errors: away.errors,
+        home_line: home.line,
+        away_line: away.line,
+        home_pitchers: pitcher_names(&home.pitchers_used),
+        away_pitchers: pitcher_names(&away.pitchers_used),
+        home_pitcher_ids: home.pitchers_used.iter().map(|x| x.player_id).collect(),
+        away_pitcher_ids: away.pitchers_used.iter().map(|x| x.player_id).collect(),
+    })
+}
+
+/// Returns the team from a league with a matching team id.
+pub fn get_league_team(league: &League, team_id: i64) -> Result<&Team, EditLeagueError> {
+    match league.team_id_hash.get(&team_id) {
+        Some(index) => Ok(&league.teams[*index]),
+        None => Err(EditLeagueError::GameError(format!(
+            "Unable to find a team with id {} in {}",
+            team_id, league.name
+        ))),
+    }
+}
+
+/// Returns the ids of the players on a team that took part in a simulated game, which are the players in the lineup and the pitchers that were used.
+fn players_in_game(team: &Team, pitcher_ids: &[i64]) -> Vec<i64> {
+    team.lineup
+        .iter()
+        .map(|player| player.player_id)
+        .chain(pitcher_ids.iter().copied())
+        .collect()
+}
+
+/// Prompts the user to pick a series from the league's schedule, and then simulates the next unplayed game in the series.
+/// The result of each game is saved to the database, and the user can keep playing until the series is finished.
+/// Injured players sit out each game, and both teams roll for injuries after each game.
+pub fn play_game_from_input<R: Rng>(
+    league: &mut League,
+    conn: &mut Connection,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    let series = match select_series(league, conn)? {
+        Some(wrapper) => wrapper,
+        None => return Ok(()),
+    };
+    loop {
+        let game = match handle_sql_error(next_unplayed_game(conn, series.series_id))? {
+            Some(game) => game,
+            None => {
+                println!("All games in this series have been played.");
+                break;
+            }
+        };
+        let home_team = healthy_team(get_league_team(league, series.home_team_id)?);
+        let away_team = healthy_team(get_league_team(league, series.away_team_id)?);
+        // Each game played moves the starting rotation forward by one.
+        let home_turn = (home_team.wins + home_team.losses) as usize;
+        let away_turn = (away_team.wins + away_team.losses) as usize;
+        let summary = simulate_game(&home_team, &away_team, home_turn, away_turn, thread)?;
+        println!("{}\n", summary);
+        let new_record = record_game_result(conn, &game, summary.home_score, summary.away_score)?;
+        update_team_records(league, &game, &new_record);
+        println!("Result saved.");
+        for (team, pitcher_ids) in [
+            (&home_team, &summary.home_pitcher_ids),
+            (&away_team, &summary.away_pitcher_ids),
+        ] {
+            let appeared = players_in_game(team, pitcher_ids);
+            for message in after_game(conn, league, team.team_id, &appeared, thread)? {
+                println!("{}", message);
+            }
+        }
+        let ans = Confirm::new("Would you like to play the next game in the series?")
+            .with_default(true)
+            .prompt();
+        match ans {
+            Ok(true) => {}
+            Ok(false) => break,
+            Err(message) => return inquire_check(message),
+        }
+    }
+    // The team files are saved so they show any changes to the injured lists.
+    save_league(league);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::era::Era;
+    use crate::game_result::get_game;
+    use crate::league::load_teams_from_sql;
+    use crate::player::PlayerGender;
+    use crate::schedule::{new_schedule, schedule_to_sql};
+    use crate::test_league;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn simulated_games() {
+        let (_, league) = test_league(Era::Modern, PlayerGender::Male, 2);
+        let (home_team, away_team) = (&league.teams[0], &league.teams[1]);
+        let mut extra_innings = 0;
+        let mut walk_offs = 0;
+        for seed in 0..200 {
+            let mut seeded = StdRng::seed_from_u64(seed);
+            let game = simulate_game(home_team, away_team, 0, 0, &mut seeded).unwrap();
+            // A game never ends in a tie, and always goes at least 9 innings.
+            assert_ne!(game.home_score, game.away_score);
+            let innings = game.away_line.len();
+            assert!(innings >= REGULATION_INNINGS);
+            assert_eq!(game.away_score, game.away_line.iter().sum::<i32>());
+            assert_eq!(game.home_score, game.home_line.iter().sum::<i32>());
+            // Extra innings are only played when the game is tied after each inning past the 9th.
+            for inning in REGULATION_INNINGS..innings {
+                let away_runs: i32 = game.away_line[..inning].iter().sum();
+                let home_runs: i32 = game.home_line[..inning].iter().sum();
+                assert_eq!(away_runs, home_runs);
+            }
+            if innings > REGULATION_INNINGS {
+                extra_innings += 1;
+            }
+            let home_before_last: i32 = game.home_line[..innings - 1].iter().sum();
+            match game.home_line.len() == innings {
+                // A walk-off ends the game as soon as the home team takes the lead, so they can win by at most a grand slam.
+                true if game.home_score > game.away_score => {
+                    assert!(home_before_last <= game.away_score);
+                    assert!(game.home_score - game.away_score <= 4);
+                    walk_offs += 1;
+                }
+                true => {}
+                // The home team only skips the bottom of the last inning if they are already winning.
+                false => assert!(game.home_score > game.away_score),
+            }
+        }
+        assert!(extra_innings > 0);
+        assert!(walk_offs > 0);
+    }
+
+    #[test]
+    fn recorded_results() {
+        let (mut conn, mut league) = test_league(Era::Ancient, PlayerGender::Male, 2);
+        let sched = new_schedule(&league.teams, 1, 1);
+        schedule_to_sql(&mut conn, &league, sched).unwrap();
+        let game_id: i64 = conn
+            .query_row("SELECT MIN(game_id) FROM games", [], |row| row.get(0))
+            .unwrap();
+        let game = get_game(&conn, game_id).unwrap().unwrap();
+        let home_team = get_league_team(&league, game.home_team_id).unwrap();
+        let away_team = get_league_team(&league, game.away_team_id).unwrap();
+        let mut seeded = StdRng::seed_from_u64(2);
+        let summary = simulate_game(home_team, away_team, 0, 0, &mut seeded).unwrap();
+        let new_record =
+            record_game_result(&mut conn, &game, summary.home_score, summary.away_score).unwrap();
+        update_team_records(&mut league, &game, &new_record);
+        // The saved game and the league's teams agree with the database.
+        let saved = get_game(&conn, game_id).unwrap().unwrap();
+        assert_eq!(saved.winner_loser(), new_record.winner_loser());
+        let mut loaded = League::new(
+            &league.name,
+            league.gender,
+            league.era,
+            league.league_id,
+            None,
+        );
+        load_teams_from_sql(league.league_id, &mut loaded, &mut conn).unwrap();
+        for (team, loaded) in league.teams.iter().zip(loaded.teams.iter()) {
+            assert_eq!((team.wins, team.losses), (loaded.wins, loaded.losses));
+            assert_eq!(team.wins + team.losses, 1);
+        }
+        let (winner, _) = new_record.winner_loser().unwrap();
+        assert_eq!(get_league_team(&league, winner).unwrap().wins, 1);
+    }
+}
@@ -0,0 +1,538 @@
+use core::fmt;
+
+use crate::edit_league_error::handle_serde_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::in_transaction;
+use crate::inquire_check;
+use crate::league::save_league;
+use crate::league::League;
+use crate::player::Player;
+use crate::position::PlayerPosition;
+use crate::team::Team;
+use crate::trade::fielding_pos;
+use crate::trade::roster_with_spots;
+use crate::traits::Toughness;
+use crate::Deserialize;
+use crate::Serialize;
+use chrono::{Datelike, Local};
+use inquire::Select;
+use rand::Rng;
+use rusqlite::Connection;
+
+/* After each game that is simulated, quickly played or entered, both teams roll a d20, and one of their players that took part in the game is injured on a 1.
+Injuries can also be rolled on demand for any player. The length of an injury is decided by rolling a d20 on the injury table, where higher rolls are worse:
+1-10 is a minor injury that costs the player their next game, 11-16 is a moderate injury that costs 1d6 games, 17-19 is a serious injury that costs 5 games for every pip on a d6, and a 20 ends the player's season.
+Deadball's T+ trait marks a tough player, and is their protection from injury: a tough player that is hurt rolls on the injury table twice and keeps the less serious result.
+Each game a team plays counts against the injuries of it's players, and injured players return once they have missed enough games. Every injury heals over the offseason.
+While a starter is injured, the best bench player that can play their position takes their place in the lineup.*/
+
+/// The die each team rolls after a game to see if a player was injured. A player is injured on a 1.
+const INJURY_DIE: i32 = 20;
+/// The number of games missed for every pip rolled on a d6 for a serious injury.
+const SERIOUS_INJURY_GAMES: i32 = 5;
+
+/// How bad an injury is.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum InjurySeverity {
+    Minor,
+    Moderate,
+    Serious,
+    SeasonEnding,
+}
+
+impl InjurySeverity {
+    /// Returns the severity that matches a d20 roll on the injury table.
+    fn from_roll(roll: i32) -> InjurySeverity {
+        match roll {
+            ..=10 => Self::Minor,
+            11..=16 => Self::Moderate,
+            17..=19 => Self::Serious,
+            _ => Self::SeasonEnding,
+        }
+    }
+
+    /// Returns how many games an injury costs a player, or None if it ends their season.
+    fn games_missed<R: Rng>(&self, thread: &mut R) -> Option<i32> {
+        match self {
+            Self::Minor => Some(1),
+            Self::Moderate => Some(thread.gen_range(1..=6)),
+            Self::Serious => Some(thread.gen_range(1..=6) * SERIOUS_INJURY_GAMES),
+            Self::SeasonEnding => None,
+        }
+    }
+}
+
+impl fmt::Display for InjurySeverity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chars = match self {
+            Self::Minor => "Minor",
+            Self::Moderate => "Moderate",
+            Self::Serious => "Serious",
+            Self::SeasonEnding => "Season Ending",
+        };
+        write!(f, "{}", chars)
+    }
+}
+
+/// An injury to a player. Players stay on their team while injured, but do not play until games_remaining reaches 0.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Injury {
+    pub injury_id: i64,
+    pub player_id: i64,
+    pub player_name: String,
+    pub severity: InjurySeverity,
+    pub games_missed: Option<i32>,
+    /// The number of games left before the player returns, or None if the player is out for the season.
+    pub games_remaining: Option<i32>,
+}
+
+impl fmt::Display for Injury {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.games_remaining {
+            Some(1) => write!(
+                f,
+                "{}, {} Injury, out 1 more game",
+                self.player_name, self.severity
+            ),
+            Some(games) => write!(
+                f,
+                "{}, {} Injury, out {} more games",
+                self.player_name, self.severity, games
+            ),
+            None => write!(
+                f,
+                "{}, {} Injury, out for the season",
+                self.player_name, self.severity
+            ),
+        }
+    }
+}
+
+/// Rolls on the injury table for a player. Following Deadball's T+ rule, tough players roll twice and keep the lower, less serious roll.
+pub fn roll_injury_table<R: Rng>(player: &Player, thread: &mut R) -> InjurySeverity {
+    let roll = thread.gen_range(1..=20);
+    let roll = match player.b_traits.toughness {
+        Toughness::T1 => roll.min(thread.gen_range(1..=20)),
+        Toughness::T0 => roll,
+    };
+    InjurySeverity::from_roll(roll)
+}
+
+/// Rolls on the injury table for a player, saves the injury to the database and returns it.
+pub fn injure_player<R: Rng>(
+    conn: &Connection,
+    player: &Player,
+    thread: &mut R,
+) -> Result<Injury, EditLeagueError> {
+    let severity = roll_injury_table(player, thread);
+    let games_missed = severity.games_missed(thread);
+    let mut injury = Injury {
+        injury_id: 0,
+        player_id: player.player_id,
+        player_name: player.name.clone(),
+        severity,
+        games_missed,
+        games_remaining: games_missed,
+    };
+    injury.injury_id = save_injury(conn, &injury)?;
+    Ok(injury)
+}
+
+/// Saves an injury to the database, and returns its injury id.
+pub fn save_injury(conn: &Connection, injury: &Injury) -> Result<i64, EditLeagueError> {
+    let now = Local::now();
+    let date_injured = format!("{}_{}_{}", now.year(), now.month(), now.day());
+    let severity_json = handle_serde_error(serde_json::to_string(&injury.severity))?;
+    let mut stmt = handle_sql_error(conn.prepare_cached(
+        "INSERT INTO injuries(player_id, severity, games_missed, games_remaining, date_injured) VALUES(?1, ?2, ?3, ?4, ?5)",
+    ))?;
+    handle_sql_error(stmt.insert((
+        injury.player_id,
+        severity_json,
+        injury.games_missed,
+        injury.games_remaining,
+        date_injured,
+    )))
+}
+
+/// Loads the injuries of every player on a team.
+pub fn load_injuries(conn: &Connection, team_id: i64) -> Result<Vec<Injury>, EditLeagueError> {
+    let mut stmt = handle_sql_error(conn.prepare_cached(
+        "SELECT injuries.injury_id, injuries.player_id, players.player_name, injuries.severity, injuries.games_missed, injuries.games_remaining
+        FROM injuries
+        INNER JOIN players ON players.player_id = injuries.player_id
+        WHERE players.team_id = ?1
+        ORDER BY injuries.injury_id",
+    ))?;
+    let rows = handle_sql_error(stmt.query_map([team_id], |row| {
+        Ok((
+            row.get(0)?,
+            row.get(1)?,
+            row.get(2)?,
+            row.get::<_, String>(3)?,
+            row.get(4)?,
+            row.get(5)?,
+        ))
+    }))?;
+    let mut injuries = Vec::new();
+    for row in rows {
+        let (injury_id, player_id, player_name, severity, games_missed, games_remaining) =
+            handle_sql_error(row)?;
+        injuries.push(Injury {
+            injury_id,
+            player_id,
+            player_name,
+            severity: handle_serde_error(serde_json::from_str(&severity))?,
+            games_missed,
+            games_remaining,
+        });
+    }
+    Ok(injuries)
+}
+
+/// Counts a game against the injuries of every player on a team, and removes the injuries that have healed.
+/// Returns the names of the players that are no longer injured.
+fn count_game(conn: &Connection, team: &mut Team) -> Result<Vec<String>, EditLeagueError> {
+    let mut healed = Vec::new();
+    for injury in team.injured_list.iter_mut() {
+        if let Some(games) = injury.games_remaining.as_mut() {
+            *games -= 1;
+            handle_sql_error(conn.execute(
+                "UPDATE injuries SET games_remaining = ?1 WHERE injury_id = ?2",
+                (*games, injury.injury_id),
+            ))?;
+        }
+    }
+    for injury in team.injured_list.iter() {
+        if injury.games_remaining.is_some_and(|games| games <= 0) {
+            handle_sql_error(conn.execute(
+                "DELETE FROM injuries WHERE injury_id = ?1",
+                [injury.injury_id],
+            ))?;
+            healed.push(injury.player_name.clone());
+        }
+    }
+    team.injured_list
+        .retain(|injury| injury.games_remaining.is_none_or(|games| games > 0));
+    Ok(healed)
+}
+
+/// Returns true if a player is on a team's injured list.
+pub fn is_injured(team: &Team, player_id: i64) -> bool {
+    team.injured_list.iter().any(|x| x.player_id == player_id)
+}
+
+/// Returns how well a bench player can fill in for a starter at a position. Higher is better.
+fn fill_in_rank(pos: &PlayerPosition, bench_player: &Player) -> i32 {
+    let bench_pos = fielding_pos(bench_player);
+    let infield = [
+        PlayerPosition::FirstBase,
+        PlayerPosition::SecondBase,
+        PlayerPosition::ThirdBase,
+        PlayerPosition::SS,
+    ];
+    let outfield = [PlayerPosition::LF, PlayerPosition::CF, PlayerPosition::RF];
+    match bench_pos {
+        bench_pos if bench_pos == pos => 3,
+        PlayerPosition::INF if infield.contains(pos) => 2,
+        PlayerPosition::OF if outfield.contains(pos) => 2,
+        PlayerPosition::UT => 1,
+        _ => 0,
+    }
+}
+
+/// Returns the team as it takes the field while it's players are injured, along with each bench player that was promoted and the starter they replace.
+/// Injured players are removed from the roster, and each injured starter is replaced by the healthy bench player that best fits their position.
+fn promote_bench(team: &Team) -> (Team, Vec<(String, String)>) {
+    let mut healthy = team.clone();
+    let mut promotions = Vec::new();
+    let injured: Vec<i64> = team.injured_list.iter().map(|x| x.player_id).collect();
+    let is_healthy = |player: &Player| !injured.contains(&player.player_id);
+    healthy.bench.retain(is_healthy);
+    healthy.starting_pitching.retain(is_healthy);
+    if let Some(pen) = healthy.bullpen.as_mut() {
+        pen.retain(is_healthy);
+    }
+    for starter in std::mem::take(&mut healthy.lineup) {
+        if is_healthy(&starter) {
+            healthy.lineup.push(starter);
+            continue;
+        }
+        let pos = fielding_pos(&starter);
+        let replacement = healthy
+            .bench
+            .iter()
+            .enumerate()
+            .max_by_key(|(_, player)| (fill_in_rank(pos, player), player.get_lineup_score()))
+            .map(|(index, _)| index);
+        if let Some(index) = replacement {
+            let player = healthy.bench.remove(index);
+            promotions.push((starter.name.clone(), player.name.clone()));
+            healthy.lineup.push(player);
+        }
+    }
+    healthy.calc_team_score();
+    (healthy, promotions)
+}
+
+/// Returns the team as it takes the field while it's players are injured, with a healthy bench player starting in place of each injured starter.
+/// The team score is calculated without the injured players.
+pub fn healthy_team(team: &Team) -> Team {
+    promote_bench(team).0
+}
+
+/// Returns the ids of the players on a team that play in every game, which are the healthy starters and starting pitchers.
+pub fn everyday_players(team: &Team) -> Vec<i64> {
+    let healthy = healthy_team(team);
+    healthy
+        .lineup
+        .iter()
+        .chain(healthy.starting_pitching.iter())
+        .map(|player| player.player_id)
+        .collect()
+}
+
+/// After a team plays a game, counts the game against the team's injuries, then rolls to see if one of the players in the game was injured.
+/// appeared contains the ids of the players that took part in the game. Returns a description of each injury that healed or was suffered.
+pub fn after_game<R: Rng>(
+    conn: &mut Connection,
+    league: &mut League,
+    team_id: i64,
+    appeared: &[i64],
+    thread: &mut R,
+) -> Result<Vec<String>, EditLeagueError> {
+    let team_index = match league.team_id_hash.get(&team_id) {
+        Some(index) => *index,
+        None => return Ok(Vec::new()),
+    };
+    let team = &mut league.teams[team_index];
+    in_transaction(conn, |conn| {
+        let mut messages: Vec<String> = count_game(conn, team)?
+            .into_iter()
+            .map(|name| format!("{} has returned from injury for {}.", name, team.name))
+            .collect();
+        if thread.gen_range(1..=INJURY_DIE) != 1 || appeared.is_empty() {
+            return Ok(messages);
+        }
+        let player_id = appeared[thread.gen_range(0..appeared.len())];
+        let player = roster_with_spots(team)
+            .into_iter()
+            .map(|(_, player)| player)
+            .find(|player| player.player_id == player_id && !is_injured(team, player_id))
+            .cloned();
+        if let Some(player) = player {
+            let injury = injure_player(conn, &player, thread)?;
+            messages.push(format!("{} injury: {}", team.name, injury));
+            team.injured_list.push(injury);
+        }
+        Ok(messages)
+    })
+}
+
+/// Heals every injury for the players in a league, which happens during the offseason.
+pub fn heal_all_injuries(conn: &Connection, league: &mut League) -> Result<(), EditLeagueError> {
+    for team in league.teams.iter_mut() {
+        for injury in team.injured_list.drain(..) {
+            handle_sql_error(conn.execute(
+                "DELETE FROM injuries WHERE injury_id = ?1",
+                [injury.injury_id],
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// Removes any injury for a player that is leaving the players table.
+pub fn delete_player_injuries(conn: &Connection, player_id: i64) -> Result<(), EditLeagueError> {
+    handle_sql_error(conn.execute("DELETE FROM injuries WHERE player_id = ?1", [player_id]))?;
+    Ok(())
+}
+
+/// Returns the injured list of a team as a string, including who is starting in place of each injured starter, or None if no one is injured.
+pub fn injured_list_string(team: &Team) -> Option<String> {
+    if team.injured_list.is_empty() {
+        return None;
+    }
+    let (_, promotions) = promote_bench(team);
+    let mut chars = "Injured List:".to_string();
+    for injury in team.injured_list.iter() {
+        chars.push_str(&format!("\n{}", injury));
+        if let Some((_, replacement)) = promotions
+            .iter()
+            .find(|(starter, _)| *starter == injury.player_name)
+        {
+            chars.push_str(&format!(", replaced in the lineup by {}", replacement));
+        }
+    }
+    Some(chars)
+}
+
+/// Returns a report of every injured player in a league.
+pub fn injury_report(league: &League) -> String {
+    let lists: Vec<String> = league
+        .teams
+        .iter()
+        .filter_map(|team| injured_list_string(team).map(|list| format!("{}\n{}", team.name, list)))
+        .collect();
+    match lists.is_empty() {
+        true => "No players in the league are injured.".to_string(),
+        false => format!("Injuries\n{}", lists.join("\n\n")),
+    }
+}
+
+/// The choices for what to do with a league's injuries.
+#[derive(Debug, Clone, Copy)]
+enum InjuryChoice {
+    View,
+    Roll,
+}
+
+impl fmt::Display for InjuryChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chars = match self {
+            Self::View => "View a team's injured list.",
+            Self::Roll => "Roll for an injury.",
+        };
+        write!(f, "{}", chars)
+    }
+}
+
+/// Prompts the user to view a team's injured list, or to choose a team and one of it's healthy players and roll on the injury table for the player.
+pub fn injury_from_input<R: Rng>(
+    league: &mut League,
+    conn: &mut Connection,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    let action = match Select::new(
+        "What would you like to do?",
+        vec![InjuryChoice::View, InjuryChoice::Roll],
+    )
+    .prompt()
+    {
+        Ok(choice) => choice,
+        Err(message) => return inquire_check(message),
+    };
+    let team_prompt = match action {
+        InjuryChoice::View => "Which team's injured list would you like to view?",
+        InjuryChoice::Roll => "Which team has a newly injured player?",
+    };
+    let team_names: Vec<String> = league.teams.iter().map(|team| team.name.clone()).collect();
+    let team_index = match Select::new(team_prompt, team_names).raw_prompt() {
+        Ok(choice) => choice.index,
+        Err(message) => return inquire_check(message),
+    };
+    if let InjuryChoice::View = action {
+        let team = &league.teams[team_index];
+        match injured_list_string(team) {
+            Some(list) => println!("{}\n{}", team.name, list),
+            None => println!("{} does not have any injured players.", team.name),
+        }
+        return Ok(());
+    }
+    let team = &league.teams[team_index];
+    let healthy: Vec<&Player> = roster_with_spots(team)
+        .into_iter()
+        .map(|(_, player)| player)
+        .filter(|player| !is_injured(team, player.player_id))
+        .collect();
+    let options: Vec<String> = healthy
+        .iter()
+        .map(|player| format!("{} {}", player.name, player.pos))
+        .collect();
+    let choice = match Select::new("Which player was injured?", options)
+        .with_page_size(10)
+        .raw_prompt()
+    {
+        Ok(choice) => choice,
+        Err(message) => return inquire_check(message),
+    };
+    let player = healthy[choice.index].clone();
+    let injury = injure_player(conn, &player, thread)?;
+    println!("{}", injury);
+    league.teams[team_index].injured_list.push(injury);
+    save_league(league);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::era::Era;
+    use crate::league::load_teams_from_sql;
+    use crate::offseason::run_offseason_with_retirements;
+    use crate::player::PlayerGender;
+    use crate::test_league;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn player_injuries() {
+        let (mut conn, mut league) = test_league(Era::Modern, PlayerGender::Male, 10);
+        // Players with T+ roll twice on the injury table and keep the lower roll, so they suffer fewer serious injuries.
+        let mut tough = league.teams[0].lineup[0].clone();
+        let mut frail = tough.clone();
+        tough.b_traits.toughness = Toughness::T1;
+        frail.b_traits.toughness = Toughness::T0;
+        let mut seeded = StdRng::seed_from_u64(22);
+        let minor_count = |player: &Player, thread: &mut StdRng| {
+            (0..1000)
+                .filter(|_| roll_injury_table(player, thread) == InjurySeverity::Minor)
+                .count()
+        };
+        assert!(minor_count(&tough, &mut seeded) > minor_count(&frail, &mut seeded));
+        // An injured starter is replaced in the lineup by a bench player that can play their position.
+        let shortstop = league.teams[0]
+            .lineup
+            .iter()
+            .find(|x| x.pos == PlayerPosition::SS)
+            .unwrap()
+            .clone();
+        let mut injury = injure_player(&conn, &shortstop, &mut seeded).unwrap();
+        injury.games_remaining = Some(2);
+        conn.execute(
+            "UPDATE injuries SET games_remaining = 2 WHERE injury_id = ?1",
+            [injury.injury_id],
+        )
+        .unwrap();
+        let mut loaded = League::new(
+            &league.name,
+            league.gender,
+            league.era,
+            league.league_id,
+            None,
+        );
+        load_teams_from_sql(league.league_id, &mut loaded, &mut conn).unwrap();
+        assert_eq!(loaded.teams[0].injured_list.len(), 1);
+        assert!(loaded.teams[1].injured_list.is_empty());
+        league.teams[0].injured_list.push(injury);
+        let healthy = healthy_team(&league.teams[0]);
+        assert_eq!(healthy.lineup.len(), 8);
+        assert_eq!(healthy.bench.len(), 4);
+        let shortstop_id = shortstop.player_id;
+        assert!(healthy.lineup.iter().all(|x| x.player_id != shortstop_id));
+        assert!(healthy.lineup.iter().any(|x| x.pos == PlayerPosition::INF));
+        assert!(healthy.team_score <= league.teams[0].team_score);
+        let team_file = league.teams[0].to_string();
+        assert!(team_file.contains("Injured List:"));
+        assert!(team_file.contains("replaced in the lineup by"));
+        assert!(injury_report(&league).contains(&shortstop.name));
+        // Each game a team plays counts against it's injuries.
+        let team_id = league.teams[0].team_id;
+        after_game(&mut conn, &mut league, team_id, &[], &mut seeded).unwrap();
+        assert_eq!(league.teams[0].injured_list[0].games_remaining, Some(1));
+        let messages = after_game(&mut conn, &mut league, team_id, &[], &mut seeded).unwrap();
+        assert!(messages[0].contains("has returned from injury"));
+        assert!(league.teams[0].injured_list.is_empty());
+        let count = |conn: &Connection| -> i64 {
+            conn.query_row("SELECT COUNT(*) FROM injuries", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(count(&conn), 0);
+        // Every injury heals over the offseason.
+        let injury = injure_player(&conn, &shortstop, &mut seeded).unwrap();
+        league.teams[0].injured_list.push(injury);
+        run_offseason_with_retirements(&mut conn, &mut league, &mut seeded).unwrap();
+        assert_eq!(count(&conn), 0);
+        assert!(league.teams[0].injured_list.is_empty());
+    }
+}
@@ -0,0 +1,527 @@
+use core::fmt;
+
+use crate::edit_league_error::handle_inquire_error;
+use crate::edit_league_error::handle_serde_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::game_result::get_game;
+use crate::game_result::get_series_games;
+use crate::game_result::save_game_note;
+use crate::in_transaction;
+use crate::inquire_check;
+use crate::league::save_league;
+use crate::league::League;
+use crate::note::Notable;
+use crate::note::Note;
+use crate::sched_view::select_series;
+use crate::trade::roster_with_spots;
+use crate::update_player_db::PlayerColumn;
+use crate::update_player_db::UpdatePlayerDb;
+use chrono::{Datelike, Local};
+use inquire::{Select, Text};
+use rusqlite::Connection;
+
+/* The journal lets users keep a storyline for their league by writing notes on the league, its teams, players and games.
+A note can be written, which attaches a new note or replaces the old one, appended to, or cleared.
+The note is saved on the league, team, player or game it belongs to, and every change is also saved in the note_log table.
+The note log is used to show a league's timeline, which lists every note written in the league in the order they were written.*/
+
+/// What a note is written on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NoteSubject {
+    League,
+    Team(i64),
+    Player(i64),
+    Game(i64),
+}
+
+impl NoteSubject {
+    /// Returns the type of the subject, which is saved in the note log.
+    fn type_name(&self) -> &str {
+        match self {
+            Self::League => "League",
+            Self::Team(_) => "Team",
+            Self::Player(_) => "Player",
+            Self::Game(_) => "Game",
+        }
+    }
+}
+
+/// A change to a note.
+#[derive(Debug, Clone)]
+pub enum NoteEdit {
+    /// Attaches a new note, replacing the old note if there is one.
+    Write(String),
+    /// Adds text to the end of the note.
+    Append(String),
+    /// Removes the note.
+    Clear,
+}
+
+impl NoteEdit {
+    /// Returns the note after the edit is made, along with how the note was changed.
+    fn apply(&self, note: &Note) -> (Note, &'static str) {
+        match (self, note) {
+            (Self::Write(text), None) | (Self::Append(text), None) => {
+                (Some(text.clone()), "Attached")
+            }
+            (Self::Write(text), Some(_)) => (Some(text.clone()), "Edited"),
+            (Self::Append(text), Some(old)) => (Some(format!("{} {}", old, text)), "Appended"),
+            (Self::Clear, _) => (None, "Cleared"),
+        }
+    }
+
+    /// Returns the text that is saved in the note log.
+    fn logged_text(&self) -> Note {
+        match self {
+            Self::Write(text) | Self::Append(text) => Some(text.clone()),
+            Self::Clear => None,
+        }
+    }
+}
+
+/// A note in a league's timeline.
+#[derive(Debug)]
+pub struct NoteLogEntry {
+    pub subject_type: String,
+    pub subject_name: String,
+    pub action: String,
+    pub note: Note,
+    pub date_written: Option<String>,
+}
+
+impl fmt::Display for NoteLogEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let date = self.date_written.as_deref().unwrap_or("Undated");
+        let subject = format!("{} ({})", self.subject_name, self.subject_type);
+        match &self.note {
+            Some(text) => write!(f, "{} {}: {} \"{}\"", date, subject, self.action, text),
+            None => write!(f, "{} {}: {} the note", date, subject, self.action),
+        }
+    }
+}
+
+/// The name and current note of what a note is written on.
+struct SubjectNote {
+    name: String,
+    note: Note,
+}
+
+impl Notable for SubjectNote {
+    fn get_note(&self) -> &Note {
+        &self.note
+    }
+
+    fn get_note_input_string(&self) -> String {
+        format!("Please enter the note for {}", self.name)
+    }
+}
+
+/// Returns the name and current note of the subject of a note, or an error if the subject is not part of the league.
+fn find_subject(
+    conn: &Connection,
+    league: &League,
+    subject: NoteSubject,
+) -> Result<SubjectNote, EditLeagueError> {
+    let missing = |description: &str, id: i64| {
+        Err(EditLeagueError::NoteError(format!(
+            "There is no {} with the id {} in {}.",
+            description, id, league.name
+        )))
+    };
+    let found = |name: String, note: &Note| {
+        Ok(SubjectNote {
+            name,
+            note: note.clone(),
+        })
+    };
+    match subject {
+        NoteSubject::League => found(league.name.clone(), &league.note),
+        NoteSubject::Team(team_id) => match league.teams.iter().find(|x| x.team_id == team_id) {
+            Some(team) => found(team.name.clone(), &team.note),
+            None => missing("team", team_id),
+        },
+        NoteSubject::Player(player_id) => {
+            let player = league.teams.iter().find_map(|team| {
+                roster_with_spots(team)
+                    .into_iter()
+                    .find(|(_, player)| player.player_id == player_id)
+                    .map(|(_, player)| (team, player))
+            });
+            match player {
+                Some((team, player)) => {
+                    found(format!("{} ({})", player.name, team.abrv), &player.note)
+                }
+                None => missing("player", player_id),
+            }
+        }
+        NoteSubject::Game(game_id) => {
+            let game = handle_sql_error(get_game(conn, game_id))?;
+            let abrv = |team_id: i64| {
+                league
+                    .teams
+                    .iter()
+                    .find(|x| x.team_id == team_id)
+                    .map(|team| team.abrv.clone())
+            };
+            match game.map(|game| (abrv(game.away_team_id), abrv(game.home_team_id), game)) {
+                Some((Some(away), Some(home), game)) => {
+                    found(format!("Game {}: {} @ {}", game_id, away, home), &game.note)
+                }
+                _ => missing("game", game_id),
+            }
+        }
+    }
+}
+
+/// Saves a note on the league, team, player or game it belongs to.
+fn save_subject_note(
+    conn: &mut Connection,
+    league: &League,
+    subject: NoteSubject,
+    note: &Note,
+) -> Result<(), EditLeagueError> {
+    match subject {
+        NoteSubject::League => {
+            let league_note = handle_serde_error(serde_json::to_value(note))?;
+            handle_sql_error(conn.execute(
+                "UPDATE leagues SET league_note = ?1 WHERE league_id = ?2",
+                (league_note, league.league_id),
+            ))?;
+        }
+        NoteSubject::Team(team_id) => {
+            let team_note = handle_serde_error(serde_json::to_value(note))?;
+            handle_sql_error(conn.execute(
+                "UPDATE teams SET team_note = ?1 WHERE team_id = ?2",
+                (team_note, team_id),
+            ))?;
+        }
+        NoteSubject::Player(player_id) => {
+            handle_sql_error(
+                PlayerColumn::new("player_note", note).update_player_db(conn, player_id),
+            )?;
+        }
+        NoteSubject::Game(game_id) => {
+            if let Some(game) = handle_sql_error(get_game(conn, game_id))? {
+                handle_sql_error(save_game_note(conn, &game, note))?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Changes the note on a league, team, player or game, and saves the change in the note log. Returns the new note.
+pub fn write_note(
+    conn: &mut Connection,
+    league: &mut League,
+    subject: NoteSubject,
+    edit: &NoteEdit,
+) -> Result<Note, EditLeagueError> {
+    let found = find_subject(conn, league, subject)?;
+    let (note, action) = edit.apply(&found.note);
+    in_transaction(conn, |conn| {
+        save_subject_note(conn, league, subject, &note)?;
+        let subject_id = match subject {
+            NoteSubject::League => league.league_id,
+            NoteSubject::Team(id) | NoteSubject::Player(id) | NoteSubject::Game(id) => id,
+        };
+        let now = Local::now();
+        let date_written = format!("{}_{}_{}", now.year(), now.month(), now.day());
+        handle_sql_error(conn.execute(
+            "INSERT INTO note_log(league_id, subject_type, subject_id, subject_name, action, note, date_written) VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            (
+                league.league_id,
+                subject.type_name(),
+                subject_id,
+                &found.name,
+                action,
+                edit.logged_text(),
+                date_written,
+            ),
+        ))?;
+        Ok(())
+    })?;
+    // The league is updated to match the database, so the note shows up in the league's text files.
+    match subject {
+        NoteSubject::League => league.note = note.clone(),
+        NoteSubject::Team(team_id) => {
+            if let Some(team) = league.teams.iter_mut().find(|x| x.team_id == team_id) {
+                team.note = note.clone();
+            }
+        }
+        NoteSubject::Player(player_id) => {
+            let player = league
+                .teams
+                .iter_mut()
+                .flat_map(|team| {
+                    team.lineup
+                        .iter_mut()
+                        .chain(team.bench.iter_mut())
+                        .chain(team.starting_pitching.iter_mut())
+                        .chain(team.bullpen.iter_mut().flatten())
+                })
+                .find(|player| player.player_id == player_id);
+            if let Some(player) = player {
+                player.note = note.clone();
+            }
+        }
+        NoteSubject::Game(_) => {}
+    }
+    Ok(note)
+}
+
+/// Loads every note written in a league, in the order they were written.
+pub fn load_timeline(
+    conn: &Connection,
+    league_id: i64,
+) -> Result<Vec<NoteLogEntry>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT subject_type, subject_name, action, note, date_written
+        FROM note_log
+        WHERE league_id = ?1
+        ORDER BY note_log_id ASC",
+    )?;
+    let rows = stmt.query_map([league_id], |row| {
+        Ok(NoteLogEntry {
+            subject_type: row.get(0)?,
+            subject_name: row.get(1)?,
+            action: row.get(2)?,
+            note: row.get(3)?,
+            date_written: row.get(4)?,
+        })
+    })?;
+    rows.collect()
+}
+
+/// Returns a league's timeline as a string.
+pub fn timeline_string(conn: &Connection, league: &League) -> Result<String, EditLeagueError> {
+    let timeline = handle_sql_error(load_timeline(conn, league.league_id))?;
+    let lines: Vec<String> = timeline.iter().map(|entry| entry.to_string()).collect();
+    match lines.is_empty() {
+        true => Ok(format!("No notes have been written in {}.", league.name)),
+        false => Ok(format!("{} Timeline:\n{}", league.name, lines.join("\n"))),
+    }
+}
+
+/// The choices for what to do in the journal.
+#[derive(Debug, Clone, Copy)]
+enum JournalChoice {
+    League,
+    Team,
+    Player,
+    Game,
+    Timeline,
+}
+
+impl fmt::Display for JournalChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chars = match self {
+            Self::League => "Write a note on the league.",
+            Self::Team => "Write a note on a team.",
+            Self::Player => "Write a note on a player.",
+            Self::Game => "Write a note on a game.",
+            Self::Timeline => "View the league's timeline.",
+        };
+        write!(f, "{}", chars)
+    }
+}
+
+/// The ways a note can be changed from the menu.
+#[derive(Debug, Clone, Copy)]
+enum NoteEditChoice {
+    Write,
+    Append,
+    Clear,
+}
+
+impl fmt::Display for NoteEditChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chars = match self {
+            Self::Write => "Write a new note.",
+            Self::Append => "Add to the end of the note.",
+            Self::Clear => "Clear the note.",
+        };
+        write!(f, "{}", chars)
+    }
+}
+
+/// Prompts the user to choose what the note will be written on. Returns None if there is nothing to choose from.
+fn subject_from_input(
+    conn: &Connection,
+    league: &League,
+    choice: JournalChoice,
+) -> Result<Option<NoteSubject>, EditLeagueError> {
+    let team_names: Vec<String> = league.teams.iter().map(|team| team.name.clone()).collect();
+    match choice {
+        JournalChoice::League | JournalChoice::Timeline => Ok(Some(NoteSubject::League)),
+        JournalChoice::Team | JournalChoice::Player => {
+            let team_index = handle_inquire_error(
+                Select::new("Which team would you like to choose?", team_names).raw_prompt(),
+            )?
+            .index;
+            let team = &league.teams[team_index];
+            if let JournalChoice::Team = choice {
+                return Ok(Some(NoteSubject::Team(team.team_id)));
+            }
+            let roster = roster_with_spots(team);
+            let options: Vec<String> = roster
+                .iter()
+                .map(|(_, player)| player.to_string())
+                .collect();
+            let choice = handle_inquire_error(
+                Select::new("Which player would you like to write about?", options)
+                    .with_page_size(10)
+                    .raw_prompt(),
+            )?;
+            Ok(Some(NoteSubject::Player(roster[choice.index].1.player_id)))
+        }
+        JournalChoice::Game => {
+            let series = match select_series(league, conn)? {
+                Some(series) => series,
+                None => return Ok(None),
+            };
+            let games = handle_sql_error(get_series_games(conn, series.series_id))?;
+            let options: Vec<String> = games.iter().map(|game| game.to_string()).collect();
+            let choice = handle_inquire_error(
+                Select::new("Which game would you like to write about?", options).raw_prompt(),
+            )?;
+            Ok(Some(NoteSubject::Game(games[choice.index].game_id)))
+        }
+    }
+}
+
+/// Prompts the user for a change to a note.
+fn note_edit_from_input(found: &SubjectNote) -> Result<NoteEdit, EditLeagueError> {
+    let options = match found.get_note() {
+        Some(_) => vec![
+            NoteEditChoice::Write,
+            NoteEditChoice::Append,
+            NoteEditChoice::Clear,
+        ],
+        None => vec![NoteEditChoice::Write],
+    };
+    let choice = handle_inquire_error(Select::new("What would you like to do?", options).prompt())?;
+    match choice {
+        NoteEditChoice::Clear => Ok(NoteEdit::Clear),
+        NoteEditChoice::Write => {
+            // Writing an empty note is the same as clearing it.
+            match handle_inquire_error(found.generate_note())? {
+                Some(text) if !text.trim().is_empty() => Ok(NoteEdit::Write(text)),
+                _ => Ok(NoteEdit::Clear),
+            }
+        }
+        NoteEditChoice::Append => {
+            let text = handle_inquire_error(
+                Text::new("Please enter the text to add to the note.").prompt(),
+            )?;
+            Ok(NoteEdit::Append(text))
+        }
+    }
+}
+
+/// Prompts the user to write a note on a league, team, player or game, or view the league's timeline.
+pub fn journal_from_input(
+    league: &mut League,
+    conn: &mut Connection,
+) -> Result<(), EditLeagueError> {
+    let options = vec![
+        JournalChoice::League,
+        JournalChoice::Team,
+        JournalChoice::Player,
+        JournalChoice::Game,
+        JournalChoice::Timeline,
+    ];
+    let choice = match Select::new("What would you like to do?", options).prompt() {
+        Ok(choice) => choice,
+        Err(message) => return inquire_check(message),
+    };
+    if let JournalChoice::Timeline = choice {
+        println!("{}", timeline_string(conn, league)?);
+        return Ok(());
+    }
+    let result = subject_from_input(conn, league, choice).and_then(|subject| match subject {
+        Some(subject) => {
+            let found = find_subject(conn, league, subject)?;
+            match found.get_note() {
+                Some(text) => println!("The note for {} is: {}", found.name, text),
+                None => println!("{} does not have a note.", found.name),
+            }
+            let edit = note_edit_from_input(&found)?;
+            write_note(conn, league, subject, &edit).map(Some)
+        }
+        None => Ok(None),
+    });
+    match result {
+        Ok(Some(_)) => {
+            println!("The note has been saved.");
+            save_league(league);
+            Ok(())
+        }
+        Ok(None) => Ok(()),
+        Err(EditLeagueError::Inquire(message)) => inquire_check(message),
+        Err(message) => Err(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::era::Era;
+    use crate::league::{get_all_leagues_from_db, load_teams_from_sql};
+    use crate::player::PlayerGender;
+    use crate::schedule::{new_schedule, schedule_to_sql};
+    use crate::test_league;
+
+    #[test]
+    fn note_journal() {
+        let (mut conn, mut league) = test_league(Era::Ancient, PlayerGender::Male, 25);
+        let sched = new_schedule(&league.teams, 1, 1);
+        schedule_to_sql(&mut conn, &league, sched).unwrap();
+        let game_id: i64 = conn
+            .query_row("SELECT MIN(game_id) FROM games", [], |row| row.get(0))
+            .unwrap();
+        let team_id = league.teams[0].team_id;
+        let player_id = league.teams[1].lineup[0].player_id;
+        let write = |text: &str| NoteEdit::Write(text.to_string());
+        let append = |text: &str| NoteEdit::Append(text.to_string());
+        let edits = [
+            (NoteSubject::League, write("Opening day.")),
+            (NoteSubject::Team(team_id), write("Picked to win.")),
+            (NoteSubject::Team(team_id), append("Or not.")),
+            (NoteSubject::Player(player_id), write("Holdout.")),
+            (NoteSubject::Player(player_id), NoteEdit::Clear),
+            (NoteSubject::Game(game_id), write("Rain delay.")),
+        ];
+        for (subject, edit) in edits.iter() {
+            write_note(&mut conn, &mut league, *subject, edit).unwrap();
+        }
+        // Notes can only be written on things that are in the league.
+        let missing = NoteSubject::Player(-1);
+        assert!(matches!(
+            write_note(&mut conn, &mut league, missing, &NoteEdit::Clear),
+            Err(EditLeagueError::NoteError(_))
+        ));
+        let team_note = Some("Picked to win. Or not.".to_string());
+        assert_eq!(league.teams[0].note, team_note);
+        let mut loaded = get_all_leagues_from_db(&mut conn).unwrap().remove(0).league;
+        load_teams_from_sql(loaded.league_id, &mut loaded, &mut conn).unwrap();
+        assert_eq!(loaded.note, Some("Opening day.".to_string()));
+        assert_eq!(loaded.teams[0].note, team_note);
+        assert_eq!(loaded.teams[1].lineup[0].note, None);
+        let game = get_game(&conn, game_id).unwrap().unwrap();
+        assert_eq!(game.note, Some("Rain delay.".to_string()));
+        // The timeline lists every change in the order it was made.
+        let timeline = load_timeline(&conn, league.league_id).unwrap();
+        let actions: Vec<&str> = timeline.iter().map(|x| x.action.as_str()).collect();
+        assert_eq!(
+            actions,
+            ["Attached", "Attached", "Appended", "Attached", "Cleared", "Attached"]
+        );
+        assert_eq!(timeline[2].subject_name, "Los Angeles Angels");
+        assert_eq!(timeline[2].note, Some("Or not.".to_string()));
+        assert_eq!(timeline[5].subject_type, "Game");
+        assert!(timeline_string(&conn, &league)
+            .unwrap()
+            .contains("Holdout."));
+    }
+}
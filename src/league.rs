@@ -4,21 +4,42 @@ use std::fs::exists;
 use std::fs::File;
 use std::io::Write;
 
+use crate::archive::archive_prospects;
+use crate::archive::archive_retired_players;
+use crate::archive::restore_archive_from_input;
 use crate::b_traits::BTraits;
+use crate::draft::draft_from_input;
 use crate::edit_league_error::handle_serde_error;
 use crate::edit_league_error::handle_sql_error;
 use crate::edit_league_error::EditLeagueError;
 use crate::era::select_era;
+use crate::free_agent::free_agency_from_input;
+use crate::free_agent::load_free_agents;
+use crate::game_sim::play_game_from_input;
+use crate::in_transaction;
+use crate::injury::injury_from_input;
 use crate::inquire_check;
+use crate::journal::journal_from_input;
 use crate::main_menu::EditLeagueInput;
 use crate::main_menu::LoadLeagueInput;
 use crate::main_menu::RankingsChoice;
+use crate::minor_leaguer::call_up_from_input;
+use crate::minor_leaguer::fill_farm;
 use crate::note::Notable;
 use crate::note::Note;
+use crate::offseason::offseason_from_input;
 use crate::pd::PD;
 use crate::pennantgen::generate_pennant_standings;
 use crate::pennantgen::PennantStanding;
 use crate::player::select_gender;
+use crate::player_editor::player_editor_from_input;
+use crate::quick_play::quick_play_from_input;
+use crate::result_entry::enter_results_from_input;
+use crate::roster_csv::export_roster_from_input;
+use crate::roster_csv::import_roster_from_input;
+use crate::roster_editor::roster_editor_from_input;
+use crate::season_standings::view_season_standings;
+use crate::settings::output_path;
 use chrono::{Datelike, Local};
 use inquire::validator::MinLengthValidator;
 use inquire::Confirm;
@@ -27,7 +48,7 @@ use inquire::InquireError;
 use inquire::Select;
 use inquire::Text;
 use rand::prelude::*;
-use std::path::Path;
+use rand::rngs::StdRng;
 
 use rusqlite::Result;
 
@@ -45,6 +66,7 @@ use crate::sched_view::view_schedule;
 use crate::schedule::save_schedule_sql;
 use crate::team::add_new_team;
 use crate::team::load_team;
+use crate::trade::trade_from_input;
 use crate::vec_to_hash;
 use crate::Deserialize;
 use crate::Era;
@@ -131,10 +153,17 @@ pub struct League {
     pub teams: Vec<Team>,
     pub gender: PlayerGender,
     pub era: Era,
+    // Ids and lookup hashes are filled in when a league is loaded, so a hand written league file can leave them out.
+    #[serde(default)]
     pub league_id: i64, //bench_quality:BatterQuality,
     pub note: Note,
-    pub team_id_hash: HashMap<i64,usize>,
-    pub team_abrv_hash: HashMap<String,usize>
+    #[serde(default)]
+    pub team_id_hash: HashMap<i64, usize>,
+    #[serde(default)]
+    pub team_abrv_hash: HashMap<String, usize>,
+    /// The seed used to generate the league's teams. Leagues created before seeds were added do not have one.
+    #[serde(default)]
+    pub seed: Option<u64>,
 }
 
 impl Notable for League {
@@ -165,6 +194,15 @@ impl fmt::Display for EditLeagueError {
             Self::DatabaseError(message) => message.to_string(),
             Self::SerdeError(message) => message.to_string(),
             Self::Inquire(message) => message.to_string(),
+            Self::PennantError(message)
+            | Self::GameError(message)
+            | Self::CommandError(message)
+            | Self::ImportError(message)
+            | Self::SchemaError(message)
+            | Self::RosterError(message)
+            | Self::InvalidPlayer(message)
+            | Self::FileError(message)
+            | Self::NoteError(message) => message.to_string(),
             _ => "Invalid Team and/or Abbreviation".to_string(),
         };
         write!(f, "{}", text)
@@ -179,7 +217,13 @@ struct StandingWrapper {
     games_behind: i32,
 }
 impl League {
-    pub fn new(name: &String, gender: PlayerGender, era: Era, league_id: i64) -> League {
+    pub fn new(
+        name: &String,
+        gender: PlayerGender,
+        era: Era,
+        league_id: i64,
+        seed: Option<u64>,
+    ) -> League {
         League {
             name: name.to_string(),
             teams: Vec::new(),
@@ -188,22 +232,37 @@ impl League {
             league_id,
             note: None,
             team_id_hash: HashMap::new(),
-            team_abrv_hash: HashMap::new()
+            team_abrv_hash: HashMap::new(),
+            seed,
         }
     }
 
-    // Adds a created team into the leagues team vector and lookup hash maps.
-    pub fn add_team(&mut self, new_team: Team){
+    /// Returns a random number generator for the next team added to the league.
+    /// If the league has a seed, each team's seed is drawn in turn from a generator seeded with the league seed, so the same seed always creates the same teams.
+    /// Drawing the team seeds keeps leagues with neighbouring seeds from sharing teams.
+    pub fn team_rng(&self) -> StdRng {
+        match self.seed {
+            Some(seed) => {
+                let mut league_rng = StdRng::seed_from_u64(seed);
+                let team_seed = std::iter::repeat_with(|| league_rng.gen::<u64>())
+                    .nth(self.teams.len())
+                    .unwrap();
+                StdRng::seed_from_u64(team_seed)
+            }
+            None => StdRng::from_entropy(),
+        }
+    }
 
+    // Adds a created team into the leagues team vector and lookup hash maps.
+    pub fn add_team(&mut self, new_team: Team) {
         let team_id = new_team.team_id;
         let team_abrv = new_team.abrv.clone();
         // And add the team to the league's teams vector.
         self.teams.push(new_team);
         // We calculate where in the vector the team has been loaded by subtracting 1 from the length.
         let teams_index = self.teams.len() - 1;
-        self.team_id_hash.insert(team_id,teams_index);
-        self.team_abrv_hash.insert(team_abrv,teams_index);
-
+        self.team_id_hash.insert(team_id, teams_index);
+        self.team_abrv_hash.insert(team_abrv, teams_index);
     }
     /*pub fn get_new_schedule(&self, series_length: i32,series_per_matchup:i32) ->  Result<Vec<Round>, ScheduleGenError>{
         new_schedule(&self.teams,  series_length, series_per_matchup)
@@ -707,11 +766,11 @@ impl League {
     /*  Take a new abbreviation and name for a team, a thread for random number, a league id and connection to the the database.
         If there are no teams in the league that have the same name or abbreviation,  we attempt to add the team to the league. If it succeeds, an Ok is returned
     */
-    pub fn new_team(
+    pub fn new_team<R: Rng>(
         &mut self,
         new_abrv: &String,
         new_name: &String,
-        thread: &mut ThreadRng,
+        thread: &mut R,
         league_id: i64,
         conn: &mut Connection,
     ) -> Result<(), EditLeagueError> {
@@ -725,26 +784,25 @@ impl League {
         // We create a new team
         let mut new_team = Team::new(new_abrv, new_name, self.gender, self.era, thread);
         // We get the team score for hte new team.
-        // We enter the team into the database.
-        let team_enter_result = conn.execute(
-            "INSERT INTO teams(team_name,abrv, league_id) VALUES(?1,?2, ?3)",
-            [new_name, new_abrv, &league_id.to_string()],
-        );
-        // We save the team ID, so that we we generate the new players they can be saved in the database with the league id as the foreign key.
-        let new_team_id = conn.last_insert_rowid();
-        // And we set the new teams ID to it.
-        new_team.team_id = new_team_id;
-     
-        match team_enter_result {
-            Ok(_) => (),
-            Err(message) => return Err(EditLeagueError::DatabaseError(message)),
-        };
-        //If all has gone well, we save the players that have been generated into the database
-
-        new_team.save_players_sql(conn, new_team_id)?;
+        // We enter the team and its players into the database in a single transaction, so a team is never saved with only part of its roster.
+        in_transaction(conn, |conn| {
+            handle_sql_error(conn.execute(
+                "INSERT INTO teams(team_name,abrv, league_id) VALUES(?1,?2, ?3)",
+                [new_name, new_abrv, &league_id.to_string()],
+            ))?;
+            // We save the team ID, so that we we generate the new players they can be saved in the database with the league id as the foreign key.
+            let new_team_id = conn.last_insert_rowid();
+            // And we set the new teams ID to it.
+            new_team.team_id = new_team_id;
+            //If all has gone well, we save the players that have been generated into the database
+            new_team.save_players_sql(conn, new_team_id)?;
+            // As well as the team's farm system.
+            fill_farm(conn, new_team_id, self.gender, self.era, thread)?;
+            Ok(())
+        })?;
         // And we load the new team into the league
         self.add_team(new_team);
-        
+
         Ok(())
     }
 
@@ -755,42 +813,32 @@ impl League {
         }
         result
     }
+    /// Returns an error if the league does not have enough teams to generate a pennant race.
+    pub fn pennant_team_check(&self) -> Result<(), EditLeagueError> {
+        match self.teams.len() <= 3 {
+            true => Err(EditLeagueError::PennantError(
+                "Error: A league must have 4 or more teams in order to generate a pennant."
+                    .to_string(),
+            )),
+            false => Ok(()),
+        }
+    }
     /// Creates a randomly generated pennant race that is saved to the database and is also exported as a file.
-    pub fn create_pennant_race(
+    pub fn create_pennant_race<R: Rng>(
         &self,
-        thread: &mut ThreadRng,
+        thread: &mut R,
         conn: &mut Connection,
         games_played: i32,
     ) -> Result<(), EditLeagueError> {
         // First, we check to see if there are already any pennant races associated with the league.
 
-        let existing_stmt = conn.prepare(
-            "
-            SELECT 
-                COUNT(pennants.pennant_id)
-            FROM 
-                pennants
-            WHERE
-                pennants.league_id = ?1
-        
-        
-        ",
-        );
-        let mut pennant_stmt = match existing_stmt {
-            Ok(value) => value,
-            Err(message) => return Err(EditLeagueError::DatabaseError(message)),
-        };
-        let pennants_iter = pennant_stmt.query_map([self.league_id], |row| {
-            let num: i32 = row.get(0).unwrap();
-            Ok(num)
-        });
-
-        let count: i32 = handle_sql_error(pennants_iter)?
-            .filter_map(|x| x.ok())
-            .next()
-            .unwrap_or(0);
+        let count: i32 = handle_sql_error(conn.query_row(
+            "SELECT COUNT(pennants.pennant_id) FROM pennants WHERE pennants.league_id = ?1",
+            [self.league_id],
+            |row| row.get(0),
+        ))?;
         // And use it to determine the file name we will be writing to.
-        let file_name = format!("{}_Pennant_{}.txt", self.name, count);
+        let file_name = output_path(&format!("{}_Pennant_{}.txt", self.name, count));
         // We map all teams to a vector of team pennant wrappers.
         let mut pennant_wrappers: Vec<TeamPennantWrapper> = self
             .teams
@@ -837,44 +885,54 @@ impl League {
         let input_standings = generate_pennant_standings(games_played, thread, target_len as i32)?;
 
         let final_standings = team_ranks.iter().zip(input_standings);
-        handle_sql_error(conn.execute(
-            "INSERT INTO pennants(league_id) VALUES(?1)",
-            [self.league_id],
-        ))?;
-        let pennant_id = conn.last_insert_rowid();
-
-        let mut file_string = "Name,Wins,Losses, Games Behind,Team Score".to_string();
-        let mut top_wins = None;
-        for (i, standing) in final_standings.rev().enumerate() {
-            let (pennant_wrapper, win_loss) = standing;
-            let TeamPennantWrapper {
-                id,
-                name,
-                team_score,
-                ..
-            } = pennant_wrapper;
-            let PennantStanding { wins, losses } = win_loss;
-            if i == 0 {
-                top_wins = Some(wins)
-            };
-            let games_behind = top_wins.unwrap_or(wins) - wins;
-            let standing_line = format!(
-                "\n{},{},{},{},{}",
-                name, wins, losses, games_behind, team_score
-            );
-            file_string.push_str(&standing_line);
+        // The pennant and its standings are saved in a single transaction, so a pennant is never saved without every team's standing.
+        let file_string = in_transaction(conn, |conn| {
             handle_sql_error(conn.execute(
-                "
-                INSERT INTO pennants_standings(team_id,pennant_id,wins,losses) VALUES(?1,?2,?3,?4)
-            
-            
-            ",
-                [id, &pennant_id, &(wins as i64), &(losses as i64)],
+                "INSERT INTO pennants(league_id) VALUES(?1)",
+                [self.league_id],
             ))?;
-        }
+            let pennant_id = conn.last_insert_rowid();
+            let mut standings_stmt = handle_sql_error(conn.prepare_cached(
+                "INSERT INTO pennants_standings(team_id,pennant_id,wins,losses) VALUES(?1,?2,?3,?4)",
+            ))?;
+
+            let mut file_string = "Name,Wins,Losses, Games Behind,Team Score".to_string();
+            let mut top_wins = None;
+            for (i, standing) in final_standings.rev().enumerate() {
+                let (pennant_wrapper, win_loss) = standing;
+                let TeamPennantWrapper {
+                    id,
+                    name,
+                    team_score,
+                    ..
+                } = pennant_wrapper;
+                let PennantStanding { wins, losses } = win_loss;
+                if i == 0 {
+                    top_wins = Some(wins)
+                };
+                let games_behind = top_wins.unwrap_or(wins) - wins;
+                let standing_line = format!(
+                    "\n{},{},{},{},{}",
+                    name, wins, losses, games_behind, team_score
+                );
+                file_string.push_str(&standing_line);
+                handle_sql_error(standings_stmt.execute([
+                    id,
+                    &pennant_id,
+                    &(wins as i64),
+                    &(losses as i64),
+                ]))?;
+            }
+            Ok(file_string)
+        })?;
         println!("{}", file_string);
-        fs::write(file_name, file_string).unwrap();
-        Ok(())
+        fs::write(&file_name, file_string).map_err(|message| {
+            EditLeagueError::FileError(format!(
+                "The pennant was saved, however it could not be written to {}. The error was {}",
+                file_name.display(),
+                message
+            ))
+        })
     }
     /// Creates a copy of the league in a Json object that is saved to a text file. The object is also saved in the database.
     pub fn create_json_archives(&self, conn: &mut Connection) -> Result<(), EditLeagueError> {
@@ -883,10 +941,23 @@ impl League {
         let now = Local::now();
         // We create a string for todays date
         let date_saved = format!("{}_{}_{}", now.year(), now.month(), now.day());
+        // The free agent pool is saved with the archive, so it can be restored when the league is rolled back.
+        let free_agents = load_free_agents(conn, self.league_id)?;
+        let free_agent_data = handle_serde_error(serde_json::to_string(&free_agents))?;
+        // The farm systems and retired players are saved as well, so they match the teams after a roll back.
+        let prospect_data = archive_prospects(conn, self.league_id)?;
+        let retired_data = archive_retired_players(conn, self.league_id)?;
         // We save the json string in the league archive table
         handle_sql_error(conn.execute(
-            "INSERT INTO league_archive(league_id,date_saved,league_data) VALUES(?1,?2,?3)",
-            [&self.league_id.to_string(), &date_saved, &json_string],
+            "INSERT INTO league_archive(league_id,date_saved,league_data,free_agent_data,prospect_data,retired_data) VALUES(?1,?2,?3,?4,?5,?6)",
+            [
+                &self.league_id.to_string(),
+                &date_saved,
+                &json_string,
+                &free_agent_data,
+                &prospect_data,
+                &retired_data,
+            ],
         ))?;
         // We convert the json string to bytes so we can write the file later.
         let j_u = json_string.as_bytes();
@@ -897,10 +968,17 @@ impl League {
         // We loop to ensure the archive is saved in a unique file name.
         loop {
             let path = match i {
-                0 => format!("{}.json", base),
-                _ => format!("{}_{}.json", base, i),
+                0 => output_path(&format!("{}.json", base)),
+                _ => output_path(&format!("{}_{}.json", base, i)),
             };
-            if exists(&path).unwrap() {
+            let taken = exists(&path).map_err(|message| {
+                EditLeagueError::FileError(format!(
+                    "Unable to check if {} exists. The error was {}",
+                    path.display(),
+                    message
+                ))
+            })?;
+            if taken {
                 i += 1
             } else {
                 file_name = path;
@@ -908,9 +986,15 @@ impl League {
             }
         }
         // And we save the file
-        let mut file = File::create(file_name).unwrap();
-        file.write_all(j_u).unwrap();
-        Ok(())
+        File::create(&file_name)
+            .and_then(|mut file| file.write_all(j_u))
+            .map_err(|message| {
+                EditLeagueError::FileError(format!(
+                    "The archive was saved to the database, however it could not be written to {}. The error was {}",
+                    file_name.display(),
+                    message
+                ))
+            })
     }
 }
 /// Checks database to make sure that a name is not already used by a league in the database.
@@ -936,6 +1020,23 @@ pub fn check_name_hash(conn: &Connection) -> Result<HashMap<String, bool>, rusql
     Ok(result)
 }
 
+/// Prompts the user for a seed for a new league. If the user leaves the prompt blank, a random seed is used.
+pub fn select_seed<R: Rng>(thread: &mut R) -> Result<u64, InquireError> {
+    loop {
+        let input =
+            Text::new("Please enter a seed for the league, or leave blank for a random seed.")
+                .prompt()?;
+        let trimmed = input.trim();
+        if trimmed.is_empty() {
+            return Ok(thread.gen());
+        }
+        match trimmed.parse::<u64>() {
+            Ok(seed) => return Ok(seed),
+            Err(_) => println!("A seed must be a positive whole number."),
+        }
+    }
+}
+
 // Creates a new leagues, and saves the league in the database
 pub fn create_new_league(
     thread: &mut ThreadRng,
@@ -975,6 +1076,11 @@ pub fn create_new_league(
         }
     }
 
+    // We have the user enter a seed for the league, so the league can be generated again.
+    let seed = match select_seed(thread) {
+        Ok(input) => input,
+        Err(message) => return inquire_check(message),
+    };
     // We have the user select the era for the league.
     let era_choice = select_era();
     let era = match era_choice {
@@ -987,41 +1093,37 @@ pub fn create_new_league(
         Ok(input) => input,
         Err(message) => return inquire_check(message),
     };
-    // We then create a league struct.
-
-    // We then serialize the era and gender to json.
-    let era_json = match serde_json::to_string(&era) {
-        Ok(data) => data,
-        Err(message) => return Err(EditLeagueError::SerdeError(message)),
-    };
+    // We then create the league in the database, in a single transaction with it's teams so a failed team does not leave a partial league behind.
+    in_transaction(conn, |conn| {
+        let mut new_league = insert_new_league(conn, &league_name, era, gender, seed)?;
+        let league_id = new_league.league_id;
+        println!("{} created with seed {}", &league_name, seed);
+        //And then prompt the user to create the first team for the league.
+        add_new_team(&mut new_league, conn, league_id, true)
+    })
+}
 
-    let gender_json = match serde_json::to_string(&gender) {
-        Ok(gender) => gender,
-        Err(message) => return Err(EditLeagueError::SerdeError(message)),
-    };
+/// Saves a new league in the database, and returns a league struct with the id the league was saved under.
+/// The league name is assumed to have already been checked against the names in the database.
+pub fn insert_new_league(
+    conn: &mut Connection,
+    league_name: &String,
+    era: Era,
+    gender: PlayerGender,
+    seed: u64,
+) -> Result<League, EditLeagueError> {
+    // We serialize the era and gender to json.
+    let era_json = handle_serde_error(serde_json::to_string(&era))?;
+    let gender_json = handle_serde_error(serde_json::to_string(&gender))?;
     // And we create a new entry in the sql database.
-    match conn.execute(
-        "INSERT INTO leagues(league_name,era,gender) VALUES(?1, ?2, ?3)",
-        [&league_name, &era_json, &gender_json],
-    ) {
-        Ok(_) => {}
-        Err(message) => return Err(EditLeagueError::DatabaseError(message)),
-    };
-
-    // if league_entry.is_err() {
-    // println!("Error creating a new league in the database.");
-    // return Ok(());
-    //};
+    handle_sql_error(conn.execute(
+        "INSERT INTO leagues(league_name,era,gender,seed) VALUES(?1, ?2, ?3, ?4)",
+        (league_name, &era_json, &gender_json, seed as i64),
+    ))?;
     // Via last_insert ow id, we get the SQl id for the new league, as the teams we generate will need it.
     let league_id = conn.last_insert_rowid();
     // We then create a league struct in rust.
-    let mut new_league = League::new(&league_name, gender, era, league_id);
-    println!("{} created", &league_name);
-    //And then prompt the user to create the first team for the league.
-    match add_new_team(&mut new_league, thread, conn, league_id, true) {
-        Ok(_) => Ok(()),
-        Err(message) => Err(message),
-    }
+    Ok(League::new(league_name, gender, era, league_id, Some(seed)))
 }
 
 /// Loads teams from SQL database and adds to league struct.
@@ -1062,6 +1164,7 @@ pub fn load_teams_from_sql(
 
                     team_score: 0,
                     note: serde_json::from_value(row.get(5)?).unwrap(),
+                    injured_list: Vec::new(),
                 },
             )
         }))?
@@ -1098,23 +1201,18 @@ pub fn load_league(
 
     // Now that we have loaded the existing league from the database, it is time to generate a new team or create a new schedule based off the input
     match edit_input {
-        EditLeagueInput::CreateNewTeam => add_new_team(&mut league, thread, conn, league_id, true)?,
+        EditLeagueInput::CreateNewTeam => add_new_team(&mut league, conn, league_id, true)?,
         EditLeagueInput::CreateSchedule => {
-            match league.teams.len() % 2 == 0 {
-                true => save_schedule_sql(conn, &league)?,
-                false => {
-                    println!("League must have an even number of teams");
-                    save_league(&league);
-                }
-            };
-        }
-        EditLeagueInput::GeneratePennant => {
-            if league.teams.len() <= 3 {
-                return Err(EditLeagueError::PennantError(
-                    "Error: A league must have 4 or more teams in order to generate a pennant."
+            if league.teams.len() < 2 {
+                return Err(EditLeagueError::GameError(
+                    "Error: A league must have 2 or more teams in order to generate a schedule."
                         .to_string(),
                 ));
             };
+            save_schedule_sql(conn, &league)?
+        }
+        EditLeagueInput::GeneratePennant => {
+            league.pennant_team_check()?;
             let games_played_input = CustomType::<i32>::new(
                 "Please enter how many games have been played in the season.",
             )
@@ -1126,6 +1224,20 @@ pub fn load_league(
             }
         }
         EditLeagueInput::CreateArchive => league.create_json_archives(conn)?,
+        EditLeagueInput::PlayGame => play_game_from_input(&mut league, conn, thread)?,
+        EditLeagueInput::QuickPlay => quick_play_from_input(&mut league, conn, thread)?,
+        EditLeagueInput::EnterResults => enter_results_from_input(&mut league, conn, thread)?,
+        EditLeagueInput::RestoreArchive => restore_archive_from_input(&league, conn, thread)?,
+        EditLeagueInput::ImportRoster => import_roster_from_input(&league, conn)?,
+        EditLeagueInput::RunOffseason => offseason_from_input(&mut league, conn, thread)?,
+        EditLeagueInput::CallUpProspect => call_up_from_input(&mut league, conn, thread)?,
+        EditLeagueInput::HoldDraft => draft_from_input(&mut league, conn, thread)?,
+        EditLeagueInput::MakeTrade => trade_from_input(&mut league, conn)?,
+        EditLeagueInput::FreeAgency => free_agency_from_input(&mut league, conn, thread)?,
+        EditLeagueInput::Injuries => injury_from_input(&mut league, conn, thread)?,
+        EditLeagueInput::EditRoster => roster_editor_from_input(&mut league, conn)?,
+        EditLeagueInput::EditPlayer => player_editor_from_input(&mut league, conn)?,
+        EditLeagueInput::Notes => journal_from_input(&mut league, conn)?,
     };
     Ok(())
 }
@@ -1152,7 +1264,9 @@ pub fn get_all_leagues_from_db(
                     league_id: row.get(0)?,
                     teams: Vec::new(),
                     team_abrv_hash: HashMap::new(),
-                    team_id_hash: HashMap::new()
+                    team_id_hash: HashMap::new(),
+                    // Seeds are stored as an integer in the database, so we cast it back to an unsigned number.
+                    seed: row.get::<usize, Option<i64>>(5)?.map(|seed| seed as u64),
                 },
             })
         })?
@@ -1197,6 +1311,8 @@ pub fn league_check(
                 }
                 LoadLeagueInput::ViewSchedule => view_schedule(&select.league, conn),
                 LoadLeagueInput::ViewRankings => select.league.display_ranking(conn),
+                LoadLeagueInput::ViewSeasonStandings => view_season_standings(&select.league, conn),
+                LoadLeagueInput::ExportRoster => export_roster_from_input(&select.league, conn),
             },
             Err(message) => inquire_check(message),
         }
@@ -1206,9 +1322,8 @@ pub fn league_check(
 // Once a league is saved, we save a copy of the league data in a folder.
 pub fn save_league_to_folders(league: &League) -> std::io::Result<()> {
     println!();
-    let folder_path_string = league.name.to_string();
-    let folder_path = Path::new(&folder_path_string);
-    fs::create_dir_all(folder_path)?;
+    let folder_path = output_path(&league.name);
+    fs::create_dir_all(&folder_path)?;
 
     for team in &league.teams {
         let file_path = folder_path.join(format!("{}.txt", team.name).as_str());
@@ -0,0 +1,205 @@
+use std::collections::HashSet;
+use std::fs;
+
+use crate::archive::insert_league_copy;
+use crate::archive::new_league_name;
+use crate::edit_league_error::handle_serde_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::era::Era;
+use crate::inquire_check;
+use crate::league::check_name_vec;
+use crate::league::save_league;
+use crate::league::League;
+use crate::pd::PD;
+use crate::player::Player;
+use crate::position::PlayerPosition;
+use crate::position::PositionCategory;
+use inquire::validator::MinLengthValidator;
+use inquire::Text;
+use rand::Rng;
+use rusqlite::Connection;
+
+/* Leagues can be imported from a JSON file, such as the LEAGUE_JSON.txt file in a league's folder, an archive, or a file written by hand.
+This lets groups share leagues without copying their database. Every player is checked before anything is saved, and the league is only imported if no problems are found.*/
+
+/// Reads a league from a JSON file.
+pub fn read_league_file(path: &str) -> Result<League, EditLeagueError> {
+    let contents = fs::read_to_string(path).map_err(|message| {
+        EditLeagueError::ImportError(format!(
+            "Unable to read {}. The error was {}",
+            path, message
+        ))
+    })?;
+    handle_serde_error(serde_json::from_str(&contents))
+}
+
+/// Checks that a pitch die is used in the league's era.
+fn check_pd(pd: PD, era: Era) -> bool {
+    era.get_all_pd().contains(&pd)
+}
+
+/// Checks a player against the rules for the era, and returns a line describing each problem found.
+/// is_pitcher_spot is true if the player is in a team's rotation or bullpen.
+fn check_player(player: &Player, era: Era, is_pitcher_spot: bool, team_name: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let describe = |problem: &str| format!("{} ({}): {}", player.name, team_name, problem);
+    let era_pitchers = PlayerPosition::get_all_pitcher_positions(era);
+    let pitches = match &player.pos {
+        PlayerPosition::TwoWay(info) => match info.get_pitcher_type() {
+            Ok(pitcher_type) => {
+                if !era_pitchers.contains(pitcher_type) {
+                    result.push(describe(&format!(
+                        "{} is not a pitcher position in the {} Era.",
+                        pitcher_type, era
+                    )));
+                }
+                true
+            }
+            Err(message) => {
+                result.push(describe(&message));
+                true
+            }
+        },
+        pos if pos.matches_cat(PositionCategory::Pitcher) => {
+            if !era_pitchers.contains(pos) {
+                result.push(describe(&format!(
+                    "{} is not a pitcher position in the {} Era.",
+                    pos, era
+                )));
+            }
+            if !is_pitcher_spot {
+                result.push(describe("Pitchers can not be in the lineup or bench."));
+            }
+            true
+        }
+        _ => {
+            if is_pitcher_spot {
+                result.push(describe("Only pitchers can be in the rotation or bullpen."));
+            }
+            false
+        }
+    };
+    match (pitches, player.pd) {
+        (true, None) => result.push(describe("Pitchers must have a pitch die.")),
+        (false, Some(_)) => result.push(describe("Only pitchers can have a pitch die.")),
+        (true, Some(pd)) if !check_pd(pd, era) => result.push(describe(&format!(
+            "{} is not a pitch die in the {} Era.",
+            pd, era
+        ))),
+        _ => {}
+    }
+    let pd_int = player.pd.map(|pd| pd.to_int()).unwrap_or_default();
+    if let Some(player_error) = player.get_player_error(pd_int) {
+        result.push(player_error.to_string());
+    }
+    result
+}
+
+/// Checks every team and player in a league, and returns an ImportError listing every problem if any are found.
+/// Ancient Era teams with an empty bullpen and Modern Era teams without a bullpen are fixed instead of rejected, and every player's trade value is calculated again.
+pub fn validate_league(league: &mut League) -> Result<(), EditLeagueError> {
+    let mut problems = Vec::new();
+    let mut names = HashSet::new();
+    let mut abrvs = HashSet::new();
+    let era = league.era;
+    for team in league.teams.iter_mut() {
+        if team.name.trim().is_empty() || team.abrv.trim().is_empty() {
+            problems.push("Every team must have a name and abbreviation.".to_string());
+        }
+        if !names.insert(team.name.to_string()) {
+            problems.push(format!("There is more than one team named {}.", team.name));
+        }
+        if !abrvs.insert(team.abrv.to_string()) {
+            problems.push(format!(
+                "There is more than one team with the abbreviation {}.",
+                team.abrv
+            ));
+        }
+        match (era, &team.bullpen) {
+            (Era::Ancient, Some(pen)) if pen.is_empty() => team.bullpen = None,
+            (Era::Ancient, Some(_)) => problems.push(format!(
+                "{} is an Ancient Era team, which can not have a bullpen.",
+                team.name
+            )),
+            (Era::Modern, None) => team.bullpen = Some(Vec::new()),
+            _ => {}
+        }
+        for player in team.lineup.iter().chain(team.bench.iter()) {
+            problems.extend(check_player(player, era, false, &team.name));
+        }
+        let pen = team.bullpen.iter().flatten();
+        for player in team.starting_pitching.iter().chain(pen) {
+            problems.extend(check_player(player, era, true, &team.name));
+        }
+        // Trade values are not written by hand, so they are calculated instead of checked.
+        let pen = team.bullpen.iter_mut().flatten();
+        for player in team
+            .lineup
+            .iter_mut()
+            .chain(team.bench.iter_mut())
+            .chain(team.starting_pitching.iter_mut())
+            .chain(pen)
+        {
+            player.trade_value = player.calc_trade_value();
+        }
+    }
+    match problems.is_empty() {
+        true => Ok(()),
+        false => Err(EditLeagueError::ImportError(format!(
+            "{} can not be imported:\n{}",
+            league.name,
+            problems.join("\n")
+        ))),
+    }
+}
+
+/// Validates a league and saves it to the database under the given name, giving every team and player a new id.
+pub fn import_league(
+    conn: &mut Connection,
+    mut league: League,
+    name: &String,
+    seed: u64,
+) -> Result<League, EditLeagueError> {
+    validate_league(&mut league)?;
+    for team in league.teams.iter_mut() {
+        team.calc_team_score();
+    }
+    insert_league_copy(conn, league, name, seed)
+}
+
+/// Prompts the user for the path to a league file, and imports the league. If the league's name is already used, the user is asked for a new one.
+pub fn import_league_from_input<R: Rng>(
+    conn: &mut Connection,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    let path = match Text::new("Please enter the path of the league file to import.")
+        .with_validator(MinLengthValidator::new(1))
+        .prompt()
+    {
+        Ok(path) => path,
+        Err(message) => return inquire_check(message),
+    };
+    let league = read_league_file(&path)?;
+    let taken_names = handle_sql_error(check_name_vec(conn))?;
+    let name = match taken_names.contains(&league.name) {
+        false => league.name.to_string(),
+        true => {
+            println!("There is already a league named {}.", league.name);
+            match new_league_name(conn, &league.name) {
+                Ok(name) => name,
+                Err(EditLeagueError::Inquire(message)) => return inquire_check(message),
+                Err(message) => return Err(message),
+            }
+        }
+    };
+    let seed = league.seed.unwrap_or_else(|| thread.gen());
+    let new_league = import_league(conn, league, &name, seed)?;
+    println!(
+        "{} has been imported with {} teams.",
+        name,
+        new_league.teams.len()
+    );
+    save_league(&new_league);
+    Ok(())
+}
@@ -1,8 +1,9 @@
+use crate::edit_league_error::handle_sql_error;
 use crate::edit_league_error::EditLeagueError;
 use crate::{
     era::Era,
-    inquire_check,
-    league::{check_name_hash, save_league, League},
+    in_transaction, inquire_check,
+    league::{check_name_hash, insert_new_league, save_league, select_seed},
     player::PlayerGender,
 };
 use core::fmt;
@@ -76,12 +77,11 @@ pub fn load_league_templates() -> Vec<LeagueTemplate> {
         ],
     }]
 }
-/// Takes a league template, and creates a league based off the template that is added to the database.
-pub fn new_league_from_template(
-    conn: &mut Connection,
-    thread: &mut ThreadRng,
+/// Finds a name for a league created from a template that is not already used by a league in the database.
+fn template_league_name(
+    conn: &Connection,
     template: &LeagueTemplate,
-) -> Result<(), rusqlite::Error> {
+) -> Result<String, rusqlite::Error> {
     // First, we query to see what league has the largest id.
     let mut max_id_stmt = conn.prepare("SELECT COUNT(leagues.league_id) FROM leagues")?;
     let max_id_iter = max_id_stmt.query_map([], |row| row.get(0))?;
@@ -115,35 +115,35 @@ pub fn new_league_from_template(
         }
         true => format!("{}_1", template.name),
     };
-    println!("League_name = {}", league_name);
-    drop(max_id_stmt);
-    let era_json = serde_json::to_string(&template.era).unwrap();
-
-    let gender_json = serde_json::to_string(&template.gender).unwrap();
-    // And we create a new entry in the sql database.
-    let _league_entry = conn.execute(
-        "INSERT INTO leagues(league_name,era,gender) VALUES(?1, ?2, ?3)",
-        [&league_name, &era_json, &gender_json],
-    )?;
-
-    // Via last_insert_rowid, we get the SQl id for the new league, as the teams we generate will need it.
-    let league_id = conn.last_insert_rowid();
-
-    let mut new_league = League::new(&league_name, template.gender, template.era, league_id);
-    println!("{} created", &league_name);
+    Ok(league_name)
+}
 
-    for team_template in template.teams_templates.iter() {
-        let team_add = new_league.new_team(
-            &team_template.abrv,
-            &team_template.name,
-            thread,
-            league_id,
-            conn,
-        );
-        if let Err(message) = team_add {
-            panic!("{:?}", message)
-        };
-    }
+/// Takes a league template, and creates a league based off the template that is added to the database.
+/// The league and all of its teams are saved in a single transaction, so if any team can not be created the league is not saved.
+pub fn new_league_from_template(
+    conn: &mut Connection,
+    template: &LeagueTemplate,
+    seed: u64,
+) -> Result<(), EditLeagueError> {
+    let league_name = handle_sql_error(template_league_name(conn, template))?;
+    println!("League_name = {}", league_name);
+    let new_league = in_transaction(conn, |conn| {
+        let mut new_league =
+            insert_new_league(conn, &league_name, template.era, template.gender, seed)?;
+        let league_id = new_league.league_id;
+        for team_template in template.teams_templates.iter() {
+            let mut team_thread = new_league.team_rng();
+            new_league.new_team(
+                &team_template.abrv,
+                &team_template.name,
+                &mut team_thread,
+                league_id,
+                conn,
+            )?;
+        }
+        Ok(new_league)
+    })?;
+    println!("{} created with seed {}", &league_name, seed);
     save_league(&new_league);
     Ok(())
 }
@@ -156,11 +156,12 @@ pub fn load_new_template(
 
     let template_choice = inquire::Select::new("Please choose a league_template", options).prompt();
 
-    match template_choice {
-        Ok(template) => match new_league_from_template(conn, thread, &template) {
-            Ok(()) => Ok(()),
-            Err(message) => Err(EditLeagueError::DatabaseError(message)),
-        },
+    let template = match template_choice {
+        Ok(template) => template,
+        Err(message) => return inquire_check(message),
+    };
+    match select_seed(thread) {
+        Ok(seed) => new_league_from_template(conn, &template, seed),
         Err(message) => inquire_check(message),
     }
 }
@@ -1,24 +1,47 @@
+mod archive;
+mod at_bat;
 mod b_traits;
+mod cli;
+mod db_audit;
+mod draft;
 mod edit_league_error;
 mod era;
+mod free_agent;
+mod game_result;
+mod game_sim;
+mod injury;
+mod journal;
 mod league;
+mod league_import;
 mod league_template;
 mod lineup_score;
 mod main_menu;
+mod migrations;
 mod minor_leaguer;
+mod names;
 mod note;
+mod offseason;
 mod pd;
 mod pennantgen;
 mod pitcher_rank_info;
 mod player;
+mod player_editor;
 mod player_error;
 mod player_quality;
 mod player_row;
 mod position;
+mod quick_play;
+mod result_entry;
+mod retirement;
+mod roster_csv;
+mod roster_editor;
 mod sched_view;
 mod schedule;
+mod season_standings;
+mod settings;
 mod team;
 mod tier;
+mod trade;
 mod traits;
 mod update_player_db;
 use crate::era::Era;
@@ -35,6 +58,7 @@ use crate::traits::Defense;
 use crate::traits::Power;
 use crate::traits::Speed;
 use crate::traits::Toughness;
+use edit_league_error::handle_sql_error;
 use edit_league_error::EditLeagueError;
 use inquire::Confirm;
 use inquire::InquireError;
@@ -54,6 +78,27 @@ pub fn inquire_check(err: InquireError) -> Result<(), EditLeagueError> {
     }
 }
 
+/// Runs a function inside of a database transaction. If the function returns an error, every change it made is rolled back.
+/// If a transaction has already been started, the function becomes part of it, and the outer transaction decides if the changes are saved.
+pub fn in_transaction<T>(
+    conn: &mut Connection,
+    f: impl FnOnce(&mut Connection) -> Result<T, EditLeagueError>,
+) -> Result<T, EditLeagueError> {
+    if !conn.is_autocommit() {
+        return f(conn);
+    }
+    handle_sql_error(conn.execute_batch("BEGIN"))?;
+    let result = f(conn);
+    match result {
+        Ok(_) => handle_sql_error(conn.execute_batch("COMMIT"))?,
+        Err(_) => {
+            handle_sql_error(conn.execute_batch("ROLLBACK"))?;
+            println!("No changes were saved to the database.");
+        }
+    };
+    result
+}
+
 // Takes a vec of type e,  returns a hash map of each value with a result of true.
 pub fn vec_to_hash<E: std::hash::Hash + std::cmp::Eq>(vec: &[E]) -> HashMap<&E, bool> {
     let mut result = HashMap::new();
@@ -63,21 +108,58 @@ pub fn vec_to_hash<E: std::hash::Hash + std::cmp::Eq>(vec: &[E]) -> HashMap<&E,
     result
 }
 fn main() -> Result<(), ()> {
+    // The database and output folder can be set via flags, environment variables, or the config file.
+    let (options, args) = match settings::take_global_options(std::env::args().skip(1).collect()) {
+        Ok(value) => value,
+        Err(message) => {
+            println!("{}", message);
+            std::process::exit(message.exit_code());
+        }
+    };
+    let config = match settings::load_config() {
+        Ok(config) => config,
+        Err(message) => {
+            println!(
+                "Unable to read the config file, the default settings will be used.\nThe error was {}",
+                message
+            );
+            settings::Config::default()
+        }
+    };
+    let settings = match settings::resolve_settings(&options, &config) {
+        Ok(settings) => settings,
+        Err(message) => {
+            println!("{}", message);
+            std::process::exit(message.exit_code());
+        }
+    };
+    settings::set_output_dir(&settings.output_dir);
+    settings::set_database_path(&settings.database);
     // First, we load the databsae, or create one if it doesn't exist.
-    let default_path = "deadball.db";
+    let default_path = settings.database.as_str();
     let conn_load = load_database(default_path);
     let mut conn = match conn_load {
         Ok(connection) => connection,
-        Err(_) => {
-            println!(
-                "Unable to create database under {}, please check if the folder is read only.",
-                default_path
-            );
+        Err(message) => {
+            match message {
+                EditLeagueError::SchemaError(ref text) => println!("{}", text),
+                _ => println!(
+                    "Unable to create database under {}, please check if the folder is read only.",
+                    default_path
+                ),
+            }
+            if !args.is_empty() {
+                std::process::exit(message.exit_code());
+            }
             return Ok(());
         }
     };
     //Next we generate a thread for the random numbers we will need to generate.
     let mut r_thread = rand::thread_rng();
+    // If the program is run with a command, the command is run instead of the main menu.
+    if !args.is_empty() {
+        std::process::exit(cli::run_cli(&args, &mut conn, &mut r_thread));
+    }
 
     println!("Welcome to the Deadball league generator!");
     println!("This tool is based off the Deadball tabletop game by W.M. Akers.");
@@ -90,9 +172,15 @@ fn main() -> Result<(), ()> {
             Err(ref message) => {
                 match message {
                     EditLeagueError::DatabaseError(output) => {
-                        println!("Something went wrong with the database, please check that this folder is not read only.\nIt is also possible that {} is corrupted or that the database contains invalid data, please check that as well.\nThe Error message was {}",default_path,output);
+                        println!("Something went wrong with the database, please check that this folder is not read only.\nIt is also possible that {} is corrupted or that the database contains invalid data, please check that as well.\nThe Error message was {}",settings::database_path(),output);
                     }
-                    EditLeagueError::PennantError(err_message) => {
+                    EditLeagueError::PennantError(err_message)
+                    | EditLeagueError::GameError(err_message)
+                    | EditLeagueError::CommandError(err_message)
+                    | EditLeagueError::ImportError(err_message)
+                    | EditLeagueError::SchemaError(err_message)
+                    | EditLeagueError::RosterError(err_message)
+                    | EditLeagueError::InvalidPlayer(err_message) => {
                         println!("{}", err_message)
                     }
                     EditLeagueError::SerdeError(err_message) => println!("There was an error deserializing data from the database.\nThe problem is most likely invalid data in the database. The error was: {}",err_message),
@@ -130,228 +218,11 @@ fn main() -> Result<(), ()> {
     Ok(())
 }
 
-fn load_database(path: &str) -> Result<Connection, rusqlite::Error> {
+/// Opens the database, or creates one if it doesn't exist, and upgrades it to the current schema.
+fn load_database(path: &str) -> Result<Connection, EditLeagueError> {
     // We look for the database, and create a new one if it doesn't exist. If no database exists and there we are unable to create a new database in the folder, the function returns an error
-    let conn = Connection::open(path)?;
-    // We create the league table in the database. Each league has an ID and a unique name. Each league also has an era and gender, which are used in creating teams and players withing the league.
-    conn.execute(
-        "create table if not exists leagues (
-             league_id INTEGER PRIMARY KEY,
-             league_name TEXT NOT NULL UNIQUE,
-             era TEXT NOT NULL,
-             gender TEXT NOT NULL,
-             league_note TEXT
-         )",
-        (),
-    )?;
-    /*  Leagues have a one to many relationship to teams. Each team has its own id, as well as a foreign key that references the league id.
-    Each team also has a name, and an abbreviation of their name. For example, if  you wanted to create a team named after the Los Angeles Dodgers, the abbreviation would be LAD.
-    If a team is generated via the program, the program will not let you have multiple teams in the same league with the same name and/or abbreviation.
-    Teams also have a team score, which is a number that summarizes how good a team is based off the quality of their players, as well as current wins and losses*/
-    conn.execute(
-        "create table if not exists teams (
-             team_id INTEGER PRIMARY KEY,
-             league_id INTEGER NOT NULL,
-             team_name TEXT NOT NULL,
-             abrv TEXT NOT NULL,
-             wins INTEGER DEFAULT 0,
-             losses INTEGER DEFAULT 0,
-             team_note TEXT,
-             FOREIGN KEY(league_id) REFERENCES leagues(league_id)
-         )",
-        (),
-    )?;
-    /*  The last table to create is the players tables. Teams have a one to many relationship with players, with each player belonging to one team
-        Each player has a unique id, and a foreign key team_id which references the id of the team the player belongs to
-
-
-    */
-    conn.execute(
-        "create table if not exists players(
-             player_id INTEGER PRIMARY KEY,
-             team_id INTEGER NOT NULL,
-             player_name TEXT NOT NULL, -- Player's Name
-             age INTEGER NOT NULL, --Players Age
-             pos TEXT NOT NULL, --Player's position, E.G Shortstop, Right Fielder, Pitcher, etc.
-             hand TEXT NOT NULL, --Notes if a player bats left handed, right handed, or if the player is not a pitcher, bats as a switch hitter.
-             bt INTEGER NOT NULL, -- Players batting target, which is an approximation of a players batting average.
-             obt_mod INTEGER NOT NULL, --OBT Modifier, which is used to calculate a players on base target by addition to a player batter target
-             obt INTEGER NOT NULL, -- On base Target, indicates how often a player get's on base. Correlates to a player on base percentage in real life.
-             PD TEXT , -- If a player is a pitcher, they are assigned a pitch die, which represents the stand rpg die, E.G. d12, d4. Pitch die can be negative.
-             pitcher_trait TEXT , -- Traits for pitchers
-             team_spot TEXT NOT NULL, -- Represents where a player is on a team. E.G are they starting lineup or in the bullpen.
-             contact TEXT ,
-             defense TEXT,
-             power TEXT ,
-             speed TEXT ,
-             toughness TEXT,
-             trade_value INTEGER NOT NULL,
-             player_note TEXT,
-             FOREIGN KEY(team_id) REFERENCES teams(team_id)
-         )",
-        (),
-    )?;
-    // We also generate a season table.
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS seasons(
-
-        season_id INTEGER PRIMARY KEY,
-        league_id INTEGER NOT NULL,
-        champion_id INTEGER,
-        FOREIGN KEY(league_id) REFERENCES leagues(league_id),
-        FOREIGN KEY(champion_id) REFERENCES teams(team_id))",
-        (),
-    )?;
-    // As well as a table of rounds.
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS rounds(
-        round_id INTEGER PRIMARY KEY,
-        season_id INTEGER NOT NULL,
-        FOREIGN KEY (season_id) REFERENCES seasons(season_id)
-    )",
-        (),
-    )?;
-    // Ans series that are part of a round.
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS series(
-    series_id INTEGER PRIMARY KEY,
-    round_id INTEGER NOT NULL,
-    home_team_id INTEGER NOT NULL,
-    away_team_id INTEGER NOT NULL,
-    FOREIGN KEY (round_id) REFERENCES rounds(round_id),
-    FOREIGN KEY (home_team_id) REFERENCES teams(team_id),
-    FOREIGN KEY (away_team_id) REFERENCES teams(team_id)
-    )",
-        (),
-    )?;
-    // And games that are part of a series.
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS games(
-        game_id INTEGER PRIMARY KEY,
-        series_id INTEGER NOT NULL,
-        home_score INTEGER DEFAULT 0,
-        away_score INTEGER DEFAULT 0,
-        game_not TEXT,
-        FOREIGN KEY (series_id) REFERENCES series(series_id)
-    )",
-        (),
-    )?;
-
-    conn.execute(
-        "
-        CREATE TABLE IF NOT EXISTS pennants(
-            pennant_id INTEGER PRIMARY KEY,
-            league_id INTEGER NOT NULL,
-            FOREIGN KEY (league_id) REFERENCES leagues(league_id)
-        
-        )
-    ",
-        (),
-    )?;
-
-    conn.execute(
-        "
-        CREATE TABLE IF NOT EXISTS pennants_standings(
-        standings_id INTEGER PRIMARY KEY,
-        pennant_id INTEGER NOT NULL,
-        team_id INTEGER NOT NULL,
-        wins INTEGER NOT NULL,
-        losses INTEGER NOT NULL,
-        FOREIGN KEY (pennant_id) REFERENCES pennants(pennant_id),
-        FOREIGN KEY(team_id) REFERENCES teams(team_id)
-        )
-    
-    ",
-        (),
-    )?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS league_archive(
-    archive_id INTEGER PRIMARY KEY,
-    league_id INTEGER NOT NULL,
-    date_saved TEXT NOT NULL,
-    league_data TEXT NOT NULL,
-    FOREIGN KEY (league_id) REFERENCES leagues(league_id))",
-        (),
-    )?;
-
-    // We create some indexes to optimize queries
-
-    // we create indexes on the foreign keys for the most important tables
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS players_team_id_index ON players(team_id);",
-        (),
-    )?;
-    conn.execute(
-        " CREATE INDEX IF NOT EXISTS teams_league_id_index ON teams(league_id);",
-        (),
-    )?;
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS teams_league_id_index ON teams(league_id);",
-        (),
-    )?;
-    // We also use querys that check if a player has a PD, so we create an index that tracks a players pd.
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS player_pd_index ON players(PD);",
-        (),
-    )?;
-    // As well as an index that tracks a players team ID as well as PD.
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS player_team_id_pd_index ON players(team_id,PD)",
-        (),
-    )?;
-    conn.execute(
-        "CREATE INDEX IF NOT EXISTS archive_index ON league_archive(league_id)",
-        (),
-    )?;
-    /*  We attempt to create a pitch die table. If we are able to create the table, we fill the table with the string version of each pitch die as well as the farthest value away from zero possible from the die.
-    This is used to to make querying pitcher die values easier, as the maximum absolute value of every pitch die is saved in this table.
-     Since we only want the table filled with values once, we use an if statement to see if the query to create a new table is valid, which it will not be if the table already exist.
-     */
-    if conn
-        .execute(
-            "CREATE TABLE pitch_die(
-        die_id INTEGER PRIMARY KEY ,
-        die_text STRING UNIQUE NOT NULL,
-        die_int INTEGER NOT NULL)",
-            (),
-        )
-        .is_ok()
-    {
-        let all_dice = [
-            PD::D20,
-            PD::D12,
-            PD::D8,
-            PD::D6,
-            PD::D4,
-            PD::D0,
-            PD::DM4,
-            PD::DM6,
-            PD::DM8,
-            PD::DM12,
-            PD::DM20,
-        ];
-        for die in all_dice {
-            conn.execute(
-                "INSERT INTO pitch_die(die_text, die_int) VALUES(?1, ?2)",
-                [
-                    serde_json::to_string(&die).unwrap(),
-                    die.to_int().to_string(),
-                ],
-            )?;
-        }
-    }
-
-    /*conn.execute("CREATE TABLE IF NOT EXISTS team_seasons(
-    team_season_id INTEGER PRIMARY KEY,st
-    league_season_id INTEGER,
-    team_id INTEGER,
-    wins INTEGER DEFAULT 0,
-    losses INTEGER DEFAULT 0,
-
-
-    ", params) */
-
+    let mut conn = handle_sql_error(Connection::open(path))?;
+    migrations::run_migrations(&mut conn)?;
     // If no errors occurred, the database is returned.
     Ok(conn)
 }
@@ -375,10 +246,34 @@ fn player_pool_test(input: &[Player], team_id: i64, for_pitchers: bool) {
         assert!(player_err.is_none());
     }
 }
-/* Used for testing schedules. Commented for now untill we have a better solution for scheduling.
-//fn how_many_rounds(number_of_teams: i32, series_per_matchup: i32) -> i32 {
-   // (number_of_teams - 1) * series_per_matchup
-}*/
+#[cfg(test)]
+/// Returns how many rounds a schedule should have. A league with an odd number of teams needs an extra round per cycle, as one team has a bye each round.
+fn how_many_rounds(number_of_teams: i32, series_per_matchup: i32) -> i32 {
+    let slots = number_of_teams + number_of_teams % 2;
+    (slots - 1) * series_per_matchup
+}
+
+#[cfg(test)]
+/// Creates a league in a new in memory database, with the Los Angeles Angels and the Seattle Rainiers as it's teams.
+pub fn test_league(era: Era, gender: PlayerGender, seed: u64) -> (Connection, league::League) {
+    let mut conn = load_database(":memory:").unwrap();
+    let name = "Test League".to_string();
+    let mut league = league::insert_new_league(&mut conn, &name, era, gender, seed).unwrap();
+    for (abrv, name) in [("LA", "Los Angeles Angels"), ("SEA", "Seattle Rainiers")] {
+        let mut thread = league.team_rng();
+        let league_id = league.league_id;
+        league
+            .new_team(
+                &abrv.to_string(),
+                &name.to_string(),
+                &mut thread,
+                league_id,
+                &mut conn,
+            )
+            .unwrap();
+    }
+    (conn, league)
+}
 
 #[cfg(test)]
 mod tests {
@@ -388,9 +283,10 @@ mod tests {
     use b_traits::BTraits;
     use chrono::{Datelike, Local, Timelike};
 
-    use league::{get_all_leagues_from_db, load_teams_from_sql};
+    use league::{get_all_leagues_from_db, load_teams_from_sql, League};
     use league_template::{load_league_templates, new_league_from_template};
     use position::{PlayerPosition, TwoWayInfo};
+    use rand::Rng;
 
     /// Used to test Leagues in database.
     struct LeagueListing {
@@ -413,7 +309,7 @@ mod tests {
         let first = &templates[0];
         // We use the template to create a new league in the database multiple times
         for _ in 1..=3 {
-            new_league_from_template(&mut test_conn, &mut r_thread, &first).unwrap();
+            new_league_from_template(&mut test_conn, &first, r_thread.gen()).unwrap();
         }
         // We query for the league ids and names.
         let mut league_stmt = test_conn
@@ -479,13 +375,13 @@ mod tests {
         // Next, we select the first team.
         let first_team = current_league.teams.get_mut(0).unwrap();
         let first_team_id = first_team.team_id;
-        
+
         // We check that the league lookup hashmaps are working.
         let id_lookup = current_league.team_id_hash.get(&first_team_id).unwrap();
-        assert_eq!(*id_lookup,0);
+        assert_eq!(*id_lookup, 0);
 
         let abrv_lookup = current_league.team_abrv_hash.get(&first_team.abrv).unwrap();
-        assert_eq!(*abrv_lookup,0);
+        assert_eq!(*abrv_lookup, 0);
         //Next we check the team's player pools to make sure they have all the players we expect.
         assert_eq!(first_team.lineup.len(), 8);
         // And then check that the player structs data matches what we expect.
@@ -614,14 +510,343 @@ mod tests {
         // And we save the new directory in the old_tests directory.
         let moved_dir_string = format!("old_tests/{}", dir_name);
         fs::rename(dir_name, moved_dir_string).unwrap();
-
-        /*let series_per_matchup = 6;
-        /let test_sched = new_schedule(&current_league.teams, 3, series_per_matchup);
+    }
+    #[test]
+    fn round_robin_schedule() {
+        use crate::schedule::new_schedule_from_ids;
+        use std::collections::HashMap;
+        let series_per_matchup = 4;
+        for team_count in [2, 5, 8, 11] {
+            let ids: Vec<i64> = (1..=team_count).collect();
+            let sched = new_schedule_from_ids(&ids, 3, series_per_matchup);
+            assert_eq!(
+                sched.len() as i32,
+                how_many_rounds(team_count as i32, series_per_matchup)
+            );
+            let mut home_series = HashMap::new();
+            let mut matchups = HashMap::new();
+            for round in sched.iter() {
+                let mut teams_in_round = vec![];
+                for (home, away) in round.get_matchups() {
+                    teams_in_round.push(home);
+                    teams_in_round.push(away);
+                    *home_series.entry(home).or_insert(0) += 1;
+                    *matchups
+                        .entry((home.min(away), home.max(away)))
+                        .or_insert(0) += 1;
+                }
+                // No team plays twice in a round, and at most 1 team has a bye.
+                teams_in_round.sort();
+                teams_in_round.dedup();
+                assert_eq!(teams_in_round.len() as i64, team_count - team_count % 2);
+            }
+            // Every pair of teams meets the same number of times, and every team hosts half of their series.
+            assert!(matchups.values().all(|count| *count == series_per_matchup));
+            assert_eq!(matchups.len() as i64, team_count * (team_count - 1) / 2);
+            for id in ids {
+                assert_eq!(
+                    home_series[&id],
+                    (team_count - 1) as i32 * series_per_matchup / 2
+                );
+            }
+        }
+    }
+    #[test]
+    fn at_bat_table() {
+        use crate::at_bat::{read_hit_table, read_swing, HitType, OutType, SwingResult};
+        use crate::player::Hand;
+        let batter = Player {
+            bt: 30,
+            obt_mod: 6,
+            obt: 36,
+            hand: Hand::R,
+            ..Player::default()
+        };
+        let pitcher = Player {
+            pd: Some(PD::D8),
+            hand: Hand::R,
+            ..Player::default()
+        };
+        assert_eq!(read_swing(&batter, &pitcher, 5), SwingResult::CriticalHit);
+        assert_eq!(read_swing(&batter, &pitcher, 30), SwingResult::Hit);
+        assert_eq!(read_swing(&batter, &pitcher, 31), SwingResult::Walk);
+        assert_eq!(read_swing(&batter, &pitcher, 36), SwingResult::Walk);
+        assert_eq!(
+            read_swing(&batter, &pitcher, 41),
+            SwingResult::PossibleError
+        );
+        assert_eq!(
+            read_swing(&batter, &pitcher, 44),
+            SwingResult::ProductiveOut(OutType::GroundOut)
+        );
+        assert_eq!(
+            read_swing(&batter, &pitcher, 42),
+            SwingResult::Out(OutType::Strikeout)
+        );
+        assert_eq!(
+            read_swing(&batter, &pitcher, 77),
+            SwingResult::Out(OutType::FlyOut)
+        );
+        // A lefty gets the platoon advantage against a right handed pitcher.
+        let lefty = Player {
+            hand: Hand::L,
+            ..batter.clone()
+        };
+        assert_eq!(read_swing(&lefty, &pitcher, 31), SwingResult::Hit);
+        // Pitchers with good control shrink the walk range, while K+ pitchers strike out more batters.
+        let control_pitcher = PitcherTrait::CN.force_on_player(&pitcher);
+        assert_eq!(
+            read_swing(&batter, &control_pitcher, 35),
+            SwingResult::PossibleError
+        );
+        let k_pitcher = PitcherTrait::K.force_on_player(&pitcher);
         assert_eq!(
-            test_sched.len() as i32,
-            how_many_rounds(current_league.teams.len() as i32, series_per_matchup)
+            read_swing(&batter, &k_pitcher, 53),
+            SwingResult::Out(OutType::Strikeout)
+        );
+        let contact_batter = Contact::C1.force_on_player(&batter);
+        assert_eq!(
+            read_swing(&contact_batter, &pitcher, 52),
+            SwingResult::Out(OutType::GroundOut)
+        );
+        // Power and speed change the hit table.
+        assert_eq!(read_hit_table(&batter, 17), HitType::Double);
+        let fast_batter = Speed::S1.force_on_player(&batter);
+        assert_eq!(read_hit_table(&fast_batter, 17), HitType::Triple);
+        let slow_batter = Speed::SM1.force_on_player(&batter);
+        assert_eq!(read_hit_table(&slow_batter, 18), HitType::Double);
+        assert_eq!(read_hit_table(&batter, 20), HitType::HomeRun);
+    }
+    #[test]
+    fn quick_play_scores() {
+        use crate::quick_play::settle_quick_game;
+        // The team with the higher total wins by at least a run.
+        assert_eq!(settle_quick_game(80, 79, 2), Some((3, 2)));
+        assert_eq!(settle_quick_game(70, 81, 0), Some((0, 3)));
+        // Tied totals are rolled again.
+        assert_eq!(settle_quick_game(75, 75, 4), None);
+    }
+    #[test]
+    fn season_standings() {
+        use crate::game_result::GameRecord;
+        use crate::season_standings::compute_standings;
+        let teams = vec![(1, "Alpha".to_string()), (2, "Beta".to_string())];
+        let game = |game_id, home_team_id, away_team_id, home_score, away_score| GameRecord {
+            game_id,
+            home_team_id,
+            away_team_id,
+            home_score,
+            away_score,
+            note: None,
+        };
+        let games = vec![
+            game(1, 1, 2, 5, 2),
+            game(2, 1, 2, 1, 3),
+            game(3, 2, 1, 0, 4),
+            // Unplayed games are ignored.
+            game(4, 2, 1, 0, 0),
+        ];
+        let standings = compute_standings(&teams, &games);
+        let (leader, second) = (&standings[0], &standings[1]);
+        assert_eq!(leader.name, "Alpha");
+        assert_eq!((leader.wins, leader.losses), (2, 1));
+        assert_eq!((leader.home_wins, leader.home_losses), (1, 1));
+        assert_eq!((leader.away_wins, leader.away_losses), (1, 0));
+        assert_eq!(leader.run_differential(), 5);
+        assert_eq!(leader.streak(), "W1");
+        assert_eq!(second.streak(), "L1");
+        assert_eq!(second.recent_record(), (1, 2));
+        assert_eq!(second.games_behind(leader), 1.0);
+    }
+    #[test]
+    fn seeded_generation() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        // The same seed must always produce the same team.
+        let gen_team = |seed| {
+            let mut thread = StdRng::seed_from_u64(seed);
+            let team = Team::new(
+                &"SEA".to_string(),
+                &"Seattle Rainiers".to_string(),
+                PlayerGender::Coed,
+                Era::Modern,
+                &mut thread,
+            );
+            serde_json::to_string(&team).unwrap()
+        };
+        assert_eq!(gen_team(1921), gen_team(1921));
+        assert_ne!(gen_team(1921), gen_team(1922));
+        // Each team in a seeded league gets a different generator.
+        let mut league = League::new(
+            &"Seeded".to_string(),
+            PlayerGender::Male,
+            Era::Ancient,
+            0,
+            Some(42),
+        );
+        let first_roll: u64 = league.team_rng().gen();
+        assert_eq!(first_roll, league.team_rng().gen::<u64>());
+        let mut thread = league.team_rng();
+        league.add_team(Team::new(
+            &"POR".to_string(),
+            &"Portland Beavers".to_string(),
+            PlayerGender::Male,
+            Era::Ancient,
+            &mut thread,
+        ));
+        assert_ne!(first_roll, league.team_rng().gen::<u64>());
+        // The second team of one seed is not the first team of the next seed.
+        let next_seed = League::new(
+            &"Next Seed".to_string(),
+            PlayerGender::Male,
+            Era::Ancient,
+            0,
+            Some(43),
+        );
+        assert_ne!(
+            league.team_rng().gen::<u64>(),
+            next_seed.team_rng().gen::<u64>()
         );
-        schedule_to_sql(&mut test_conn, &current_league, test_sched).unwrap();*/
+    }
+    #[test]
+    fn league_import_validation() {
+        use crate::league_import::validate_league;
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+        let mut thread = StdRng::seed_from_u64(1910);
+        let mut league = League::new(
+            &"Imported".to_string(),
+            PlayerGender::Female,
+            Era::Ancient,
+            0,
+            None,
+        );
+        for (abrv, name) in [("ONE", "First"), ("TWO", "Second")] {
+            league.add_team(Team::new(
+                &abrv.to_string(),
+                &name.to_string(),
+                PlayerGender::Female,
+                Era::Ancient,
+                &mut thread,
+            ));
+        }
+        // Trade values in the file are replaced with the calculated value.
+        league.teams[0].lineup[0].trade_value = 0;
+        assert!(validate_league(&mut league).is_ok());
+        let batter = &league.teams[0].lineup[0];
+        assert_eq!(batter.trade_value, batter.calc_trade_value());
+        league.teams[1].abrv = "ONE".to_string();
+        assert!(validate_league(&mut league).is_err());
+        league.teams[1].abrv = "TWO".to_string();
+        league.teams[0].lineup[0].obt += 1;
+        assert!(validate_league(&mut league).is_err());
+        league.teams[0].lineup[0].obt -= 1;
+        // A pitcher can not be in the lineup.
+        let pitcher = league.teams[0].starting_pitching[0].clone();
+        league.teams[0].lineup.push(pitcher);
+        assert!(validate_league(&mut league).is_err());
+        // Pitch dice must belong to the league's era.
+        let mut modern = League::new(
+            &"Modern Import".to_string(),
+            PlayerGender::Female,
+            Era::Modern,
+            0,
+            None,
+        );
+        modern.add_team(Team::new(
+            &"MOD".to_string(),
+            &"Moderns".to_string(),
+            PlayerGender::Female,
+            Era::Modern,
+            &mut thread,
+        ));
+        assert!(validate_league(&mut modern).is_ok());
+        modern.teams[0].starting_pitching[0].pd = Some(PD::D20);
+        assert!(validate_league(&mut modern).is_err());
+    }
+    #[test]
+    fn schema_migrations() {
+        let path = "migration_test.db";
+        fs::remove_file(path).ok();
+        {
+            // A database from before migrations, where the pitch die table was created but never filled.
+            let old_conn = Connection::open(path).unwrap();
+            old_conn
+                .execute_batch(
+                    "CREATE TABLE leagues (league_id INTEGER PRIMARY KEY, league_name TEXT NOT NULL UNIQUE, era TEXT NOT NULL, gender TEXT NOT NULL, league_note TEXT);
+                    CREATE TABLE pitch_die(die_id INTEGER PRIMARY KEY, die_text STRING UNIQUE NOT NULL, die_int INTEGER NOT NULL);
+                    INSERT INTO leagues VALUES(1, 'Old Timers', '\"Ancient\"', '\"Male\"', '\"Founded in 1903.\"');",
+                )
+                .unwrap();
+        }
+        let conn = load_database(path).unwrap();
+        let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        assert_eq!(count("SELECT COUNT(*) FROM pitch_die"), 11);
+        assert_eq!(
+            count("SELECT COUNT(*) FROM pragma_table_info('leagues') WHERE name = 'seed'"),
+            1
+        );
+        // Notes written before the note log existed start the league's timeline.
+        assert_eq!(
+            count("SELECT COUNT(*) FROM note_log WHERE note = 'Founded in 1903.' AND date_written IS NULL"),
+            1
+        );
+        assert_eq!(
+            count("SELECT version FROM schema_version"),
+            migrations::SCHEMA_VERSION
+        );
+        // Opening an up to date database does not change it.
+        drop(conn);
+        let conn = load_database(path).unwrap();
+        conn.execute(
+            "UPDATE schema_version SET version = ?1",
+            [migrations::SCHEMA_VERSION + 1],
+        )
+        .unwrap();
+        drop(conn);
+        assert!(matches!(
+            load_database(path),
+            Err(EditLeagueError::SchemaError(_))
+        ));
+        fs::remove_file(path).ok();
+    }
+    #[test]
+    fn transactional_creation() {
+        let mut conn = load_database(":memory:").unwrap();
+        // The trigger makes saving a player fail once a roster is partly saved.
+        conn.execute_batch(
+            "CREATE TRIGGER full_roster BEFORE INSERT ON players WHEN (SELECT COUNT(*) FROM players) >= 10
+            BEGIN SELECT RAISE(ABORT, 'roster is full'); END;",
+        )
+        .unwrap();
+        let mut league = league::insert_new_league(
+            &mut conn,
+            &"Partial".to_string(),
+            Era::Modern,
+            PlayerGender::Coed,
+            3,
+        )
+        .unwrap();
+        let mut thread = league.team_rng();
+        let result = league.new_team(
+            &"OAK".to_string(),
+            &"Oakland Oaks".to_string(),
+            &mut thread,
+            league.league_id,
+            &mut conn,
+        );
+        assert!(matches!(result, Err(EditLeagueError::DatabaseError(_))));
+        assert!(league.teams.is_empty());
+        let count = |conn: &Connection, sql: &str| -> i64 {
+            conn.query_row(sql, [], |row| row.get(0)).unwrap()
+        };
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM teams"), 0);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM players"), 0);
+        // A league created from a template is not saved if any of its teams fail.
+        let templates = load_league_templates();
+        assert!(new_league_from_template(&mut conn, &templates[0], 3).is_err());
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM leagues"), 1);
+        assert!(conn.is_autocommit());
     }
     #[test]
     fn trait_logic() {
@@ -653,4 +878,50 @@ mod tests {
         let k_b_traits = strike_out.force_b_traits(&def_b_traits);
         assert_eq!(k_b_traits, def_b_traits)
     }
+
+    #[test]
+    fn settings_precedence() {
+        let mut config = settings::Config::default();
+        assert_eq!(
+            settings::resolve_settings(&settings::GlobalOptions::default(), &config)
+                .unwrap()
+                .database,
+            "deadball.db"
+        );
+        config.profiles.insert(
+            "Tuesday".to_string(),
+            settings::Profile {
+                database: "tuesday.db".to_string(),
+                output_dir: "tuesday".to_string(),
+            },
+        );
+        config.active_profile = Some("Tuesday".to_string());
+        let from_profile =
+            settings::resolve_settings(&settings::GlobalOptions::default(), &config).unwrap();
+        assert_eq!(from_profile.database, "tuesday.db");
+        assert_eq!(from_profile.output_dir, "tuesday");
+        // A flag overrides the profile.
+        let options = settings::GlobalOptions {
+            database: Some("other.db".to_string()),
+            output_dir: None,
+            profile: None,
+        };
+        let from_flag = settings::resolve_settings(&options, &config).unwrap();
+        assert_eq!(from_flag.database, "other.db");
+        assert_eq!(from_flag.output_dir, "tuesday");
+        // An unknown profile is an error that lists the profiles that exist.
+        let options = settings::GlobalOptions {
+            profile: Some("Friday".to_string()),
+            ..settings::GlobalOptions::default()
+        };
+        match settings::resolve_settings(&options, &config) {
+            Err(EditLeagueError::CommandError(message)) => assert!(message.contains("Tuesday")),
+            _ => panic!("An unknown profile should not be resolved."),
+        }
+        // A flag without a value is an error.
+        let args = vec!["refresh".to_string(), "--db".to_string()];
+        assert!(settings::take_global_options(args).is_err());
+        let args = vec!["--db".to_string(), "--league".to_string()];
+        assert!(settings::take_global_options(args).is_err());
+    }
 }
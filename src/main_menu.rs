@@ -1,6 +1,9 @@
 use core::fmt;
 
+use crate::db_audit::audit_from_input;
 use crate::edit_league_error::EditLeagueError;
+use crate::league_import::import_league_from_input;
+use crate::settings::switch_profile;
 use crate::{
     inquire_check, league::create_new_league, league_check, league_template::load_new_template,
 };
@@ -13,6 +16,8 @@ pub enum LoadLeagueInput {
     RefreshLeague,
     ViewSchedule,
     ViewRankings,
+    ViewSeasonStandings,
+    ExportRoster,
 }
 
 #[derive(Copy, Clone, Debug)]
@@ -21,6 +26,20 @@ pub enum EditLeagueInput {
     CreateSchedule,
     GeneratePennant,
     CreateArchive,
+    PlayGame,
+    QuickPlay,
+    EnterResults,
+    RestoreArchive,
+    ImportRoster,
+    RunOffseason,
+    CallUpProspect,
+    HoldDraft,
+    MakeTrade,
+    FreeAgency,
+    Injuries,
+    EditRoster,
+    EditPlayer,
+    Notes,
 }
 // MenuInput contains all the valid choices a user can use at the main menu.
 #[derive(Copy, Clone, Debug)]
@@ -28,6 +47,9 @@ pub enum MenuInput {
     CreateNewLeague,
     LoadExistingLeague(LoadLeagueInput),
     LoadLeagueFromTemplate,
+    ImportLeague,
+    SwitchDatabase,
+    AuditDatabase,
     Exit,
 }
 
@@ -41,12 +63,37 @@ impl fmt::Display for MenuInput {
                     EditLeagueInput::CreateNewTeam => "Create a new team.",
                     EditLeagueInput::CreateSchedule => "Generate a schedule for an existing league",
                     EditLeagueInput::GeneratePennant => "Generate a new pennant chase.",
-                    EditLeagueInput::CreateArchive => "Archive a copy of a league in it's current state in the database as well as a text file."
+                    EditLeagueInput::CreateArchive => "Archive a copy of a league in it's current state in the database as well as a text file.",
+                    EditLeagueInput::PlayGame => "Simulate a game from a league's schedule.",
+                    EditLeagueInput::QuickPlay => "Quickly resolve a game or series from a league's schedule using team scores.",
+                    EditLeagueInput::EnterResults => "Enter the results of a round from a league's schedule.",
+                    EditLeagueInput::RestoreArchive => "Roll a league back to an archive, or import an archive as a new league.",
+                    EditLeagueInput::ImportRoster => "Import players from a roster CSV file.",
+                    EditLeagueInput::RunOffseason => "Run the offseason, aging every player in a league by one year and replacing players that retire.",
+                    EditLeagueInput::CallUpProspect => "View a team's farm system and call up a prospect.",
+                    EditLeagueInput::HoldDraft => "Hold an amateur draft.",
+                    EditLeagueInput::MakeTrade => "Trade players between two teams.",
+                    EditLeagueInput::FreeAgency => "Sign or release free agents.",
+                    EditLeagueInput::Injuries => "View the injured list, or roll for an injury.",
+                    EditLeagueInput::EditRoster => {
+                        "Move players between a team's lineup, bench, rotation and bullpen."
+                    }
+                    EditLeagueInput::EditPlayer => {
+                        "Edit a player's name, age, position, ratings, traits or note."
+                    }
+                    EditLeagueInput::Notes => {
+                        "Write notes on a league, team, player or game, or view the league's timeline."
+                    }
                 },
                 LoadLeagueInput::ViewSchedule => "View schedule.",
                 LoadLeagueInput::ViewRankings => "View the top 10 batter or pitchers for a league.",
+                LoadLeagueInput::ViewSeasonStandings => "View the standings for a season based off recorded game results.",
+                LoadLeagueInput::ExportRoster => "Export a league's rosters to a CSV file.",
             },
             Self::LoadLeagueFromTemplate => "Create a new league from a template.",
+            Self::ImportLeague => "Import a league from a JSON file.",
+            Self::SwitchDatabase => "Switch to a different database profile.",
+            Self::AuditDatabase => "Check the database for errors.",
             Self::Exit => "Exit",
         };
         write!(f, "{}", chars)
@@ -73,7 +120,21 @@ pub fn run_main_menu(conn: &mut Connection, thread: &mut ThreadRng) -> Result<()
     let new_team = EditLeagueInput::CreateNewTeam;
     let new_pennant = EditLeagueInput::GeneratePennant;
     let new_archive = EditLeagueInput::CreateArchive;
-    //let new_sched = EditLeagueInput::CreateSchedule;
+    let restore_archive = EditLeagueInput::RestoreArchive;
+    let import_roster = EditLeagueInput::ImportRoster;
+    let play_game = EditLeagueInput::PlayGame;
+    let quick_play = EditLeagueInput::QuickPlay;
+    let enter_results = EditLeagueInput::EnterResults;
+    let new_sched = EditLeagueInput::CreateSchedule;
+    let offseason = EditLeagueInput::RunOffseason;
+    let call_up = EditLeagueInput::CallUpProspect;
+    let draft = EditLeagueInput::HoldDraft;
+    let trade = EditLeagueInput::MakeTrade;
+    let free_agency = EditLeagueInput::FreeAgency;
+    let injuries = EditLeagueInput::Injuries;
+    let edit_roster = EditLeagueInput::EditRoster;
+    let edit_player = EditLeagueInput::EditPlayer;
+    let notes = EditLeagueInput::Notes;
     let starting_options: Vec<MenuInput> = vec![
         MenuInput::CreateNewLeague,
         MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(new_team)),
@@ -81,10 +142,28 @@ pub fn run_main_menu(conn: &mut Connection, thread: &mut ThreadRng) -> Result<()
         MenuInput::LoadExistingLeague(LoadLeagueInput::RefreshLeague),
         MenuInput::LoadExistingLeague(LoadLeagueInput::ViewRankings),
         MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(new_archive)),
-        // Uncomment the next 2 lines to enable schedule generation.
-        //MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(new_sched)),
-        //MenuInput::LoadExistingLeague(LoadLeagueInput::ViewSchedule),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(restore_archive)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(play_game)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(quick_play)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(new_sched)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::ViewSchedule),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(enter_results)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::ViewSeasonStandings),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::ExportRoster),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(import_roster)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(offseason)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(call_up)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(draft)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(trade)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(free_agency)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(injuries)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(edit_roster)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(edit_player)),
+        MenuInput::LoadExistingLeague(LoadLeagueInput::EditLeague(notes)),
         MenuInput::LoadLeagueFromTemplate,
+        MenuInput::ImportLeague,
+        MenuInput::SwitchDatabase,
+        MenuInput::AuditDatabase,
         MenuInput::Exit,
     ];
     // We prompt the user via Inquire.
@@ -106,6 +185,9 @@ pub fn run_main_menu(conn: &mut Connection, thread: &mut ThreadRng) -> Result<()
                 Ok(_) => Ok(()),
                 Err(message) => Err(message),
             },
+            MenuInput::ImportLeague => import_league_from_input(conn, thread),
+            MenuInput::SwitchDatabase => switch_profile(conn),
+            MenuInput::AuditDatabase => audit_from_input(conn),
         },
 
         Err(message) => inquire_check(message),
@@ -0,0 +1,598 @@
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::pd::PD;
+use rusqlite::Connection;
+
+/* The database has a schema_version table that records which migrations have been run on it.
+When a database is opened, every migration newer than the database's version is run in order inside of a single transaction, so a database is never left half upgraded.
+Databases created before the schema_version table was added are treated as version 0. Since the first migrations only create what is missing, they are safe to run on those databases.
+To change the schema, add a new migration to the end of MIGRATIONS. Migrations that have been released should never be edited, as they will not be run again on databases that already have them.*/
+
+/// A change to the database schema.
+struct Migration {
+    version: i64,
+    description: &'static str,
+    run: fn(&Connection) -> Result<(), rusqlite::Error>,
+}
+
+/// Every migration, in the order they are run. The version of each migration must be one higher than the one before it.
+const MIGRATIONS: [Migration; 13] = [
+    Migration {
+        version: 1,
+        description: "Create the original tables",
+        run: create_original_tables,
+    },
+    Migration {
+        version: 2,
+        description: "Add a seed to leagues",
+        run: add_league_seed,
+    },
+    Migration {
+        version: 3,
+        description: "Fill the pitch die table",
+        run: fill_pitch_die,
+    },
+    Migration {
+        version: 4,
+        description: "Add a table of retired players",
+        run: create_retired_players,
+    },
+    Migration {
+        version: 5,
+        description: "Add a table of prospects",
+        run: create_prospects,
+    },
+    Migration {
+        version: 6,
+        description: "Add tables for the draft history",
+        run: create_draft_history,
+    },
+    Migration {
+        version: 7,
+        description: "Add tables for the trade log",
+        run: create_trade_log,
+    },
+    Migration {
+        version: 8,
+        description: "Add a table of free agents",
+        run: create_free_agents,
+    },
+    Migration {
+        version: 9,
+        description: "Add a table of injuries",
+        run: create_injuries,
+    },
+    Migration {
+        version: 10,
+        description: "Add a log of notes for the timeline",
+        run: create_note_log,
+    },
+    Migration {
+        version: 11,
+        description: "Save the free agent pool with each archive",
+        run: add_archive_free_agents,
+    },
+    Migration {
+        version: 12,
+        description: "Save the farm systems and retired players with each archive",
+        run: add_archive_history,
+    },
+    Migration {
+        version: 13,
+        description: "Keep the players drafted into farm systems",
+        run: add_drafted_players,
+    },
+];
+
+/// The schema version this version of the program uses.
+pub const SCHEMA_VERSION: i64 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+fn create_original_tables(conn: &Connection) -> Result<(), rusqlite::Error> {
+    // We create the league table in the database. Each league has an ID and a unique name. Each league also has an era and gender, which are used in creating teams and players withing the league.
+    conn.execute(
+        "create table if not exists leagues (
+             league_id INTEGER PRIMARY KEY,
+             league_name TEXT NOT NULL UNIQUE,
+             era TEXT NOT NULL,
+             gender TEXT NOT NULL,
+             league_note TEXT
+         )",
+        (),
+    )?;
+    /*  Leagues have a one to many relationship to teams. Each team has its own id, as well as a foreign key that references the league id.
+    Each team also has a name, and an abbreviation of their name. For example, if  you wanted to create a team named after the Los Angeles Dodgers, the abbreviation would be LAD.
+    If a team is generated via the program, the program will not let you have multiple teams in the same league with the same name and/or abbreviation.
+    Teams also have a team score, which is a number that summarizes how good a team is based off the quality of their players, as well as current wins and losses*/
+    conn.execute(
+        "create table if not exists teams (
+             team_id INTEGER PRIMARY KEY,
+             league_id INTEGER NOT NULL,
+             team_name TEXT NOT NULL,
+             abrv TEXT NOT NULL,
+             wins INTEGER DEFAULT 0,
+             losses INTEGER DEFAULT 0,
+             team_note TEXT,
+             FOREIGN KEY(league_id) REFERENCES leagues(league_id)
+         )",
+        (),
+    )?;
+    /*  The last table to create is the players tables. Teams have a one to many relationship with players, with each player belonging to one team
+        Each player has a unique id, and a foreign key team_id which references the id of the team the player belongs to
+
+
+    */
+    conn.execute(
+        "create table if not exists players(
+             player_id INTEGER PRIMARY KEY,
+             team_id INTEGER NOT NULL,
+             player_name TEXT NOT NULL, -- Player's Name
+             age INTEGER NOT NULL, --Players Age
+             pos TEXT NOT NULL, --Player's position, E.G Shortstop, Right Fielder, Pitcher, etc.
+             hand TEXT NOT NULL, --Notes if a player bats left handed, right handed, or if the player is not a pitcher, bats as a switch hitter.
+             bt INTEGER NOT NULL, -- Players batting target, which is an approximation of a players batting average.
+             obt_mod INTEGER NOT NULL, --OBT Modifier, which is used to calculate a players on base target by addition to a player batter target
+             obt INTEGER NOT NULL, -- On base Target, indicates how often a player get's on base. Correlates to a player on base percentage in real life.
+             PD TEXT , -- If a player is a pitcher, they are assigned a pitch die, which represents the stand rpg die, E.G. d12, d4. Pitch die can be negative.
+             pitcher_trait TEXT , -- Traits for pitchers
+             team_spot TEXT NOT NULL, -- Represents where a player is on a team. E.G are they starting lineup or in the bullpen.
+             contact TEXT ,
+             defense TEXT,
+             power TEXT ,
+             speed TEXT ,
+             toughness TEXT,
+             trade_value INTEGER NOT NULL,
+             player_note TEXT,
+             FOREIGN KEY(team_id) REFERENCES teams(team_id)
+         )",
+        (),
+    )?;
+    // We also generate a season table.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS seasons(
+
+        season_id INTEGER PRIMARY KEY,
+        league_id INTEGER NOT NULL,
+        champion_id INTEGER,
+        FOREIGN KEY(league_id) REFERENCES leagues(league_id),
+        FOREIGN KEY(champion_id) REFERENCES teams(team_id))",
+        (),
+    )?;
+    // As well as a table of rounds.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS rounds(
+        round_id INTEGER PRIMARY KEY,
+        season_id INTEGER NOT NULL,
+        FOREIGN KEY (season_id) REFERENCES seasons(season_id)
+    )",
+        (),
+    )?;
+    // Ans series that are part of a round.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS series(
+    series_id INTEGER PRIMARY KEY,
+    round_id INTEGER NOT NULL,
+    home_team_id INTEGER NOT NULL,
+    away_team_id INTEGER NOT NULL,
+    FOREIGN KEY (round_id) REFERENCES rounds(round_id),
+    FOREIGN KEY (home_team_id) REFERENCES teams(team_id),
+    FOREIGN KEY (away_team_id) REFERENCES teams(team_id)
+    )",
+        (),
+    )?;
+    // And games that are part of a series.
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS games(
+        game_id INTEGER PRIMARY KEY,
+        series_id INTEGER NOT NULL,
+        home_score INTEGER DEFAULT 0,
+        away_score INTEGER DEFAULT 0,
+        game_not TEXT,
+        FOREIGN KEY (series_id) REFERENCES series(series_id)
+    )",
+        (),
+    )?;
+
+    conn.execute(
+        "
+        CREATE TABLE IF NOT EXISTS pennants(
+            pennant_id INTEGER PRIMARY KEY,
+            league_id INTEGER NOT NULL,
+            FOREIGN KEY (league_id) REFERENCES leagues(league_id)
+        
+        )
+    ",
+        (),
+    )?;
+
+    conn.execute(
+        "
+        CREATE TABLE IF NOT EXISTS pennants_standings(
+        standings_id INTEGER PRIMARY KEY,
+        pennant_id INTEGER NOT NULL,
+        team_id INTEGER NOT NULL,
+        wins INTEGER NOT NULL,
+        losses INTEGER NOT NULL,
+        FOREIGN KEY (pennant_id) REFERENCES pennants(pennant_id),
+        FOREIGN KEY(team_id) REFERENCES teams(team_id)
+        )
+    
+    ",
+        (),
+    )?;
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS league_archive(
+    archive_id INTEGER PRIMARY KEY,
+    league_id INTEGER NOT NULL,
+    date_saved TEXT NOT NULL,
+    league_data TEXT NOT NULL,
+    FOREIGN KEY (league_id) REFERENCES leagues(league_id))",
+        (),
+    )?;
+
+    // We create some indexes to optimize queries
+
+    // we create indexes on the foreign keys for the most important tables
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS players_team_id_index ON players(team_id);",
+        (),
+    )?;
+    conn.execute(
+        " CREATE INDEX IF NOT EXISTS teams_league_id_index ON teams(league_id);",
+        (),
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS teams_league_id_index ON teams(league_id);",
+        (),
+    )?;
+    // We also use querys that check if a player has a PD, so we create an index that tracks a players pd.
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS player_pd_index ON players(PD);",
+        (),
+    )?;
+    // As well as an index that tracks a players team ID as well as PD.
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS player_team_id_pd_index ON players(team_id,PD)",
+        (),
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS archive_index ON league_archive(league_id)",
+        (),
+    )?;
+    /*  We create a pitch die table, which is filled with the string version of each pitch die as well as the farthest value away from zero possible from the die.
+    This is used to to make querying pitcher die values easier, as the maximum absolute value of every pitch die is saved in this table.
+    The table is filled by a later migration, so databases where the table was created but never filled are fixed as well.
+     */
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS pitch_die(
+        die_id INTEGER PRIMARY KEY ,
+        die_text STRING UNIQUE NOT NULL,
+        die_int INTEGER NOT NULL)",
+        (),
+    )?;
+
+    /*conn.execute("CREATE TABLE IF NOT EXISTS team_seasons(
+    team_season_id INTEGER PRIMARY KEY,st
+    league_season_id INTEGER,
+    team_id INTEGER,
+    wins INTEGER DEFAULT 0,
+    losses INTEGER DEFAULT 0,
+
+
+    ", params) */
+    Ok(())
+}
+
+/// Adds a column to a table if the table does not already have it, so databases created by older versions of the program can still be opened.
+fn add_missing_column(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    definition: &str,
+) -> Result<(), rusqlite::Error> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({})", table))?;
+    let column_names: Vec<String> = stmt
+        .query_map([], |row| row.get(1))?
+        .filter_map(|x| x.ok())
+        .collect();
+    if !column_names.iter().any(|name| name == column) {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            (),
+        )?;
+    }
+    Ok(())
+}
+
+fn add_league_seed(conn: &Connection) -> Result<(), rusqlite::Error> {
+    add_missing_column(conn, "leagues", "seed", "INTEGER")
+}
+
+fn fill_pitch_die(conn: &Connection) -> Result<(), rusqlite::Error> {
+    for die in PD::ladder() {
+        conn.execute(
+            "INSERT OR IGNORE INTO pitch_die(die_text, die_int) VALUES(?1, ?2)",
+            [
+                serde_json::to_string(&die).unwrap(),
+                die.to_int().to_string(),
+            ],
+        )?;
+    }
+    Ok(())
+}
+
+/// Players that retire are removed from the players table, and a copy of the player is kept in the retired_players table.
+fn create_retired_players(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS retired_players(
+        retired_id INTEGER PRIMARY KEY,
+        league_id INTEGER NOT NULL,
+        team_id INTEGER NOT NULL,
+        player_id INTEGER NOT NULL, -- The id the player had in the players table.
+        player_name TEXT NOT NULL,
+        age INTEGER NOT NULL, -- The player's age when they retired.
+        pos TEXT NOT NULL,
+        team_spot TEXT NOT NULL,
+        tier TEXT NOT NULL,
+        trade_value INTEGER NOT NULL,
+        date_retired TEXT NOT NULL,
+        player_data TEXT NOT NULL, -- The full player, saved as JSON.
+        FOREIGN KEY (league_id) REFERENCES leagues(league_id),
+        FOREIGN KEY (team_id) REFERENCES teams(team_id))",
+        (),
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS retired_players_league_id_index ON retired_players(league_id)",
+        (),
+    )?;
+    Ok(())
+}
+
+/// Each team has a farm system of prospects. Only the prospect's name, hand and potential are saved, as the rest of the player is generated when they are called up.
+fn create_prospects(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS prospects(
+        prospect_id INTEGER PRIMARY KEY,
+        team_id INTEGER NOT NULL,
+        prospect_name TEXT NOT NULL,
+        hand TEXT NOT NULL,
+        is_pitcher INTEGER NOT NULL,
+        potential TEXT NOT NULL, -- The quality used to generate the player when they are called up. This is not shown to the user.
+        FOREIGN KEY (team_id) REFERENCES teams(team_id))",
+        (),
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS prospects_team_id_index ON prospects(team_id)",
+        (),
+    )?;
+    Ok(())
+}
+
+/// Each draft held by a league is saved in the drafts table, with each pick saved in the draft_picks table.
+fn create_draft_history(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS drafts(
+        draft_id INTEGER PRIMARY KEY,
+        league_id INTEGER NOT NULL,
+        date_held TEXT NOT NULL,
+        draft_order TEXT NOT NULL, -- If the draft order was based off team records or a pennant race.
+        FOREIGN KEY (league_id) REFERENCES leagues(league_id))",
+        (),
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS draft_picks(
+        pick_id INTEGER PRIMARY KEY,
+        draft_id INTEGER NOT NULL,
+        round INTEGER NOT NULL,
+        pick_number INTEGER NOT NULL, -- The overall number of the pick in the draft.
+        team_id INTEGER NOT NULL,
+        player_name TEXT NOT NULL,
+        pos TEXT NOT NULL,
+        tier TEXT NOT NULL,
+        destination TEXT NOT NULL, -- If the player joined the team's roster or farm system.
+        player_id INTEGER, -- The id of the player if they joined the roster.
+        prospect_id INTEGER, -- The id of the prospect if they joined the farm system.
+        FOREIGN KEY (draft_id) REFERENCES drafts(draft_id),
+        FOREIGN KEY (team_id) REFERENCES teams(team_id))",
+        (),
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS draft_picks_draft_id_index ON draft_picks(draft_id)",
+        (),
+    )?;
+    Ok(())
+}
+
+/// Each trade is saved in the trades table, with each player that was traded saved in the trade_players table.
+/// Team and player ids are not foreign keys, so the log is kept if a team is removed or a player retires.
+fn create_trade_log(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS trades(
+        trade_id INTEGER PRIMARY KEY,
+        league_id INTEGER NOT NULL,
+        date_made TEXT NOT NULL,
+        team_a_id INTEGER NOT NULL,
+        team_b_id INTEGER NOT NULL,
+        team_a_value INTEGER NOT NULL, -- The total trade value of the players team A gave up.
+        team_b_value INTEGER NOT NULL, -- The total trade value of the players team B gave up.
+        FOREIGN KEY (league_id) REFERENCES leagues(league_id))",
+        (),
+    )?;
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS trade_players(
+        trade_player_id INTEGER PRIMARY KEY,
+        trade_id INTEGER NOT NULL,
+        player_id INTEGER NOT NULL,
+        player_name TEXT NOT NULL,
+        from_team_id INTEGER NOT NULL,
+        to_team_id INTEGER NOT NULL,
+        trade_value INTEGER NOT NULL,
+        old_team_spot TEXT NOT NULL,
+        new_team_spot TEXT NOT NULL,
+        FOREIGN KEY (trade_id) REFERENCES trades(trade_id))",
+        (),
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS trade_players_trade_id_index ON trade_players(trade_id)",
+        (),
+    )?;
+    Ok(())
+}
+
+/// Free agents belong to a league instead of a team, so they are kept out of the players table until they sign with a team.
+fn create_free_agents(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS free_agents(
+        free_agent_id INTEGER PRIMARY KEY,
+        league_id INTEGER NOT NULL,
+        former_team_id INTEGER, -- The team that released the player, or NULL if the player was generated as a free agent.
+        player_name TEXT NOT NULL,
+        pos TEXT NOT NULL,
+        tier TEXT NOT NULL,
+        trade_value INTEGER NOT NULL,
+        date_added TEXT NOT NULL,
+        player_data TEXT NOT NULL, -- The full player, saved as JSON.
+        FOREIGN KEY (league_id) REFERENCES leagues(league_id))",
+        (),
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS free_agents_league_id_index ON free_agents(league_id)",
+        (),
+    )?;
+    Ok(())
+}
+
+/// Each injured player has a row in the injuries table until they heal.
+/// The player id is not a foreign key, as an injury is removed along with the player when they retire or are released.
+fn create_injuries(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS injuries(
+        injury_id INTEGER PRIMARY KEY,
+        player_id INTEGER NOT NULL,
+        severity TEXT NOT NULL,
+        games_missed INTEGER, -- NULL if the injury ends the player's season.
+        games_remaining INTEGER, -- The number of games left before the player returns, or NULL if the injury ends the player's season.
+        date_injured TEXT NOT NULL)",
+        (),
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS injuries_player_id_index ON injuries(player_id)",
+        (),
+    )?;
+    Ok(())
+}
+
+/// Every note written on a league, team, player or game is saved in the note_log table, which is used to show a league's timeline.
+/// Notes that were saved before the table was added are copied into it without a date, so they appear at the start of the timeline.
+fn create_note_log(conn: &Connection) -> Result<(), rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS note_log(
+        note_log_id INTEGER PRIMARY KEY,
+        league_id INTEGER NOT NULL,
+        subject_type TEXT NOT NULL, -- League, Team, Player or Game.
+        subject_id INTEGER NOT NULL,
+        subject_name TEXT NOT NULL, -- The name of the subject when the note was written.
+        action TEXT NOT NULL, -- Attached, Edited, Appended or Cleared.
+        note TEXT, -- The text that was written, or NULL if the note was cleared.
+        date_written TEXT, -- NULL for notes written before the note log was added.
+        FOREIGN KEY (league_id) REFERENCES leagues(league_id))",
+        (),
+    )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS note_log_league_id_index ON note_log(league_id)",
+        (),
+    )?;
+    // League, team and player notes are saved as JSON, while game notes are saved as plain text.
+    conn.execute_batch(
+        "INSERT INTO note_log(league_id, subject_type, subject_id, subject_name, action, note)
+        SELECT league_id, 'League', league_id, league_name, 'Attached', note
+        FROM (SELECT league_id, league_name,
+            CASE WHEN json_valid(league_note) THEN json_extract(league_note, '$') ELSE league_note END AS note
+            FROM leagues)
+        WHERE note IS NOT NULL;
+        INSERT INTO note_log(league_id, subject_type, subject_id, subject_name, action, note)
+        SELECT league_id, 'Team', team_id, team_name, 'Attached', note
+        FROM (SELECT league_id, team_id, team_name,
+            CASE WHEN json_valid(team_note) THEN json_extract(team_note, '$') ELSE team_note END AS note
+            FROM teams)
+        WHERE note IS NOT NULL;
+        INSERT INTO note_log(league_id, subject_type, subject_id, subject_name, action, note)
+        SELECT league_id, 'Player', player_id, player_name || ' (' || abrv || ')', 'Attached', note
+        FROM (SELECT teams.league_id, players.player_id, players.player_name, teams.abrv,
+            CASE WHEN json_valid(player_note) THEN json_extract(player_note, '$') ELSE player_note END AS note
+            FROM players INNER JOIN teams ON teams.team_id = players.team_id)
+        WHERE note IS NOT NULL;
+        INSERT INTO note_log(league_id, subject_type, subject_id, subject_name, action, note)
+        SELECT seasons.league_id, 'Game', games.game_id,
+            'Game ' || games.game_id || ': ' || away.abrv || ' @ ' || home.abrv, 'Attached', games.game_not
+        FROM games
+        INNER JOIN series ON series.series_id = games.series_id
+        INNER JOIN rounds ON rounds.round_id = series.round_id
+        INNER JOIN seasons ON seasons.season_id = rounds.season_id
+        INNER JOIN teams AS home ON home.team_id = series.home_team_id
+        INNER JOIN teams AS away ON away.team_id = series.away_team_id
+        WHERE games.game_not IS NOT NULL;",
+    )
+}
+
+/// Archives saved before this column was added have no free agent data, and are marked by a NULL.
+fn add_archive_free_agents(conn: &Connection) -> Result<(), rusqlite::Error> {
+    add_missing_column(conn, "league_archive", "free_agent_data", "TEXT")
+}
+
+/// Archives saved before these columns were added have no farm system or retired player data, and are marked by a NULL.
+fn add_archive_history(conn: &Connection) -> Result<(), rusqlite::Error> {
+    add_missing_column(conn, "league_archive", "prospect_data", "TEXT")?;
+    add_missing_column(conn, "league_archive", "retired_data", "TEXT")
+}
+
+/// A prospect sent to the farm system by the draft keeps the player they were drafted as, saved as JSON. Other prospects have a NULL, as they are generated when called up.
+fn add_drafted_players(conn: &Connection) -> Result<(), rusqlite::Error> {
+    add_missing_column(conn, "prospects", "player_data", "TEXT")
+}
+
+/// Returns the schema version of the database, creating the schema_version table if it does not exist.
+fn get_schema_version(conn: &Connection) -> Result<i64, rusqlite::Error> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version(version INTEGER NOT NULL)",
+        (),
+    )?;
+    let version: Option<i64> =
+        conn.query_row("SELECT MAX(version) FROM schema_version", [], |row| {
+            row.get(0)
+        })?;
+    Ok(version.unwrap_or(0))
+}
+
+/// Upgrades a database to the current schema version. If the database was created by a newer version of the program, a SchemaError is returned and the database is not changed.
+pub fn run_migrations(conn: &mut Connection) -> Result<(), EditLeagueError> {
+    let version = handle_sql_error(get_schema_version(conn))?;
+    if version > SCHEMA_VERSION {
+        return Err(EditLeagueError::SchemaError(format!(
+            "The database uses schema version {}, however this version of the program only supports up to version {}. Please update the program before opening this database.",
+            version, SCHEMA_VERSION
+        )));
+    }
+    let pending: Vec<&Migration> = MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > version)
+        .collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let tx = handle_sql_error(conn.transaction())?;
+    for migration in pending {
+        if let Err(message) = (migration.run)(&tx) {
+            return Err(EditLeagueError::SchemaError(format!(
+                "Unable to upgrade the database to version {} ({}). The database has not been changed.\nThe error was {}",
+                migration.version, migration.description, message
+            )));
+        }
+    }
+    handle_sql_error(tx.execute("DELETE FROM schema_version", ()))?;
+    handle_sql_error(tx.execute(
+        "INSERT INTO schema_version(version) VALUES(?1)",
+        [SCHEMA_VERSION],
+    ))?;
+    handle_sql_error(tx.commit())
+}
@@ -1,28 +1,65 @@
-use rand::rngs::ThreadRng;
+use core::fmt;
+
+use inquire::Select;
+use rand::Rng;
+use rusqlite::Connection;
+use rusqlite::OptionalExtension;
+use serde::Serialize;
 
 use crate::{
+    edit_league_error::{handle_serde_error, handle_sql_error, EditLeagueError},
     era::Era,
-    player::{Hand, Player, PlayerGender},
-    player_quality::PlayerQuality,
+    in_transaction, inquire_check,
+    league::{save_league, League},
+    player::{AgeCat, Hand, Player, PlayerGender},
+    player_quality::{BatterQuality, PitcherQuality, PlayerQuality},
+    position::PlayerPosition,
+    team::{Team, TeamSpot},
+    trade::roster_problems,
 };
 
-#[derive(Debug)]
+/* Each team has a farm system of prospects, which is saved in the prospects table.
+Only a prospect's name, hand and potential are saved, and the potential is never shown to the user. The rest of the player is generated when the prospect is called up.
+Players sent to the farm system by the draft are the exception, as the user has already seen them, so they are saved as they were drafted until they develop.
+Each offseason a prospect may develop, upgrading their potential, and any team with fewer prospects than the size of a farm system signs new prospects.
+A prospect can only be called up if it does not leave the team's roster out of shape, so a team with a full roster must open a spot first. */
+
+/// The number of batters and pitchers each team keeps in it's farm system.
+const FARM_BATTERS: usize = 3;
+const FARM_PITCHERS: usize = 3;
+
+/// The positions a batter can be called up to play.
+pub const CALL_UP_POSITIONS: [&str; 11] = [
+    "C", "1B", "2B", "3B", "SS", "LF", "CF", "RF", "INF", "OF", "UT",
+];
+
+#[derive(Debug, Clone)]
 /// When a Deadball team has a player in the farm system,only a few fields for the player are generated, and hte rest are created when a player is promoted.
 /// This struct contains what is known about a player, including quality, that is used to generate the player once it is promoted.
-struct MinorLeaguer<PotentialQuality: PlayerQuality> {
+pub struct MinorLeaguer<PotentialQuality: PlayerQuality> {
+    pub prospect_id: i64,
     potential_quality: PotentialQuality,
-    name: String,
-    hand: Hand,
-    gender: PlayerGender,
+    pub name: String,
+    pub hand: Hand,
     era: Era,
 }
 
-impl<T: PlayerQuality> MinorLeaguer<T> {
+impl<T: PlayerQuality + Serialize> MinorLeaguer<T> {
+    fn new<R: Rng>(potential_quality: T, gender: PlayerGender, era: Era, thread: &mut R) -> Self {
+        MinorLeaguer {
+            prospect_id: 0,
+            name: gender.new_name(thread),
+            hand: potential_quality.get_hand(thread),
+            potential_quality,
+            era,
+        }
+    }
+
     fn upgrade(&mut self) {
         self.potential_quality = self.potential_quality.upgrade()
     }
 
-    fn unveil(&self, thread: &mut ThreadRng) -> Player {
+    fn unveil<R: Rng>(&self, thread: &mut R) -> Player {
         let gen_player = self.potential_quality.gen_player(thread, self.era);
 
         Player {
@@ -31,4 +68,460 @@ impl<T: PlayerQuality> MinorLeaguer<T> {
             ..gen_player
         }
     }
+
+    fn potential_json(&self) -> Result<String, EditLeagueError> {
+        handle_serde_error(serde_json::to_string(&self.potential_quality))
+    }
+}
+
+/// A prospect in a team's farm system, who will either be called up as a batter or as a pitcher.
+#[derive(Debug, Clone)]
+pub enum Prospect {
+    Batter(MinorLeaguer<BatterQuality>),
+    Pitcher(MinorLeaguer<PitcherQuality>),
+}
+
+impl fmt::Display for Prospect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Batter(prospect) => {
+                write!(f, "{}, Batter, Bats {}", prospect.name, prospect.hand)
+            }
+            Self::Pitcher(prospect) => {
+                write!(f, "{}, Pitcher, Throws {}", prospect.name, prospect.hand)
+            }
+        }
+    }
+}
+
+impl Prospect {
+    /// Randomly generates a new prospect. Most prospects are farmhands, however 1 in 6 are top prospects.
+    pub fn new<R: Rng>(for_pitcher: bool, gender: PlayerGender, era: Era, thread: &mut R) -> Self {
+        let top_prospect = thread.gen_range(1..=6) == 6;
+        match (for_pitcher, top_prospect) {
+            (false, true) => Self::Batter(MinorLeaguer::new(
+                BatterQuality::TopProspect,
+                gender,
+                era,
+                thread,
+            )),
+            (false, false) => Self::Batter(MinorLeaguer::new(
+                BatterQuality::Farmhand,
+                gender,
+                era,
+                thread,
+            )),
+            (true, true) => Self::Pitcher(MinorLeaguer::new(
+                PitcherQuality::TopProspect,
+                gender,
+                era,
+                thread,
+            )),
+            (true, false) => Self::Pitcher(MinorLeaguer::new(
+                PitcherQuality::Farmhand,
+                gender,
+                era,
+                thread,
+            )),
+        }
+    }
+
+    pub fn prospect_id(&self) -> i64 {
+        match self {
+            Self::Batter(prospect) => prospect.prospect_id,
+            Self::Pitcher(prospect) => prospect.prospect_id,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Batter(prospect) => &prospect.name,
+            Self::Pitcher(prospect) => &prospect.name,
+        }
+    }
+
+    pub fn is_pitcher(&self) -> bool {
+        matches!(self, Self::Pitcher(_))
+    }
+
+    fn hand(&self) -> Hand {
+        match self {
+            Self::Batter(prospect) => prospect.hand,
+            Self::Pitcher(prospect) => prospect.hand,
+        }
+    }
+
+    fn potential_json(&self) -> Result<String, EditLeagueError> {
+        match self {
+            Self::Batter(prospect) => prospect.potential_json(),
+            Self::Pitcher(prospect) => prospect.potential_json(),
+        }
+    }
+
+    /// Rolls a d6 to see if the prospect develops, which happens on a 5 or 6.
+    /// Returns true if the prospect developed.
+    fn develop<R: Rng>(&mut self, thread: &mut R) -> bool {
+        if thread.gen_range(1..=6) < 5 {
+            return false;
+        }
+        match self {
+            Self::Batter(prospect) => prospect.upgrade(),
+            Self::Pitcher(prospect) => prospect.upgrade(),
+        }
+        true
+    }
+
+    pub fn unveil<R: Rng>(&self, thread: &mut R) -> Player {
+        match self {
+            Self::Batter(prospect) => prospect.unveil(thread),
+            Self::Pitcher(prospect) => prospect.unveil(thread),
+        }
+    }
+
+    pub fn save_sql(&mut self, conn: &Connection, team_id: i64) -> Result<(), EditLeagueError> {
+        let hand = handle_serde_error(serde_json::to_string(&self.hand()))?;
+        let potential = self.potential_json()?;
+        let mut stmt = handle_sql_error(conn.prepare_cached(
+            "INSERT INTO prospects(team_id, prospect_name, hand, is_pitcher, potential) VALUES(?1, ?2, ?3, ?4, ?5)",
+        ))?;
+        let prospect_id = handle_sql_error(stmt.insert((
+            team_id,
+            self.name(),
+            hand,
+            self.is_pitcher(),
+            potential,
+        )))?;
+        match self {
+            Self::Batter(prospect) => prospect.prospect_id = prospect_id,
+            Self::Pitcher(prospect) => prospect.prospect_id = prospect_id,
+        }
+        Ok(())
+    }
+}
+
+/// Loads every prospect in a team's farm system.
+pub fn load_farm(
+    conn: &Connection,
+    team_id: i64,
+    era: Era,
+) -> Result<Vec<Prospect>, EditLeagueError> {
+    let mut stmt = handle_sql_error(conn.prepare_cached(
+        "SELECT prospect_id, prospect_name, hand, is_pitcher, potential FROM prospects WHERE team_id = ?1 ORDER BY prospect_id",
+    ))?;
+    let rows = handle_sql_error(stmt.query_map([team_id], |row| {
+        Ok((
+            row.get::<_, i64>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, String>(2)?,
+            row.get::<_, bool>(3)?,
+            row.get::<_, String>(4)?,
+        ))
+    }))?;
+    let mut farm = Vec::new();
+    for row in rows {
+        let (prospect_id, name, hand, is_pitcher, potential) = handle_sql_error(row)?;
+        let hand: Hand = handle_serde_error(serde_json::from_str(&hand))?;
+        let prospect = match is_pitcher {
+            true => Prospect::Pitcher(MinorLeaguer {
+                prospect_id,
+                potential_quality: handle_serde_error(serde_json::from_str(&potential))?,
+                name,
+                hand,
+                era,
+            }),
+            false => Prospect::Batter(MinorLeaguer {
+                prospect_id,
+                potential_quality: handle_serde_error(serde_json::from_str(&potential))?,
+                name,
+                hand,
+                era,
+            }),
+        };
+        farm.push(prospect);
+    }
+    Ok(farm)
+}
+
+/// Saves the player a drafted prospect was shown as, so they are called up as the same player.
+pub fn save_drafted_player(
+    conn: &Connection,
+    prospect_id: i64,
+    player: &Player,
+) -> Result<(), EditLeagueError> {
+    let player_data = handle_serde_error(serde_json::to_string(player))?;
+    handle_sql_error(conn.execute(
+        "UPDATE prospects SET player_data = ?1 WHERE prospect_id = ?2",
+        (player_data, prospect_id),
+    ))?;
+    Ok(())
+}
+
+/// Returns the player a drafted prospect was shown as, or None if the prospect will be generated when they are called up.
+pub fn drafted_player(
+    conn: &Connection,
+    prospect_id: i64,
+) -> Result<Option<Player>, EditLeagueError> {
+    let player_data: Option<Option<String>> = handle_sql_error(
+        conn.query_row(
+            "SELECT player_data FROM prospects WHERE prospect_id = ?1",
+            [prospect_id],
+            |row| row.get(0),
+        )
+        .optional(),
+    )?;
+    match player_data.flatten() {
+        Some(data) => Ok(Some(handle_serde_error(serde_json::from_str(&data))?)),
+        None => Ok(None),
+    }
+}
+
+/// Signs new prospects until a team's farm system is full. Returns the prospects that were signed.
+pub fn fill_farm<R: Rng>(
+    conn: &Connection,
+    team_id: i64,
+    gender: PlayerGender,
+    era: Era,
+    thread: &mut R,
+) -> Result<Vec<Prospect>, EditLeagueError> {
+    let farm = load_farm(conn, team_id, era)?;
+    let pitchers = farm.iter().filter(|prospect| prospect.is_pitcher()).count();
+    let batters = farm.len() - pitchers;
+    let needed = [
+        (false, FARM_BATTERS.saturating_sub(batters)),
+        (true, FARM_PITCHERS.saturating_sub(pitchers)),
+    ];
+    let mut signed = Vec::new();
+    for (for_pitcher, count) in needed {
+        for _ in 0..count {
+            let mut prospect = Prospect::new(for_pitcher, gender, era, thread);
+            prospect.save_sql(conn, team_id)?;
+            signed.push(prospect);
+        }
+    }
+    Ok(signed)
+}
+
+/// Rolls for every prospect in a league to see if they develop, and fills each team's farm system.
+pub fn develop_farms<R: Rng>(
+    conn: &mut Connection,
+    league: &League,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    let (gender, era) = (league.gender, league.era);
+    in_transaction(conn, |conn| {
+        for team in league.teams.iter() {
+            for mut prospect in load_farm(conn, team.team_id, era)? {
+                if prospect.develop(thread) {
+                    // A drafted prospect that develops is generated from their new potential when they are called up.
+                    handle_sql_error(conn.execute(
+                        "UPDATE prospects SET potential = ?1, player_data = NULL WHERE prospect_id = ?2",
+                        (prospect.potential_json()?, prospect.prospect_id()),
+                    ))?;
+                }
+            }
+            fill_farm(conn, team.team_id, gender, era, thread)?;
+        }
+        Ok(())
+    })
+}
+
+/// Returns the team spot and position a player is given when they join a team from the farm system or the draft.
+/// Batters join the bench, while pitchers join the bullpen, or the rotation in the Ancient Era as those teams do not have a bullpen.
+pub fn roster_spot(is_pitcher: bool, era: Era, batter_pos: &str) -> (TeamSpot, &'static str) {
+    match (is_pitcher, era) {
+        (true, Era::Modern) => (TeamSpot::Bullpen, "RP"),
+        (true, Era::Ancient) => (TeamSpot::StartingPitcher, "P"),
+        (false, _) => {
+            let pos = CALL_UP_POSITIONS
+                .into_iter()
+                .find(|pos| *pos == batter_pos)
+                .unwrap_or("UT");
+            (TeamSpot::BenchHitter, pos)
+        }
+    }
+}
+
+/// Adds a player that has already been saved to the database to a team's roster, and updates the team score.
+pub fn push_to_roster(team: &mut Team, player: Player, team_spot: TeamSpot) {
+    match team_spot {
+        TeamSpot::StartingLineup => team.lineup.push(player),
+        TeamSpot::BenchHitter => team.bench.push(player),
+        TeamSpot::StartingPitcher => team.starting_pitching.push(player),
+        TeamSpot::Bullpen => team.bullpen.get_or_insert_with(Vec::new).push(player),
+    }
+    team.calc_team_score();
+}
+
+/// Calls up a prospect from a team's farm system, generating the rest of the player and adding them to the team.
+/// Batters are given the position batter_pos, which is ignored for pitchers.
+/// A RosterError is returned if the call up would cause a roster problem the team did not already have.
+pub fn call_up<R: Rng>(
+    conn: &mut Connection,
+    league: &mut League,
+    team_index: usize,
+    prospect: &Prospect,
+    batter_pos: &str,
+    thread: &mut R,
+) -> Result<Player, EditLeagueError> {
+    let era = league.era;
+    let team = &mut league.teams[team_index];
+    let (team_spot, pos) = roster_spot(prospect.is_pitcher(), era, batter_pos);
+    let mut player = match drafted_player(conn, prospect.prospect_id())? {
+        Some(player) => player,
+        None => {
+            let mut player = prospect.unveil(thread);
+            player.age = AgeCat::Prospect.new_age(thread);
+            player
+        }
+    };
+    player.pos = handle_serde_error(serde_json::from_value::<PlayerPosition>(pos.into()))?;
+    let mut new_team = team.clone();
+    push_to_roster(&mut new_team, player.clone(), team_spot);
+    let old_problems = roster_problems(team, era);
+    let problems: Vec<String> = roster_problems(&new_team, era)
+        .into_iter()
+        .filter(|problem| !old_problems.contains(problem))
+        .collect();
+    if !problems.is_empty() {
+        return Err(EditLeagueError::RosterError(format!(
+            "{} can not be called up.\n{}",
+            prospect.name(),
+            problems.join("\n")
+        )));
+    }
+    let team_id = team.team_id;
+    in_transaction(conn, |conn| {
+        let deleted = handle_sql_error(conn.execute(
+            "DELETE FROM prospects WHERE prospect_id = ?1 AND team_id = ?2",
+            (prospect.prospect_id(), team_id),
+        ))?;
+        if deleted == 0 {
+            return Err(EditLeagueError::RosterError(format!(
+                "{} is not in the farm system of {}.",
+                prospect.name(),
+                team.name
+            )));
+        }
+        player.save_sql(conn, team_id, team_spot)
+    })?;
+    push_to_roster(team, player.clone(), team_spot);
+    Ok(player)
+}
+
+/// Prompts the user to choose a team and a prospect from it's farm system, and calls the prospect up to the team.
+pub fn call_up_from_input<R: Rng>(
+    league: &mut League,
+    conn: &mut Connection,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    let team_names: Vec<String> = league.teams.iter().map(|team| team.name.clone()).collect();
+    let team_name = match Select::new(
+        "Which team's farm system would you like to view?",
+        team_names,
+    )
+    .prompt()
+    {
+        Ok(name) => name,
+        Err(message) => return inquire_check(message),
+    };
+    let team_index = match league.teams.iter().position(|team| team.name == team_name) {
+        Some(index) => index,
+        None => return Ok(()),
+    };
+    let team_id = league.teams[team_index].team_id;
+    // Teams that were created before farm systems were added are given a farm system the first time it is viewed.
+    let farm = in_transaction(conn, |conn| {
+        fill_farm(conn, team_id, league.gender, league.era, thread)?;
+        load_farm(conn, team_id, league.era)
+    })?;
+    let prospect = match Select::new("Which prospect would you like to call up?", farm).prompt() {
+        Ok(prospect) => prospect,
+        Err(message) => return inquire_check(message),
+    };
+    let batter_pos = match prospect.is_pitcher() {
+        true => "P",
+        false => {
+            let options = CALL_UP_POSITIONS.to_vec();
+            match Select::new("Which position will the prospect play?", options).prompt() {
+                Ok(pos) => pos,
+                Err(message) => return inquire_check(message),
+            }
+        }
+    };
+    let player = call_up(conn, league, team_index, &prospect, batter_pos, thread)?;
+    println!(
+        "{} has been called up to {}.\n{}",
+        player.name, league.teams[team_index].name, player
+    );
+    save_league(league);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::free_agent::release_player;
+    use crate::league::load_teams_from_sql;
+    use crate::offseason::run_offseason_with_retirements;
+    use crate::{player_pool_test, test_league};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn farm_system() {
+        let (mut conn, mut league) = test_league(Era::Modern, PlayerGender::Coed, 8);
+        let mut thread = StdRng::seed_from_u64(18);
+        let team_id = league.teams[0].team_id;
+        let farm = load_farm(&conn, team_id, league.era).unwrap();
+        assert_eq!(farm.len(), 6);
+        assert_eq!(farm.iter().filter(|x| x.is_pitcher()).count(), 3);
+        // A prospect's potential is never shown.
+        for prospect in farm.iter() {
+            let text = prospect.to_string();
+            assert!(!text.contains("Prospect") && !text.contains("Farmhand"));
+        }
+        let batter = farm.iter().find(|x| !x.is_pitcher()).unwrap();
+        let pitcher = farm.iter().find(|x| x.is_pitcher()).unwrap();
+        // A team with a full roster has no room for a prospect.
+        let result = call_up(&mut conn, &mut league, 0, batter, "SS", &mut thread);
+        assert!(matches!(result, Err(EditLeagueError::RosterError(_))));
+        assert_eq!(load_farm(&conn, team_id, league.era).unwrap().len(), 6);
+        // Releasing a bench player and a reliever opens a spot for each prospect.
+        let bench_id = league.teams[0].bench[0].player_id;
+        let reliever_id = league.teams[0].bullpen.as_ref().unwrap()[0].player_id;
+        release_player(&mut conn, &mut league, 0, bench_id).unwrap();
+        release_player(&mut conn, &mut league, 0, reliever_id).unwrap();
+        let bench_len = league.teams[0].bench.len();
+        let player = call_up(&mut conn, &mut league, 0, batter, "SS", &mut thread).unwrap();
+        assert_eq!(player.name, batter.name());
+        assert!(player.pd.is_none());
+        call_up(&mut conn, &mut league, 0, pitcher, "SS", &mut thread).unwrap();
+        // A prospect can only be called up once, even when there is room for them.
+        let reliever_id = league.teams[0].bullpen.as_ref().unwrap()[0].player_id;
+        release_player(&mut conn, &mut league, 0, reliever_id).unwrap();
+        let result = call_up(&mut conn, &mut league, 0, pitcher, "SS", &mut thread);
+        assert!(matches!(result, Err(EditLeagueError::RosterError(_))));
+        let mut loaded = League::new(
+            &league.name,
+            league.gender,
+            league.era,
+            league.league_id,
+            None,
+        );
+        load_teams_from_sql(league.league_id, &mut loaded, &mut conn).unwrap();
+        let team = &loaded.teams[0];
+        assert_eq!(team.bench.len(), bench_len + 1);
+        assert!(team
+            .bench
+            .iter()
+            .any(|x| x.name == player.name && x.pos == PlayerPosition::SS));
+        assert_eq!(team.bullpen.as_ref().unwrap().len(), 6);
+        player_pool_test(&team.bench, team_id, false);
+        player_pool_test(team.bullpen.as_ref().unwrap(), team_id, true);
+        let farm = load_farm(&conn, team_id, league.era).unwrap();
+        assert_eq!(farm.len(), 4);
+        // The farm system is filled again during the offseason.
+        run_offseason_with_retirements(&mut conn, &mut league, &mut thread).unwrap();
+        let farm = load_farm(&conn, team_id, league.era).unwrap();
+        assert_eq!(farm.len(), 6);
+    }
 }
@@ -0,0 +1,44 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::sync::OnceLock;
+
+/* Player names are drawn from the name lists of the name_maker crate, which are embedded in the program under the MIT license found in the names folder.
+Picking names with the same random number generator as the rest of a player means a league generated from a seed always has the same names.*/
+
+const MALE_FIRST_NAMES: &str = include_str!("names/male_first_names.txt");
+const FEMALE_FIRST_NAMES: &str = include_str!("names/female_first_names.txt");
+const LAST_NAMES: &str = include_str!("names/last_names.txt");
+
+/// A list of names, which is only split into lines the first time it is used.
+struct NameBank {
+    raw: &'static str,
+    names: OnceLock<Vec<&'static str>>,
+}
+
+impl NameBank {
+    const fn new(raw: &'static str) -> NameBank {
+        NameBank {
+            raw,
+            names: OnceLock::new(),
+        }
+    }
+
+    fn choose<R: Rng>(&self, thread: &mut R) -> &'static str {
+        let names = self.names.get_or_init(|| self.raw.lines().collect());
+        names.choose(thread).copied().unwrap_or_default()
+    }
+}
+
+static MALE_BANK: NameBank = NameBank::new(MALE_FIRST_NAMES);
+static FEMALE_BANK: NameBank = NameBank::new(FEMALE_FIRST_NAMES);
+static LAST_BANK: NameBank = NameBank::new(LAST_NAMES);
+
+/// Returns a random full name, with a masculine first name if is_male is true, and a feminine one otherwise.
+pub fn random_name<R: Rng>(is_male: bool, thread: &mut R) -> String {
+    let first_name = match is_male {
+        true => MALE_BANK.choose(thread),
+        false => FEMALE_BANK.choose(thread),
+    };
+    let last_name = LAST_BANK.choose(thread);
+    format!("{} {}", first_name, last_name)
+}
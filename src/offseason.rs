@@ -0,0 +1,333 @@
+use core::fmt;
+use std::fs;
+
+use crate::edit_league_error::handle_serde_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::era::Era;
+use crate::in_transaction;
+use crate::injury::heal_all_injuries;
+use crate::inquire_check;
+use crate::league::save_league;
+use crate::league::League;
+use crate::minor_leaguer::develop_farms;
+use crate::player::AgeCat;
+use crate::player::Player;
+use crate::retirement::run_retirements;
+use crate::settings::output_path;
+use crate::team::Team;
+use crate::team::TeamSpot;
+use chrono::{Datelike, Local};
+use inquire::Confirm;
+use rand::Rng;
+use rusqlite::Connection;
+
+/* Between seasons, every player in a league gets one year older.
+Each player then rolls a d6 to see if they improve, decline or stay the same, with the odds based off their age category.
+Batters that change either gain or lose a point of BT, or have one of their traits upgraded or downgraded, while pitchers move their pitch die up or down a step among the dice used in their era.
+After every player has aged, older players may retire and be replaced, which is handled in retirement.rs, and each team's prospects may develop, which is handled in minor_leaguer.rs.*/
+
+/// The changes made to a team's players during the offseason.
+pub struct TeamOffseasonReport {
+    pub team_name: String,
+    pub old_team_score: i32,
+    pub new_team_score: i32,
+    pub improved: Vec<String>,
+    pub declined: Vec<String>,
+    pub retired: Vec<String>,
+}
+
+impl fmt::Display for TeamOffseasonReport {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(
+            f,
+            "{}, Team Score: {} -> {}",
+            self.team_name, self.old_team_score, self.new_team_score
+        )?;
+        let sections = [
+            ("Improved", &self.improved),
+            ("Declined", &self.declined),
+            ("Retired", &self.retired),
+        ];
+        for (header, lines) in sections {
+            if lines.is_empty() {
+                continue;
+            }
+            writeln!(f, "{}:", header)?;
+            for line in lines.iter() {
+                writeln!(f, "    {}", line)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns a description of a player's traits, where a player without traits is shown as none.
+fn traits_string(player: &Player) -> String {
+    match player.b_traits.to_string().as_str() {
+        "" => "none".to_string(),
+        text => text.to_string(),
+    }
+}
+
+/// Moves a pitcher's pitch die a step up or down among the pitch dice of their era, stopping at the best and worst die.
+/// A pitcher with a die from outside the era steps from the best era die that does not exceed it.
+pub fn progress_pitcher(player: &mut Player, era: Era, direction: i32) -> Option<String> {
+    let old_pd = player.pd?;
+    let dice = era.get_all_pd();
+    let max_index = dice.len() as i32 - 1;
+    let index = dice
+        .iter()
+        .position(|die| die.to_int() <= old_pd.to_int())
+        .unwrap_or(dice.len() - 1) as i32;
+    let new_pd = dice[(index - direction).clamp(0, max_index) as usize];
+    if new_pd == old_pd {
+        return None;
+    }
+    player.pd = Some(new_pd);
+    Some(format!("PD {} -> {}", old_pd, new_pd))
+}
+
+/// Changes either a batter's traits or their BT. If the chosen traits can not change any further, the BT is changed instead.
+fn progress_batter<R: Rng>(player: &mut Player, direction: i32, thread: &mut R) -> Option<String> {
+    if thread.gen_bool(0.5) {
+        let old_traits = traits_string(player);
+        let new_b_traits = match direction > 0 {
+            true => player.b_traits.upgrade_random_traits(thread),
+            false => player.b_traits.downgrade_random_traits(thread),
+        };
+        if new_b_traits != player.b_traits {
+            player.b_traits = new_b_traits;
+            return Some(format!(
+                "Traits {} -> {}",
+                old_traits,
+                traits_string(player)
+            ));
+        }
+    }
+    let new_bt = (player.bt + direction).max(1);
+    if new_bt == player.bt {
+        return None;
+    }
+    let (old_bt, old_obt) = (player.bt, player.obt);
+    player.bt = new_bt;
+    player.obt = player.bt + player.obt_mod;
+    Some(format!(
+        "BT {} -> {}, OBT {} -> {}",
+        old_bt, player.bt, old_obt, player.obt
+    ))
+}
+
+/// Ages a player by one year, and rolls to see if they improve or decline.
+/// Returns 1 and a description if the player improved, -1 and a description if they declined, or None if they did not change.
+pub fn progress_player<R: Rng>(
+    player: &mut Player,
+    era: Era,
+    thread: &mut R,
+) -> Option<(i32, String)> {
+    player.age += 1;
+    let direction = AgeCat::from_age(player.age).progression(thread.gen_range(1..=6));
+    if direction == 0 {
+        return None;
+    }
+    let change = match player.is_pitcher() {
+        true => progress_pitcher(player, era, direction)?,
+        false => progress_batter(player, direction, thread)?,
+    };
+    player.trade_value = player.calc_trade_value();
+    Some((
+        direction,
+        format!(
+            "{} ({}, {}): {}",
+            player.name, player.pos, player.age, change
+        ),
+    ))
+}
+
+/// Runs the offseason for a team, and saves every player to the database.
+fn team_offseason<R: Rng>(
+    conn: &Connection,
+    team: &mut Team,
+    era: Era,
+    thread: &mut R,
+) -> Result<TeamOffseasonReport, EditLeagueError> {
+    let mut report = TeamOffseasonReport {
+        team_name: team.name.to_string(),
+        old_team_score: team.team_score,
+        new_team_score: team.team_score,
+        improved: Vec::new(),
+        declined: Vec::new(),
+        retired: Vec::new(),
+    };
+    let pools = [
+        (TeamSpot::StartingLineup, &mut team.lineup),
+        (TeamSpot::BenchHitter, &mut team.bench),
+        (TeamSpot::StartingPitcher, &mut team.starting_pitching),
+    ];
+    let bullpen = team.bullpen.iter_mut().map(|pen| (TeamSpot::Bullpen, pen));
+    for (team_spot, pool) in pools.into_iter().chain(bullpen) {
+        for player in pool.iter_mut() {
+            match progress_player(player, era, thread) {
+                Some((1, line)) => report.improved.push(line),
+                Some((_, line)) => report.declined.push(line),
+                None => {}
+            }
+            let player_id = player.player_id;
+            let row = handle_serde_error(player.get_row(team_spot))?;
+            handle_sql_error(row.update_sql(conn, player_id))?;
+        }
+    }
+    team.calc_team_score();
+    report.new_team_score = team.team_score;
+    Ok(report)
+}
+
+/// Ages every player in a league by one year and applies their progression. Every change is saved in a single transaction.
+pub fn run_offseason<R: Rng>(
+    conn: &mut Connection,
+    league: &mut League,
+    thread: &mut R,
+) -> Result<Vec<TeamOffseasonReport>, EditLeagueError> {
+    let era = league.era;
+    in_transaction(conn, |conn| {
+        league
+            .teams
+            .iter_mut()
+            .map(|team| team_offseason(conn, team, era, thread))
+            .collect()
+    })
+}
+
+/// Runs the offseason for a league, followed by retirements and the development of each team's farm system. If any of these fail, no changes are saved.
+pub fn run_offseason_with_retirements<R: Rng>(
+    conn: &mut Connection,
+    league: &mut League,
+    thread: &mut R,
+) -> Result<Vec<TeamOffseasonReport>, EditLeagueError> {
+    in_transaction(conn, |conn| {
+        heal_all_injuries(conn, league)?;
+        let mut reports = run_offseason(conn, league, thread)?;
+        let retirements = run_retirements(conn, league, thread)?;
+        develop_farms(conn, league, thread)?;
+        for ((report, retired), team) in reports.iter_mut().zip(retirements).zip(&league.teams) {
+            report.retired = retired;
+            report.new_team_score = team.team_score;
+        }
+        Ok(reports)
+    })
+}
+
+/// Prints the offseason reports, saves them to a file, and refreshes the league's folder.
+pub fn save_offseason_reports(league: &League, reports: &[TeamOffseasonReport]) {
+    let text = reports
+        .iter()
+        .map(|report| report.to_string())
+        .collect::<Vec<String>>()
+        .join("\n");
+    println!("{}", text);
+    let now = Local::now();
+    let file_name = output_path(&format!(
+        "{}_Offseason_{}_{}_{}.txt",
+        league.name,
+        now.year(),
+        now.month(),
+        now.day()
+    ));
+    match fs::write(&file_name, text) {
+        Ok(()) => println!("Offseason report saved to {}", file_name.display()),
+        Err(message) => println!(
+            "Unable to save the offseason report to a file.\nThe error was {}",
+            message
+        ),
+    }
+    save_league(league);
+}
+
+/// Asks the user to confirm, then runs the offseason for a league.
+pub fn offseason_from_input<R: Rng>(
+    league: &mut League,
+    conn: &mut Connection,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    let message = format!(
+        "Every player in {} will be one year older, and may improve, decline or retire. Would you like to continue?",
+        league.name
+    );
+    match Confirm::new(&message).prompt() {
+        Ok(true) => {}
+        Ok(false) => return Ok(()),
+        Err(message) => return inquire_check(message),
+    }
+    let reports = run_offseason_with_retirements(conn, league, thread)?;
+    save_offseason_reports(league, &reports);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::b_traits::BTraits;
+    use crate::league::load_teams_from_sql;
+    use crate::pd::PD;
+    use crate::player::PlayerGender;
+    use crate::position::PlayerPosition;
+    use crate::{player_pool_test, test_league};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn offseason_progression() {
+        // Batters can always gain a trait unless every trait is maxed out.
+        let mut seeded = StdRng::seed_from_u64(1);
+        assert_ne!(
+            BTraits::default().upgrade_random_traits(&mut seeded),
+            BTraits::default()
+        );
+        assert_eq!(
+            BTraits::maxed_out().upgrade_random_traits(&mut seeded),
+            BTraits::maxed_out()
+        );
+        let downgraded = BTraits::default().downgrade_random_traits(&mut seeded);
+        assert!(downgraded.to_int() < 0);
+        // Every generated rookie progresses as a rookie.
+        for _ in 0..20 {
+            let age = AgeCat::Rookie.new_age(&mut seeded);
+            assert!(matches!(AgeCat::from_age(age), AgeCat::Rookie));
+        }
+        let (mut conn, mut league) = test_league(Era::Modern, PlayerGender::Male, 8);
+        let old_ages: Vec<i32> = league.teams[0].lineup.iter().map(|p| p.age).collect();
+        let reports = run_offseason(&mut conn, &mut league, &mut seeded).unwrap();
+        assert_eq!(reports.len(), 2);
+        let mut loaded = League::new(
+            &league.name,
+            league.gender,
+            league.era,
+            league.league_id,
+            None,
+        );
+        load_teams_from_sql(league.league_id, &mut loaded, &mut conn).unwrap();
+        let new_ages: Vec<i32> = loaded.teams[0].lineup.iter().map(|p| p.age).collect();
+        assert_eq!(
+            new_ages,
+            old_ages.iter().map(|age| age + 1).collect::<Vec<i32>>()
+        );
+        assert_eq!(loaded.teams[0].team_score, reports[0].new_team_score);
+        for team in loaded.teams.iter() {
+            player_pool_test(&team.lineup, team.team_id, false);
+            player_pool_test(&team.starting_pitching, team.team_id, true);
+        }
+        // Pitchers only move between the pitch dice of their era.
+        let mut pitcher = Player {
+            pd: Some(PD::DM4),
+            pos: PlayerPosition::SP,
+            ..Player::default()
+        };
+        assert!(progress_pitcher(&mut pitcher, Era::Modern, -1).is_none());
+        assert_eq!(pitcher.pd, Some(PD::DM4));
+        progress_pitcher(&mut pitcher, Era::Modern, 1).unwrap();
+        assert_eq!(pitcher.pd, Some(PD::D4));
+        progress_pitcher(&mut pitcher, Era::Modern, 1).unwrap();
+        assert_eq!(pitcher.pd, Some(PD::D8));
+        pitcher.pd = Some(PD::D12);
+        assert!(progress_pitcher(&mut pitcher, Era::Modern, 1).is_none());
+    }
+}
@@ -1,5 +1,6 @@
 use inquire::Confirm;
 use inquire::Select;
+use rand::seq::SliceRandom;
 use rusqlite::Connection;
 
 use crate::era::Era;
@@ -11,6 +12,7 @@ use crate::Deserialize;
 
 use crate::Serialize;
 use core::fmt;
+use rand::Rng;
 
 struct PDInfo(i32, bool);
 
@@ -112,6 +114,41 @@ impl PD {
         let range_sum: i32 = range.iter().sum();
         range_sum as f32 / range_len
     }
+    /// Rolls the pitch die. Negative dice return a negative number, and a pitcher with no dice always rolls a 0.
+    pub fn roll<R: Rng>(&self, thread: &mut R) -> i32 {
+        self.get_range().choose(thread).copied().unwrap_or(0)
+    }
+    /// Returns every standard pitch die, ordered from best to worst.
+    pub fn ladder() -> [PD; 11] {
+        [
+            PD::D20,
+            PD::D12,
+            PD::D8,
+            PD::D6,
+            PD::D4,
+            PD::D0,
+            PD::DM4,
+            PD::DM6,
+            PD::DM8,
+            PD::DM12,
+            PD::DM20,
+        ]
+    }
+    /// Returns where the die sits on the ladder. Custom dice are placed with the best standard die they do not exceed.
+    fn ladder_index(&self) -> usize {
+        let num = self.to_int();
+        PD::ladder()
+            .iter()
+            .position(|die| die.to_int() <= num)
+            .unwrap_or(PD::ladder().len() - 1)
+    }
+    /// Returns the die that is a number of steps away on the ladder. Positive steps move toward a d20, negative steps toward a -d20.
+    pub fn step(&self, steps: i32) -> PD {
+        let ladder = PD::ladder();
+        let max_index = (ladder.len() - 1) as i32;
+        let new_index = (self.ladder_index() as i32 - steps).clamp(0, max_index);
+        ladder[new_index as usize]
+    }
 
     pub fn fix_db(
         input_opt: Result<Option<Self>, serde_json::Error>,
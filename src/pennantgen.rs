@@ -1,5 +1,5 @@
 use itertools::Itertools;
-use rand::{rngs::ThreadRng, seq::IteratorRandom, Rng};
+use rand::{seq::IteratorRandom, Rng};
 #[derive(Debug, Clone, Copy)]
 /// Represents how many wins and losses a team has in the current pennant.
 pub struct PennantStanding {
@@ -9,7 +9,7 @@ pub struct PennantStanding {
 
 use crate::edit_league_error::EditLeagueError;
 /// Creates a tuple containing the wins and losses of a first place team. The teams winning percntage will be approximately 60 - 70%.
-fn get_first_place_standings(games_played: i32, thread: &mut ThreadRng) -> (i32, i32) {
+fn get_first_place_standings<R: Rng>(games_played: i32, thread: &mut R) -> (i32, i32) {
     // We convert games_played into a float.
     let g_float = games_played as f32;
     // We calculate what the min and max number of wins the team will have based off a percentage.
@@ -20,14 +20,14 @@ fn get_first_place_standings(games_played: i32, thread: &mut ThreadRng) -> (i32,
     (wins, games_played - wins)
 }
 
-fn generate_losers(
+fn generate_losers<R: Rng>(
     games_played: i32,
     total_games_played: i32,
     _max_wins: i32,
     standings_needed: usize,
     top_3_wins: i32,
     top_3_losses: i32,
-    thread: &mut ThreadRng,
+    thread: &mut R,
     max_other_wins: i32,
 ) -> Option<Vec<PennantStanding>> {
     // We set a result variable.
@@ -69,7 +69,7 @@ fn generate_losers(
             None => min_winning_percentage += -0.05,
             Some(mut standings) => {
                 //otherwise, we sort the PennantStanding by wins, and return it in a some.
-                standings.sort_by(|a, b| a.wins.cmp(&b.wins));
+                standings.sort_by_key(|standing| standing.wins);
                 result = Some(standings);
                 break;
             }
@@ -79,9 +79,9 @@ fn generate_losers(
 }
 
 /// Generates the wins and losses for a pennant race.
-pub fn generate_pennant_standings(
+pub fn generate_pennant_standings<R: Rng>(
     games_played: i32,
-    thread: &mut ThreadRng,
+    thread: &mut R,
     total_teams: i32,
 ) -> Result<Vec<PennantStanding>, EditLeagueError> {
     // We calculate how many games have been played by all teams.
@@ -9,6 +9,7 @@ use crate::note::Note;
 
 use crate::pitcher_rank_info::PitcherRankInfo;
 
+use crate::names::random_name;
 use crate::player_error::CompTable;
 use crate::player_error::PlayerError;
 use crate::player_quality::PlayerQuality;
@@ -27,9 +28,6 @@ use crate::PD;
 use inquire::Confirm;
 use inquire::InquireError;
 use inquire::Select;
-use name_maker::Gender;
-use name_maker::RandomNameGenerator;
-use rand::rngs::ThreadRng;
 use rand::Rng;
 use rusqlite::Connection;
 use serde_json::Value;
@@ -43,7 +41,7 @@ pub enum AgeCat {
 }
 
 impl AgeCat {
-    pub fn random(thread: &mut ThreadRng) -> AgeCat {
+    pub fn random<R: Rng>(thread: &mut R) -> AgeCat {
         let roll = thread.gen_range(1..=6);
         match roll {
             1..=2 => Self::Prospect,
@@ -54,7 +52,7 @@ impl AgeCat {
         }
     }
 
-    pub fn new_age(&self, thread: &mut ThreadRng) -> i32 {
+    pub fn new_age<R: Rng>(&self, thread: &mut R) -> i32 {
         let roll = thread.gen_range(1..=6);
         match self {
             Self::Prospect => 18 + roll,
@@ -63,6 +61,30 @@ impl AgeCat {
             Self::OldTimer => 32 + roll,
         }
     }
+
+    /// Returns the age category a player of a given age belongs to.
+    /// Rookies start at 22, the youngest age new_age gives a rookie, so every generated rookie is treated as a rookie.
+    pub fn from_age(age: i32) -> AgeCat {
+        match age {
+            ..=21 => Self::Prospect,
+            22..=27 => Self::Rookie,
+            28..=32 => Self::Veteran,
+            _ => Self::OldTimer,
+        }
+    }
+
+    /// Takes a d6 roll, and returns 1 if a player in this category improves during the offseason, -1 if they decline, or 0 if they stay the same.
+    /// Young players are more likely to improve, while older players are more likely to decline.
+    pub fn progression(&self, roll: i32) -> i32 {
+        match (self, roll) {
+            (Self::Prospect, 4..) => 1,
+            (Self::Rookie, 5..) => 1,
+            (Self::Veteran, ..=1) => -1,
+            (Self::Veteran, 6..) => 1,
+            (Self::OldTimer, ..=3) => -1,
+            _ => 0,
+        }
+    }
 }
 
 // Players can be either left handed or right handed, however batters may also be switch hitters. We use an enum to keep track.
@@ -143,13 +165,13 @@ pub enum PlayerGender {
 }
 
 impl PlayerGender {
-    pub fn new_name(&self) -> String {
-        let generator = RandomNameGenerator::init();
-        match self {
-            Self::Male => generator.generate_specific(Gender::Male).to_string(),
-            Self::Female => generator.generate_specific(Gender::Female).to_string(),
-            Self::Coed => generator.generate().to_string(),
-        }
+    pub fn new_name<R: Rng>(&self, thread: &mut R) -> String {
+        let is_male = match self {
+            Self::Male => true,
+            Self::Female => false,
+            Self::Coed => thread.gen_bool(0.5),
+        };
+        random_name(is_male, thread)
     }
 }
 
@@ -190,10 +212,14 @@ pub struct Player {
     pub obt: i32,       // A player's obt is calculated by adding its bt + its obt_mod
     pub pd: Option<PD>, // The main difference between a batter and pitcher is that pitchers have a base pitch die associated with themselves, while batters do not.
     // This is simulated using an option.
+    #[serde(default)]
     pub b_traits: BTraits,
     pub pitcher_trait: Option<PitcherTrait>,
+    #[serde(default)]
     pub trade_value: i32,
+    #[serde(default)]
     pub team_id: i64,
+    #[serde(default)]
     pub player_id: i64,
     pub note: Note,
 }
@@ -205,6 +231,20 @@ impl Player {
         //die.clone()
     }
 
+    /// Calculates how valuable a player is in a trade. Batters are valued by their bt and traits, while pitchers are valued by their pitch die and trait.
+    pub fn calc_trade_value(&self) -> i32 {
+        match self.pd {
+            None => self.bt + self.b_traits.get_trade_value(),
+            Some(pd) => {
+                let base = pd.to_int();
+                match self.pitcher_trait {
+                    Some(_) => (base + 1) * 5,
+                    None => base * 5,
+                }
+            }
+        }
+    }
+
     // Determine's if a player is a pitcher based off if it has a pitch die or not.'
     pub fn is_pitcher(&self) -> bool {
         matches!(self, Player { pd: Some(_), .. })
@@ -350,6 +390,7 @@ impl Player {
             speed: serde_json::to_value(speed_option)?,
             toughness: serde_json::to_value(toughness_option)?,
             trade_value: self.trade_value,
+            note: serde_json::to_value(&self.note)?,
         };
 
         Ok(new_row)
@@ -366,12 +407,12 @@ impl Player {
         self.player_id = new_player_id;
         Ok(())
     }
-    pub fn upgrade_random_batter_trait(
+    pub fn upgrade_random_batter_trait<R: Rng>(
         &mut self,
         conn: &mut Connection,
         team_id: i64,
         team_spot: TeamSpot,
-        thread: &mut ThreadRng,
+        thread: &mut R,
     ) -> Result<(), EditLeagueError> {
         match self.is_pitcher() {
             false => {
@@ -406,15 +447,15 @@ impl Player {
         (age, pos, name, player_id, team_id, note)
     }
 
-    pub fn new(
+    pub fn new<R: Rng>(
         pos_value: Value,
         gender: PlayerGender,
         quality: impl PlayerQuality,
-        thread: &mut ThreadRng,
+        thread: &mut R,
         era: Era,
     ) -> Player {
         // First, we randomly generate a player's age and name.
-        let name = gender.new_name();
+        let name = gender.new_name(thread);
         let age_cat = AgeCat::random(thread);
         let age = age_cat.new_age(thread);
         let pos = serde_json::from_value(pos_value).unwrap();
@@ -0,0 +1,638 @@
+use core::fmt;
+
+use crate::b_traits::BTraits;
+use crate::edit_league_error::handle_inquire_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::era::Era;
+use crate::in_transaction;
+use crate::inquire_check;
+use crate::league::save_league;
+use crate::league::League;
+use crate::note::Note;
+use crate::pd::PD;
+use crate::player::Hand;
+use crate::player::Player;
+use crate::position::PlayerPosition;
+use crate::position::PositionCategory;
+use crate::position::TwoWayInfo;
+use crate::team::Team;
+use crate::trade::roster_with_spots;
+use crate::traits::player_trait_option;
+use crate::traits::PitcherTrait;
+use crate::update_player_db::update_player_db_option;
+use crate::update_player_db::PlayerColumn;
+use crate::update_player_db::UpdatePlayerDb;
+use inquire::{Confirm, CustomType, Select, Text};
+use rusqlite::Connection;
+
+/* The player editor lets a user change any player in a league without editing the database by hand.
+Each change is checked before it is made, so a player can not be given a pitch die or position from another era, a batter can not be given a pitch die or pitcher trait, and a pitcher can not bat as a switch hitter.
+A player's obt and trade value are calculated again after every change, and each changed column is saved through UpdatePlayerDb. */
+
+/// The highest bt or obt a player can have.
+const MAX_TARGET: i32 = 99;
+
+/// A change to a single value of a player.
+#[derive(Debug, Clone)]
+pub enum PlayerEdit {
+    Name(String),
+    Age(i32),
+    Hand(Hand),
+    Pos(PlayerPosition),
+    BT(i32),
+    ObtMod(i32),
+    PD(Option<PD>),
+    PitcherTrait(Option<PitcherTrait>),
+    BTraits(BTraits),
+    Note(Note),
+}
+
+impl fmt::Display for PlayerEdit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chars = match self {
+            Self::Name(name) => format!("Name: {}", name),
+            Self::Age(age) => format!("Age: {}", age),
+            Self::Hand(hand) => format!("Hand: {}", hand),
+            Self::Pos(pos) => format!("Position: {}", pos),
+            Self::BT(bt) => format!("BT: {}", bt),
+            Self::ObtMod(obt_mod) => format!("OBT Modifier: {}", obt_mod),
+            Self::PD(Some(pd)) => format!("Pitch Die: {}", pd),
+            Self::PD(None) => "Pitch Die: None".to_string(),
+            Self::PitcherTrait(Some(pitcher_trait)) => format!("Pitcher Trait: {}", pitcher_trait),
+            Self::PitcherTrait(None) => "Pitcher Trait: None".to_string(),
+            Self::BTraits(b_traits) => format!("Traits: {}", b_traits),
+            Self::Note(Some(note)) => format!("Note: {}", note),
+            Self::Note(None) => "Note: None".to_string(),
+        };
+        write!(f, "{}", chars)
+    }
+}
+
+/// Returns an error describing why a player can not be edited.
+fn invalid(player: &Player, message: &str) -> Result<(), EditLeagueError> {
+    Err(EditLeagueError::InvalidPlayer(format!(
+        "{} can not be edited. {}",
+        player.name, message
+    )))
+}
+
+/// Checks that a position can be given to a player. Pitchers must have a pitcher position from their era, or be a two way player, while batters must have a batter position.
+fn check_pos(player: &Player, pos: &PlayerPosition, era: Era) -> Result<(), EditLeagueError> {
+    let era_positions = PlayerPosition::get_all_pitcher_positions(era);
+    match (player.is_pitcher(), pos) {
+        (true, PlayerPosition::TwoWay(info)) => match info.get_pitcher_type() {
+            Ok(pitcher_type) if era_positions.contains(pitcher_type) => Ok(()),
+            Ok(pitcher_type) => invalid(
+                player,
+                &format!(
+                    "{} is not a pitcher position in the {} Era.",
+                    pitcher_type, era
+                ),
+            ),
+            Err(message) => invalid(player, &message),
+        },
+        (true, _) if era_positions.contains(pos) => Ok(()),
+        (true, _) => invalid(
+            player,
+            &format!("{} is not a pitcher position in the {} Era.", pos, era),
+        ),
+        (false, _) if pos.matches_cat(PositionCategory::Pitcher) => {
+            invalid(player, "Batters can not have a pitcher position.")
+        }
+        (false, _) => Ok(()),
+    }
+}
+
+/// Checks that a change can be made to a player in a league of an era.
+pub fn check_edit(player: &Player, edit: &PlayerEdit, era: Era) -> Result<(), EditLeagueError> {
+    let in_range = |value: i32| (0..=MAX_TARGET).contains(&value);
+    match edit {
+        PlayerEdit::Name(name) if name.trim().is_empty() => {
+            invalid(player, "A player's name can not be blank.")
+        }
+        PlayerEdit::Age(age) if *age < 1 => invalid(player, "A player's age must be at least 1."),
+        PlayerEdit::Hand(Hand::S) if player.is_pitcher() => {
+            invalid(player, "Pitchers can not be switch hitters.")
+        }
+        PlayerEdit::Pos(pos) => check_pos(player, pos, era),
+        PlayerEdit::BT(bt) if !in_range(*bt) || !in_range(bt + player.obt_mod) => invalid(
+            player,
+            &format!(
+                "A player's bt and obt must be between 0 and {}.",
+                MAX_TARGET
+            ),
+        ),
+        PlayerEdit::ObtMod(obt_mod) if !in_range(player.bt + obt_mod) => invalid(
+            player,
+            &format!("A player's obt must be between 0 and {}.", MAX_TARGET),
+        ),
+        PlayerEdit::PD(Some(_)) if !player.is_pitcher() => {
+            invalid(player, "Batters can not be given a pitch die.")
+        }
+        PlayerEdit::PD(Some(pd)) if !era.get_all_pd().contains(pd) => invalid(
+            player,
+            &format!("{} is not a pitch die in the {} Era.", pd, era),
+        ),
+        PlayerEdit::PD(None) if player.is_pitcher() => {
+            invalid(player, "Pitchers must have a pitch die.")
+        }
+        PlayerEdit::PitcherTrait(Some(_)) if !player.is_pitcher() => {
+            invalid(player, "Batters can not be given a pitcher trait.")
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Makes a change to a player, then calculates their obt and trade value again.
+fn apply_edit(player: &mut Player, edit: PlayerEdit) {
+    match edit {
+        PlayerEdit::Name(name) => player.name = name.trim().to_string(),
+        PlayerEdit::Age(age) => player.age = age,
+        PlayerEdit::Hand(hand) => player.hand = hand,
+        PlayerEdit::Pos(pos) => player.pos = pos,
+        PlayerEdit::BT(bt) => player.bt = bt,
+        PlayerEdit::ObtMod(obt_mod) => player.obt_mod = obt_mod,
+        PlayerEdit::PD(pd) => player.pd = pd,
+        PlayerEdit::PitcherTrait(pitcher_trait) => player.pitcher_trait = pitcher_trait,
+        PlayerEdit::BTraits(b_traits) => player.b_traits = b_traits,
+        PlayerEdit::Note(note) => player.note = note,
+    }
+    player.obt = player.bt + player.obt_mod;
+    player.trade_value = player.calc_trade_value();
+}
+
+/// Saves each of a player's batting traits. Traits are saved as null when a player does not have them, the same as when a player is created.
+fn save_b_traits(
+    conn: &mut Connection,
+    player_id: i64,
+    b_traits: &BTraits,
+) -> Result<usize, rusqlite::Error> {
+    update_player_db_option(
+        player_trait_option(&b_traits.contact).copied(),
+        conn,
+        player_id,
+    )?;
+    update_player_db_option(
+        player_trait_option(&b_traits.defense).copied(),
+        conn,
+        player_id,
+    )?;
+    update_player_db_option(
+        player_trait_option(&b_traits.power).copied(),
+        conn,
+        player_id,
+    )?;
+    update_player_db_option(
+        player_trait_option(&b_traits.speed).copied(),
+        conn,
+        player_id,
+    )?;
+    update_player_db_option(
+        player_trait_option(&b_traits.toughness).copied(),
+        conn,
+        player_id,
+    )
+}
+
+/// Saves the columns changed by an edit to the database, along with the player's obt and trade value.
+fn save_edit(
+    conn: &mut Connection,
+    player: &Player,
+    edit: &PlayerEdit,
+) -> Result<(), EditLeagueError> {
+    let player_id = player.player_id;
+    let result = match edit {
+        // Names are saved as plain text rather than JSON, so they are not saved through UpdatePlayerDb.
+        PlayerEdit::Name(_) => conn.execute(
+            "UPDATE players SET player_name = ?1 WHERE player_id = ?2",
+            (&player.name, player_id),
+        ),
+        PlayerEdit::Age(_) => {
+            PlayerColumn::new("age", player.age).update_player_db(conn, player_id)
+        }
+        PlayerEdit::Hand(_) => player.hand.update_player_db(conn, player_id),
+        PlayerEdit::Pos(_) => player.pos.update_player_db(conn, player_id),
+        PlayerEdit::BT(_) => PlayerColumn::new("bt", player.bt).update_player_db(conn, player_id),
+        PlayerEdit::ObtMod(_) => {
+            PlayerColumn::new("obt_mod", player.obt_mod).update_player_db(conn, player_id)
+        }
+        PlayerEdit::PD(_) => update_player_db_option(player.pd, conn, player_id),
+        PlayerEdit::PitcherTrait(_) => PlayerColumn::new("pitcher_trait", player.pitcher_trait)
+            .update_player_db(conn, player_id),
+        PlayerEdit::BTraits(_) => save_b_traits(conn, player_id, &player.b_traits),
+        PlayerEdit::Note(_) => {
+            PlayerColumn::new("player_note", &player.note).update_player_db(conn, player_id)
+        }
+    };
+    handle_sql_error(result)?;
+    handle_sql_error(PlayerColumn::new("obt", player.obt).update_player_db(conn, player_id))?;
+    handle_sql_error(
+        PlayerColumn::new("trade_value", player.trade_value).update_player_db(conn, player_id),
+    )?;
+    Ok(())
+}
+
+/// Returns a player on a team that can be changed.
+fn find_player_mut(team: &mut Team, player_id: i64) -> Option<&mut Player> {
+    team.lineup
+        .iter_mut()
+        .chain(team.bench.iter_mut())
+        .chain(team.starting_pitching.iter_mut())
+        .chain(team.bullpen.iter_mut().flatten())
+        .find(|player| player.player_id == player_id)
+}
+
+/// Returns a copy of a player with a set of changes made in order, or an error if any of the changes are not valid.
+pub fn plan_player_edits(
+    player: &Player,
+    edits: &[PlayerEdit],
+    era: Era,
+) -> Result<Player, EditLeagueError> {
+    let mut new_player = player.clone();
+    for edit in edits {
+        check_edit(&new_player, edit, era)?;
+        apply_edit(&mut new_player, edit.clone());
+    }
+    Ok(new_player)
+}
+
+/// Makes a set of changes to a player on a team, saves them to the database, and updates the team score.
+pub fn edit_player(
+    conn: &mut Connection,
+    league: &mut League,
+    team_index: usize,
+    player_id: i64,
+    edits: &[PlayerEdit],
+) -> Result<Player, EditLeagueError> {
+    let era = league.era;
+    let team = &mut league.teams[team_index];
+    let player = match find_player_mut(team, player_id) {
+        Some(player) => player,
+        None => {
+            return Err(EditLeagueError::InvalidPlayer(format!(
+                "There is no player with the id {} on {}.",
+                player_id, team.name
+            )))
+        }
+    };
+    let new_player = plan_player_edits(player, edits, era)?;
+    in_transaction(conn, |conn| {
+        for edit in edits {
+            save_edit(conn, &new_player, edit)?;
+        }
+        Ok(())
+    })?;
+    *player = new_player.clone();
+    for injury in team.injured_list.iter_mut() {
+        if injury.player_id == player_id {
+            injury.player_name = new_player.name.clone();
+        }
+    }
+    team.calc_team_score();
+    Ok(new_player)
+}
+
+/// Creates a change to a player's batting traits from the text they are written as, E.G P+,S-
+fn b_traits_edit(input: &str) -> Result<PlayerEdit, EditLeagueError> {
+    match BTraits::from_string(input) {
+        Ok(b_traits) => Ok(PlayerEdit::BTraits(b_traits)),
+        Err(message) => Err(EditLeagueError::InvalidPlayer(message)),
+    }
+}
+
+/// Creates a change to a player's note, where a blank note removes it.
+fn note_edit(input: &str) -> PlayerEdit {
+    match input.trim().is_empty() {
+        true => PlayerEdit::Note(None),
+        false => PlayerEdit::Note(Some(input.to_string())),
+    }
+}
+
+/// The values that can be changed in the player editor.
+#[derive(Debug, Clone, Copy)]
+enum PlayerField {
+    Name,
+    Age,
+    Hand,
+    Pos,
+    BT,
+    ObtMod,
+    PD,
+    PitcherTrait,
+    BTraits,
+    Note,
+}
+
+impl fmt::Display for PlayerField {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chars = match self {
+            Self::Name => "Name",
+            Self::Age => "Age",
+            Self::Hand => "Hand",
+            Self::Pos => "Position",
+            Self::BT => "BT",
+            Self::ObtMod => "OBT Modifier",
+            Self::PD => "Pitch Die",
+            Self::PitcherTrait => "Pitcher Trait",
+            Self::BTraits => "Batting Traits",
+            Self::Note => "Note",
+        };
+        write!(f, "{}", chars)
+    }
+}
+
+/// Prompts the user for the text of a new value.
+fn text_input(message: &str, default: &str) -> Result<String, EditLeagueError> {
+    handle_inquire_error(Text::new(message).with_default(default).prompt())
+}
+
+/// Returns each batter position once.
+fn batter_positions() -> Vec<PlayerPosition> {
+    let mut positions = Vec::new();
+    for pos in PlayerPosition::get_all_batter_positions() {
+        if !positions.contains(&pos) {
+            positions.push(pos);
+        }
+    }
+    positions
+}
+
+/// Prompts the user for a new position. Pitchers can also be made into two way players.
+fn pos_from_input(player: &Player, era: Era) -> Result<PlayerPosition, EditLeagueError> {
+    let pitcher_positions = PlayerPosition::get_all_pitcher_positions(era);
+    if !player.is_pitcher() {
+        let mut options = batter_positions();
+        options.push(PlayerPosition::UT);
+        return handle_inquire_error(
+            Select::new("What is the player's new position?", options).prompt(),
+        );
+    }
+    let two_way = handle_inquire_error(
+        Confirm::new("Is the player a two way player?")
+            .with_default(matches!(player.pos, PlayerPosition::TwoWay(_)))
+            .prompt(),
+    )?;
+    let pitcher_type = handle_inquire_error(
+        Select::new(
+            "What is the player's new pitcher position?",
+            pitcher_positions,
+        )
+        .prompt(),
+    )?;
+    if !two_way {
+        return Ok(pitcher_type);
+    }
+    let fielder_type = handle_inquire_error(
+        Select::new("What position does the player field?", batter_positions()).prompt(),
+    )?;
+    let info = TwoWayInfo::new(pitcher_type, fielder_type);
+    Ok(PlayerPosition::TwoWay(Box::new(info)))
+}
+
+/// Prompts the user for a new value for a player.
+fn edit_from_input(
+    player: &Player,
+    field: PlayerField,
+    era: Era,
+) -> Result<PlayerEdit, EditLeagueError> {
+    let number = |message: &str, default: i32| {
+        handle_inquire_error(
+            CustomType::<i32>::new(message)
+                .with_default(default)
+                .with_error_message("Please enter a valid whole number.")
+                .prompt(),
+        )
+    };
+    match field {
+        PlayerField::Name => Ok(PlayerEdit::Name(text_input(
+            "What is the player's new name?",
+            &player.name,
+        )?)),
+        PlayerField::Age => Ok(PlayerEdit::Age(number(
+            "What is the player's new age?",
+            player.age,
+        )?)),
+        PlayerField::Hand => {
+            let options = match player.is_pitcher() {
+                true => vec![Hand::R, Hand::L],
+                false => vec![Hand::R, Hand::L, Hand::S],
+            };
+            let hand = handle_inquire_error(
+                Select::new("What is the player's new hand?", options).prompt(),
+            )?;
+            Ok(PlayerEdit::Hand(hand))
+        }
+        PlayerField::Pos => Ok(PlayerEdit::Pos(pos_from_input(player, era)?)),
+        PlayerField::BT => Ok(PlayerEdit::BT(number(
+            "What is the player's new bt?",
+            player.bt,
+        )?)),
+        PlayerField::ObtMod => Ok(PlayerEdit::ObtMod(number(
+            "What is the player's new obt modifier?",
+            player.obt_mod,
+        )?)),
+        PlayerField::PD => {
+            let pd = handle_inquire_error(
+                Select::new("What is the player's new pitch die?", era.get_all_pd()).prompt(),
+            )?;
+            Ok(PlayerEdit::PD(Some(pd)))
+        }
+        PlayerField::PitcherTrait => {
+            let traits = [
+                None,
+                Some(PitcherTrait::K),
+                Some(PitcherTrait::GB),
+                Some(PitcherTrait::CN),
+                Some(PitcherTrait::CNM),
+                Some(PitcherTrait::ST),
+            ];
+            let options: Vec<String> = traits
+                .iter()
+                .map(|pitcher_trait| match pitcher_trait {
+                    Some(pitcher_trait) => pitcher_trait.to_string(),
+                    None => "None".to_string(),
+                })
+                .collect();
+            let choice = handle_inquire_error(
+                Select::new("What is the player's new pitcher trait?", options).raw_prompt(),
+            )?;
+            Ok(PlayerEdit::PitcherTrait(traits[choice.index]))
+        }
+        PlayerField::BTraits => {
+            let input = text_input(
+                "What are the player's new batting traits? Separate each trait with a comma, E.G P+,S-",
+                &player.b_traits.to_string(),
+            )?;
+            b_traits_edit(&input)
+        }
+        PlayerField::Note => {
+            let default = player.note.clone().unwrap_or_default();
+            let input = text_input(
+                "What is the player's new note? Leave blank to remove the note.",
+                &default,
+            )?;
+            Ok(note_edit(&input))
+        }
+    }
+}
+
+/// Prompts the user to choose a player in a league, make changes to them, and save the changes.
+pub fn player_editor_from_input(
+    league: &mut League,
+    conn: &mut Connection,
+) -> Result<(), EditLeagueError> {
+    let team_names: Vec<String> = league.teams.iter().map(|team| team.name.clone()).collect();
+    let team_index = match Select::new("Which team is the player on?", team_names).raw_prompt() {
+        Ok(choice) => choice.index,
+        Err(message) => return inquire_check(message),
+    };
+    let roster: Vec<Player> = roster_with_spots(&league.teams[team_index])
+        .into_iter()
+        .map(|(_, player)| player.clone())
+        .collect();
+    let options: Vec<String> = roster.iter().map(|player| player.to_string()).collect();
+    let player = match Select::new("Which player would you like to edit?", options)
+        .with_page_size(10)
+        .raw_prompt()
+    {
+        Ok(choice) => &roster[choice.index],
+        Err(message) => return inquire_check(message),
+    };
+    let mut edits = Vec::new();
+    let mut new_player = player.clone();
+    loop {
+        println!("{}", new_player);
+        let mut fields = vec![
+            PlayerField::Name,
+            PlayerField::Age,
+            PlayerField::Hand,
+            PlayerField::Pos,
+            PlayerField::BT,
+            PlayerField::ObtMod,
+        ];
+        match new_player.is_pitcher() {
+            true => fields.extend([PlayerField::PD, PlayerField::PitcherTrait]),
+            false => fields.push(PlayerField::BTraits),
+        }
+        fields.push(PlayerField::Note);
+        // Finishing is the last option, after every field that can be changed.
+        let mut options: Vec<String> = fields.iter().map(|field| field.to_string()).collect();
+        options.push("Finish editing the player.".to_string());
+        let field = match Select::new("What would you like to change?", options).raw_prompt() {
+            Ok(choice) if choice.index == fields.len() => break,
+            Ok(choice) => fields[choice.index],
+            Err(message) => return inquire_check(message),
+        };
+        let edit = match edit_from_input(&new_player, field, league.era) {
+            Ok(edit) => edit,
+            Err(EditLeagueError::Inquire(message)) => return inquire_check(message),
+            // An invalid value is not used, so the user can try again.
+            Err(message) => {
+                println!("{}", message);
+                continue;
+            }
+        };
+        edits.push(edit);
+        match plan_player_edits(player, &edits, league.era) {
+            Ok(planned) => new_player = planned,
+            Err(message) => {
+                println!("{}", message);
+                edits.pop();
+            }
+        }
+    }
+    if edits.is_empty() {
+        return Ok(());
+    }
+    for edit in edits.iter() {
+        println!("{}", edit);
+    }
+    match Confirm::new("Would you like to save these changes?").prompt() {
+        Ok(true) => {}
+        Ok(false) => return Ok(()),
+        Err(message) => return inquire_check(message),
+    }
+    let player_id = player.player_id;
+    let saved = edit_player(conn, league, team_index, player_id, &edits)?;
+    println!("{} has been saved.", saved.name);
+    save_league(league);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::league::load_teams_from_sql;
+    use crate::player::PlayerGender;
+    use crate::test_league;
+    use crate::traits::{Power, Speed};
+
+    #[test]
+    fn player_editor() {
+        let (mut conn, mut league) = test_league(Era::Modern, PlayerGender::Female, 24);
+        let batter = league.teams[0].lineup[0].clone();
+        let pitcher = league.teams[0].starting_pitching[0].clone();
+        // Values that are not valid for the player or the era are rejected.
+        let invalid = [
+            (&pitcher, PlayerEdit::PD(Some(PD::D20))),
+            (&pitcher, PlayerEdit::Hand(Hand::S)),
+            (&pitcher, PlayerEdit::Pos(PlayerPosition::P)),
+            (&batter, PlayerEdit::PD(Some(PD::D8))),
+            (&batter, PlayerEdit::Pos(PlayerPosition::RP)),
+            (&batter, PlayerEdit::BT(120)),
+        ];
+        for (player, edit) in invalid {
+            let result = plan_player_edits(player, &[edit], league.era);
+            assert!(matches!(result, Err(EditLeagueError::InvalidPlayer(_))));
+        }
+        assert!(matches!(
+            b_traits_edit("P+,X+"),
+            Err(EditLeagueError::InvalidPlayer(_))
+        ));
+        assert!(matches!(note_edit("  "), PlayerEdit::Note(None)));
+        // obt and trade value are calculated again, and every change is saved to the database.
+        let edits = [
+            PlayerEdit::Name("Frankie Kelleher".to_string()),
+            PlayerEdit::BT(30),
+            PlayerEdit::ObtMod(4),
+            b_traits_edit("P+,S-").unwrap(),
+            note_edit("Led the league in home runs"),
+        ];
+        let edited = edit_player(&mut conn, &mut league, 0, batter.player_id, &edits).unwrap();
+        assert_eq!(edited.obt, 34);
+        assert_eq!(edited.trade_value, edited.calc_trade_value());
+        let edits = [
+            PlayerEdit::Pos(PlayerPosition::TwoWay(Box::new(TwoWayInfo::new(
+                PlayerPosition::SP,
+                PlayerPosition::FirstBase,
+            )))),
+            PlayerEdit::PitcherTrait(Some(PitcherTrait::K)),
+        ];
+        edit_player(&mut conn, &mut league, 0, pitcher.player_id, &edits).unwrap();
+        let mut loaded = League::new(
+            &league.name,
+            league.gender,
+            league.era,
+            league.league_id,
+            None,
+        );
+        load_teams_from_sql(league.league_id, &mut loaded, &mut conn).unwrap();
+        let team = &loaded.teams[0];
+        let saved = team
+            .lineup
+            .iter()
+            .find(|x| x.player_id == batter.player_id)
+            .unwrap();
+        assert_eq!(saved.name, "Frankie Kelleher");
+        assert_eq!((saved.bt, saved.obt_mod, saved.obt), (30, 4, 34));
+        assert_eq!(saved.b_traits.power, Power::P1);
+        assert_eq!(saved.b_traits.speed, Speed::SM1);
+        assert_eq!(saved.trade_value, edited.trade_value);
+        assert_eq!(saved.note, Some("Led the league in home runs".to_string()));
+        let saved = &team.starting_pitching[0];
+        assert!(matches!(saved.pos, PlayerPosition::TwoWay(_)));
+        assert_eq!(saved.pitcher_trait, Some(PitcherTrait::K));
+        assert_eq!(team.team_score, league.teams[0].team_score);
+    }
+}
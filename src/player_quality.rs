@@ -6,7 +6,6 @@ use crate::player::Player;
 
 use crate::traits::PitcherTrait;
 use crate::Era;
-use crate::ThreadRng;
 use rand::Rng;
 use serde::Deserialize;
 use serde::Serialize;
@@ -16,11 +15,11 @@ use serde::Serialize;
 */
 
 pub trait PlayerQuality {
-    fn get_bt(&self, thread: &mut ThreadRng) -> i32;
-    fn get_obt_mod(&self, thread: &mut ThreadRng) -> i32;
-    fn get_pd(&self, thread: &mut ThreadRng, era: Era) -> Option<PD>;
+    fn get_bt<R: Rng>(&self, thread: &mut R) -> i32;
+    fn get_obt_mod<R: Rng>(&self, thread: &mut R) -> i32;
+    fn get_pd<R: Rng>(&self, thread: &mut R, era: Era) -> Option<PD>;
     fn for_pitcher(&self) -> bool;
-    fn get_hand(&self, thread: &mut ThreadRng) -> Hand {
+    fn get_hand<R: Rng>(&self, thread: &mut R) -> Hand {
         let roll = thread.gen_range(1..=10);
         match roll {
             1..=6 => Hand::R,
@@ -32,12 +31,12 @@ pub trait PlayerQuality {
             _ => Hand::R,
         }
     }
-    fn calc_traits(&self, _trait_struct: &mut BTraits, _thread: &mut ThreadRng) {}
-    fn get_pitcher_trait(&self, thread: &mut ThreadRng) -> Option<PitcherTrait>;
+    fn calc_traits<R: Rng>(&self, _trait_struct: &mut BTraits, _thread: &mut R) {}
+    fn get_pitcher_trait<R: Rng>(&self, thread: &mut R) -> Option<PitcherTrait>;
     fn upgrade(&self) -> Self;
     fn get_box_copy(&self) -> Box<Self>;
     /// Randomly generates a base player based off the quality.
-    fn gen_player(&self, thread: &mut ThreadRng, era: Era) -> Player {
+    fn gen_player<R: Rng>(&self, thread: &mut R, era: Era) -> Player {
         let bt = self.get_bt(thread);
         let obt_mod = self.get_obt_mod(thread);
         let obt = bt + obt_mod;
@@ -45,19 +44,9 @@ pub trait PlayerQuality {
         self.calc_traits(&mut b_traits, thread);
         let pd = self.get_pd(thread, era);
         let pitcher_trait = self.get_pitcher_trait(thread);
-        let trade_value = match pd {
-            None => bt + b_traits.get_trade_value(),
-            Some(pd) => {
-                let base = pd.to_int();
-                match pitcher_trait {
-                    Some(_) => (base + 1) * 5,
-                    None => base * 5,
-                }
-            }
-        };
         let hand = self.get_hand(thread);
         let (age, pos, name, player_id, team_id, note) = Player::get_default_info();
-        Player {
+        let mut player = Player {
             name,
             age,
             pos,
@@ -67,25 +56,27 @@ pub trait PlayerQuality {
             b_traits,
             pd,
             pitcher_trait,
-            trade_value,
+            trade_value: 0,
             hand,
             player_id,
             team_id,
             note,
-        }
+        };
+        player.trade_value = player.calc_trade_value();
+        player
     }
 }
 
 /* Batter quality is the enum used ot generated batters. Batters do not get a base pitch die, however their stats for hitting are much better then pitchers.
  The batter quality enum has 2 levels, TopProspect and Farmhand. Currently, TopProspect is used to generate players in a teams starting lineup, while the lower quality
 farmhands is used for the typically worse performing bench players. */
-#[derive(Copy, Clone, Serialize, Deserialize)]
+#[derive(Copy, Clone, Serialize, Deserialize, Debug)]
 pub enum BatterQuality {
     TopProspect,
     Farmhand,
 }
 
-fn new_bt(die: i32, base: i32, thread: &mut ThreadRng) -> i32 {
+fn new_bt<R: Rng>(die: i32, base: i32, thread: &mut R) -> i32 {
     let mut result = base;
 
     for _i in 1..=2 {
@@ -95,20 +86,20 @@ fn new_bt(die: i32, base: i32, thread: &mut ThreadRng) -> i32 {
 }
 
 impl PlayerQuality for BatterQuality {
-    fn get_bt(&self, thread: &mut ThreadRng) -> i32 {
+    fn get_bt<R: Rng>(&self, thread: &mut R) -> i32 {
         match self {
             Self::TopProspect => new_bt(10, 15, thread),
             Self::Farmhand => new_bt(10, 12, thread),
         }
     }
-    fn get_pitcher_trait(&self, _thread: &mut ThreadRng) -> Option<PitcherTrait> {
+    fn get_pitcher_trait<R: Rng>(&self, _thread: &mut R) -> Option<PitcherTrait> {
         None
     }
-    fn get_obt_mod(&self, thread: &mut ThreadRng) -> i32 {
+    fn get_obt_mod<R: Rng>(&self, thread: &mut R) -> i32 {
         thread.gen_range(1..=4) + thread.gen_range(1..=4)
     }
 
-    fn get_pd(&self, _threat: &mut ThreadRng, _era: Era) -> Option<PD> {
+    fn get_pd<R: Rng>(&self, _threat: &mut R, _era: Era) -> Option<PD> {
         None
     }
 
@@ -116,7 +107,7 @@ impl PlayerQuality for BatterQuality {
         false
     }
 
-    fn calc_traits(&self, trait_struct: &mut BTraits, thread: &mut ThreadRng) {
+    fn calc_traits<R: Rng>(&self, trait_struct: &mut BTraits, thread: &mut R) {
         let first_calc = trait_struct.generate(thread);
         if first_calc {
             if let BatterQuality::TopProspect = self {
@@ -134,22 +125,22 @@ impl PlayerQuality for BatterQuality {
 }
 
 // The pitcher quality enum is used to generate the pitcher stats. Currenly, only the top prosepect enum is used, this may change in the future.
-#[derive(Copy, Clone)]
+#[derive(Copy, Clone, Serialize, Deserialize, Debug)]
 pub enum PitcherQuality {
     TopProspect,
     Farmhand,
 }
 
 impl PlayerQuality for PitcherQuality {
-    fn get_bt(&self, thread: &mut ThreadRng) -> i32 {
+    fn get_bt<R: Rng>(&self, thread: &mut R) -> i32 {
         new_bt(6, 12, thread)
     }
 
-    fn get_obt_mod(&self, thread: &mut ThreadRng) -> i32 {
+    fn get_obt_mod<R: Rng>(&self, thread: &mut R) -> i32 {
         thread.gen_range(1..=8)
     }
     // A players PD is generated based off the current eta
-    fn get_pd(&self, thread: &mut ThreadRng, era: Era) -> Option<PD> {
+    fn get_pd<R: Rng>(&self, thread: &mut R, era: Era) -> Option<PD> {
         let new_pd = era.new_pd(thread, self);
         Some(new_pd)
     }
@@ -158,7 +149,7 @@ impl PlayerQuality for PitcherQuality {
         true
     }
 
-    fn get_pitcher_trait(&self, thread: &mut ThreadRng) -> Option<PitcherTrait> {
+    fn get_pitcher_trait<R: Rng>(&self, thread: &mut R) -> Option<PitcherTrait> {
         let roll = thread.gen_range(1..=10) + thread.gen_range(1..=10);
         //println!("Pitch Trait roll = {}", roll);
         match roll {
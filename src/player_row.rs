@@ -19,17 +19,19 @@ pub struct PlayerRow<'a> {
     pub speed: serde_json::Value,
     pub toughness: serde_json::Value,
     pub trade_value: i32,
+    pub note: serde_json::Value,
 }
 
 impl PlayerRow<'_> {
     /// Saves player information to a row in the database. If no error occurred, the row ID is returned.
+    /// The statement is cached, so saving every player on a roster only prepares it once.
     pub fn save_to_sql(&self, conn: &mut Connection) -> Result<i64, rusqlite::Error> {
-        conn.execute(
+        let mut stmt = conn.prepare_cached(
             "INSERT INTO players(
                 team_id,player_name,age,pos,hand,
                 bt,obt_mod,obt,
                 pd,pitcher_trait,team_spot,
-                contact,defense,power,speed,toughness,trade_value) 
+                contact,defense,power,speed,toughness,trade_value,player_note) 
             VALUES(:team_id, 
                 :player_name, 
                 :age, 
@@ -46,9 +48,11 @@ impl PlayerRow<'_> {
                 :power,
                 :speed,
                 :toughness,
-                :trade_value
+                :trade_value,
+                :player_note
             )",
-            named_params![
+        )?;
+        stmt.execute(named_params![
                 ":team_id": self.team_id,
                 ":player_name":self.player_name,
                 ":age":&self.age.to_string(),
@@ -65,11 +69,58 @@ impl PlayerRow<'_> {
                 ":power": self.power,
                 ":speed": self.speed,
                 ":toughness": self.toughness,
-                ":trade_value": self.trade_value
-            ],
-        )?;
+                ":trade_value": self.trade_value,
+                ":player_note": self.note
+        ])?;
 
         let new_player_id = conn.last_insert_rowid();
         Ok(new_player_id)
     }
+
+    /// Replaces the values saved for an existing player with the values in the row.
+    pub fn update_sql(&self, conn: &Connection, player_id: i64) -> Result<usize, rusqlite::Error> {
+        conn.execute(
+            "UPDATE players SET
+                team_id = :team_id,
+                player_name = :player_name,
+                age = :age,
+                pos = :pos,
+                hand = :hand,
+                bt = :bt,
+                obt_mod = :obt_mod,
+                obt = :obt,
+                pd = :pd,
+                pitcher_trait = :pitcher_trait,
+                team_spot = :team_spot,
+                contact = :contact,
+                defense = :defense,
+                power = :power,
+                speed = :speed,
+                toughness = :toughness,
+                trade_value = :trade_value,
+                player_note = :player_note
+            WHERE player_id = :player_id",
+            named_params![
+                ":team_id": self.team_id,
+                ":player_name":self.player_name,
+                ":age":&self.age.to_string(),
+                ":pos":&self.pos,
+                ":hand":self.hand,
+                ":bt":&self.bt,
+                ":obt_mod":&self.obt_mod,
+                ":obt":&self.obt,
+                ":pd":self.pd,
+                ":pitcher_trait": self.pitcher_trait,
+                ":team_spot":self.team_spot,
+                ":contact": self.contact,
+                ":defense":self.defense,
+                ":power": self.power,
+                ":speed": self.speed,
+                ":toughness": self.toughness,
+                ":trade_value": self.trade_value,
+                ":player_note": self.note,
+                ":player_id": player_id
+            ],
+        )
+    }
 }
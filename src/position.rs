@@ -3,7 +3,7 @@ use inquire::{Confirm, Select};
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 
-use crate::{era::Era, pd::PD};
+use crate::{era::Era, pd::PD, update_player_db::UpdatePlayerDb};
 
 /// If a player is a position player, we lists what type of pitcher they are, and what position they can field.
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
@@ -58,6 +58,10 @@ impl TwoWayInfo {
         Ok(&self_ref.pitcher_type)
     }
 
+    pub fn get_fielder_type(&self) -> &PlayerPosition {
+        &self.fielder_type
+    }
+
     pub fn new(pitcher_type: PlayerPosition, fielder_type: PlayerPosition) -> Self {
         TwoWayInfo {
             pitcher_type,
@@ -111,6 +115,12 @@ impl fmt::Display for PlayerPosition {
     }
 }
 
+impl UpdatePlayerDb for PlayerPosition {
+    fn get_column_name(&self) -> &str {
+        "pos"
+    }
+}
+
 impl PlayerPosition {
     pub fn get_pitcher_type(&self) -> Result<&PlayerPosition, String> {
         match self {
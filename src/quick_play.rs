@@ -0,0 +1,122 @@
+use core::fmt;
+
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::game_result::get_series_games;
+use crate::game_result::record_game_result;
+use crate::game_result::update_team_records;
+use crate::game_sim::get_league_team;
+use crate::injury::after_game;
+use crate::injury::everyday_players;
+use crate::injury::healthy_team;
+use crate::inquire_check;
+use crate::league::save_league;
+use crate::league::League;
+use crate::sched_view::select_series;
+use crate::team::Team;
+use inquire::Select;
+use rand::Rng;
+use rusqlite::Connection;
+
+/* Deadball can settle a game with only a few dice rolls by using each teams Team Score.
+Each team rolls a d20 and adds their team score, with the home team getting a bonus for home field advantage.
+The team with the higher total wins, and if the totals are tied, the teams keep rolling as if the game went to extra innings.
+Since the games table needs a final score, the loser's runs are rolled on a d8, and the winner wins by at least a run,
+with blowouts becoming more likely the further apart the totals are.*/
+
+/// The bonus added to the home teams roll.
+const HOME_FIELD_ADVANTAGE: i32 = 1;
+/// For every this many points the winner's total beats the loser's total, the winner scores another run.
+const POINTS_PER_RUN: i32 = 5;
+
+/// Takes the total of each team's roll, and the number of runs the losing team scored.
+/// Returns a tuple of the home score and away score, or None if the totals are tied.
+pub fn settle_quick_game(home_total: i32, away_total: i32, loser_runs: i32) -> Option<(i32, i32)> {
+    let margin = 1 + (home_total - away_total).abs() / POINTS_PER_RUN;
+    match home_total.cmp(&away_total) {
+        std::cmp::Ordering::Greater => Some((loser_runs + margin, loser_runs)),
+        std::cmp::Ordering::Less => Some((loser_runs, loser_runs + margin)),
+        std::cmp::Ordering::Equal => None,
+    }
+}
+
+/// Plays a game between 2 teams using only their team scores. Returns a tuple of the home score and away score.
+pub fn quick_game<R: Rng>(home_team: &Team, away_team: &Team, thread: &mut R) -> (i32, i32) {
+    loop {
+        let home_total = thread.gen_range(1..=20) + home_team.team_score + HOME_FIELD_ADVANTAGE;
+        let away_total = thread.gen_range(1..=20) + away_team.team_score;
+        let loser_runs = thread.gen_range(1..=8) - 1;
+        if let Some(score) = settle_quick_game(home_total, away_total, loser_runs) {
+            return score;
+        }
+    }
+}
+
+/// How much of a series the user would like to quickly resolve.
+#[derive(Copy, Clone, Debug)]
+enum QuickPlayChoice {
+    NextGame,
+    RestOfSeries,
+}
+
+impl fmt::Display for QuickPlayChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chars = match self {
+            Self::NextGame => "Play the next game in the series.",
+            Self::RestOfSeries => "Play every remaining game in the series.",
+        };
+        write!(f, "{}", chars)
+    }
+}
+
+/// Prompts the user to pick a series from the league's schedule, and settles either the next game or the rest of the series via team scores.
+/// Each result is saved to the database, and the teams wins and losses are updated.
+/// Team scores are calculated without injured players, and both teams roll for injuries after each game.
+pub fn quick_play_from_input<R: Rng>(
+    league: &mut League,
+    conn: &mut Connection,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    let series = match select_series(league, conn)? {
+        Some(wrapper) => wrapper,
+        None => return Ok(()),
+    };
+    let unplayed: Vec<_> = handle_sql_error(get_series_games(conn, series.series_id))?
+        .into_iter()
+        .filter(|game| !game.is_played())
+        .collect();
+    if unplayed.is_empty() {
+        println!("All games in this series have been played.");
+        return Ok(());
+    }
+    let options = vec![QuickPlayChoice::NextGame, QuickPlayChoice::RestOfSeries];
+    let choice =
+        match Select::new("How much of the series would you like to play?", options).prompt() {
+            Ok(choice) => choice,
+            Err(message) => return inquire_check(message),
+        };
+    let games_to_play = match choice {
+        QuickPlayChoice::NextGame => 1,
+        QuickPlayChoice::RestOfSeries => unplayed.len(),
+    };
+    for game in unplayed.iter().take(games_to_play) {
+        let home_team = healthy_team(get_league_team(league, series.home_team_id)?);
+        let away_team = healthy_team(get_league_team(league, series.away_team_id)?);
+        let (home_score, away_score) = quick_game(&home_team, &away_team, thread);
+        let new_record = record_game_result(conn, game, home_score, away_score)?;
+        update_team_records(league, game, &new_record);
+        println!(
+            "{} {} @ {} {}",
+            away_team.name, away_score, home_team.name, home_score
+        );
+        for team in [&home_team, &away_team] {
+            let appeared = everyday_players(team);
+            for message in after_game(conn, league, team.team_id, &appeared, thread)? {
+                println!("{}", message);
+            }
+        }
+    }
+    println!("Results saved.");
+    save_league(league);
+    Ok(())
+}
@@ -0,0 +1,194 @@
+use crate::edit_league_error::handle_inquire_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::game_result::get_series_games;
+use crate::game_result::record_game_result;
+use crate::game_result::save_game_note;
+use crate::game_result::update_team_records;
+use crate::game_result::GameRecord;
+use crate::injury::after_game;
+use crate::injury::everyday_players;
+use crate::inquire_check;
+use crate::league::save_league;
+use crate::league::League;
+use crate::note::Notable;
+use crate::sched_view::get_series_vec;
+use crate::sched_view::select_round;
+use crate::sched_view::SeriesWrapper;
+use inquire::{Confirm, CustomType, InquireError};
+use rand::Rng;
+use rusqlite::Connection;
+
+/// Asks the user for the number of runs a team scored. Loops until a number that is 0 or greater is entered.
+fn get_score(team_name: &str) -> Result<i32, InquireError> {
+    let message = format!("Please enter how many runs {} scored.", team_name);
+    loop {
+        let input = CustomType::<i32>::new(&message)
+            .with_error_message("Please type a valid number")
+            .prompt()?;
+        match input >= 0 {
+            true => return Ok(input),
+            false => println!("\nA team can not score less than 0 runs."),
+        }
+    }
+}
+
+/// Prompts the user for the final score of a game. Since a game can not end in a tie, the user is asked again if they enter a tied score.
+/// Returns a tuple of the home score and away score.
+fn get_final_score(series: &SeriesWrapper) -> Result<(i32, i32), InquireError> {
+    loop {
+        let away_score = get_score(&series.away_team_name)?;
+        let home_score = get_score(&series.home_team_name)?;
+        match home_score == away_score {
+            true => println!("\nA game can not end in a tie, please enter the score again."),
+            false => return Ok((home_score, away_score)),
+        }
+    }
+}
+
+/// Saves the result of a game, and updates the wins and losses of the league's teams to match.
+/// Both teams roll for injuries after a game's first result is saved, while a corrected result does not count against injuries a second time.
+fn save_game_result<R: Rng>(
+    conn: &mut Connection,
+    league: &mut League,
+    game: &GameRecord,
+    home_score: i32,
+    away_score: i32,
+    thread: &mut R,
+) -> Result<GameRecord, EditLeagueError> {
+    let new_record = record_game_result(conn, game, home_score, away_score)?;
+    update_team_records(league, game, &new_record);
+    if !game.is_played() {
+        for team_id in [game.home_team_id, game.away_team_id] {
+            let appeared = match league.team_id_hash.get(&team_id) {
+                Some(index) => everyday_players(&league.teams[*index]),
+                None => Vec::new(),
+            };
+            for message in after_game(conn, league, team_id, &appeared, thread)? {
+                println!("{}", message);
+            }
+        }
+    }
+    Ok(new_record)
+}
+
+/// Prompts the user for the result of a game, and saves it to the database. If the game was already played, the user is asked before it is overwritten.
+/// Afterwards, the user can attach a note to the game. Both teams roll for injuries after a game's first result is entered.
+fn enter_game_result<R: Rng>(
+    conn: &mut Connection,
+    league: &mut League,
+    series: &SeriesWrapper,
+    game: &GameRecord,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    println!("{}", game);
+    if game.is_played() {
+        let overwrite = handle_inquire_error(
+            Confirm::new("This game already has a result, would you like to replace it?")
+                .with_default(false)
+                .prompt(),
+        )?;
+        if !overwrite {
+            return Ok(());
+        }
+    }
+    let (home_score, away_score) = handle_inquire_error(get_final_score(series))?;
+    let new_record = save_game_result(conn, league, game, home_score, away_score, thread)?;
+    let add_note = handle_inquire_error(
+        Confirm::new("Would you like to add a note to this game?")
+            .with_default(false)
+            .prompt(),
+    )?;
+    if add_note {
+        let note = handle_inquire_error(new_record.generate_note())?;
+        handle_sql_error(save_game_note(conn, &new_record, &note))?;
+    }
+    Ok(())
+}
+
+/// Prompts the user to pick a round from the league's schedule, and walks through every series in the round, asking for the score of each game.
+/// Each result updates the teams wins and losses.
+pub fn enter_results_from_input<R: Rng>(
+    league: &mut League,
+    conn: &mut Connection,
+    thread: &mut R,
+) -> Result<(), EditLeagueError> {
+    let round_id = match select_round(league, conn)? {
+        Some(num) => num,
+        None => return Ok(()),
+    };
+    let series_vec = handle_sql_error(get_series_vec(conn, round_id))?;
+    for series in series_vec.iter() {
+        println!("\n{}", series);
+        let games = handle_sql_error(get_series_games(conn, series.series_id))?;
+        for game in games.iter() {
+            match enter_game_result(conn, league, series, game, thread) {
+                Ok(()) => {}
+                // If the user cancels, the results entered so far are kept, and the league's files are saved to show them.
+                Err(EditLeagueError::Inquire(message)) => {
+                    save_league(league);
+                    return inquire_check(message);
+                }
+                Err(message) => return Err(message),
+            }
+        }
+    }
+    println!("\nAll results for the round have been entered.");
+    save_league(league);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::era::Era;
+    use crate::game_result::get_game;
+    use crate::league::load_teams_from_sql;
+    use crate::player::PlayerGender;
+    use crate::schedule::{new_schedule, schedule_to_sql};
+    use crate::test_league;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn entered_results() {
+        let (mut conn, mut league) = test_league(Era::Modern, PlayerGender::Male, 5);
+        let sched = new_schedule(&league.teams, 1, 1);
+        schedule_to_sql(&mut conn, &league, sched).unwrap();
+        let game_id: i64 = conn
+            .query_row("SELECT MIN(game_id) FROM games", [], |row| row.get(0))
+            .unwrap();
+        let mut seeded = StdRng::seed_from_u64(5);
+        let records = |league: &League| -> Vec<(i32, i32)> {
+            league
+                .teams
+                .iter()
+                .map(|team| (team.wins, team.losses))
+                .collect()
+        };
+        let game = get_game(&conn, game_id).unwrap().unwrap();
+        let home_index = league.team_id_hash[&game.home_team_id];
+        save_game_result(&mut conn, &mut league, &game, 5, 3, &mut seeded).unwrap();
+        assert_eq!(league.teams[home_index].wins, 1);
+        // Correcting a result takes away the old win and loss, so the game is only counted once.
+        let game = get_game(&conn, game_id).unwrap().unwrap();
+        let corrected = save_game_result(&mut conn, &mut league, &game, 2, 4, &mut seeded).unwrap();
+        assert_eq!(corrected.winner_loser().unwrap().1, game.home_team_id);
+        assert_eq!(league.teams[home_index].wins, 0);
+        assert_eq!(league.teams[home_index].losses, 1);
+        let total: i32 = league
+            .teams
+            .iter()
+            .map(|team| team.wins + team.losses)
+            .sum();
+        assert_eq!(total, 2);
+        let mut loaded = League::new(
+            &league.name,
+            league.gender,
+            league.era,
+            league.league_id,
+            None,
+        );
+        load_teams_from_sql(league.league_id, &mut loaded, &mut conn).unwrap();
+        assert_eq!(records(&loaded), records(&league));
+    }
+}
@@ -0,0 +1,236 @@
+use crate::edit_league_error::handle_serde_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::era::Era;
+use crate::in_transaction;
+use crate::league::League;
+use crate::player::AgeCat;
+use crate::player::Player;
+use crate::player_quality::BatterQuality;
+use crate::player_quality::PitcherQuality;
+use crate::team::Team;
+use crate::team::TeamSpot;
+use crate::tier::Tier;
+use crate::PlayerGender;
+use chrono::{Datelike, Local};
+use rand::Rng;
+use rusqlite::named_params;
+use rusqlite::Connection;
+
+/* After the offseason, players that are 33 or older may retire.
+Each of those players rolls a d6, and retires if the roll is less than or equal to their age minus 32, so the older a player is the more likely they are to retire.
+Stars in tier A or S are less likely to retire, while players in tier D or F are more likely, as are players with a lower trade value than the average of their spot on the team.
+Players that are 38 or older always retire.
+Retired players are removed from the players table and saved in the retired_players table, and are replaced by a rookie at the same position, generated the same way as a new team's players.*/
+
+/// The age at which players may begin to retire.
+const RETIREMENT_AGE: i32 = 33;
+/// The age at which players always retire.
+const MANDATORY_RETIREMENT_AGE: i32 = 38;
+
+/// Returns the number a player's d6 roll must be less than or equal to for them to retire.
+fn retirement_target(player: &Player, average_trade_value: f32) -> i32 {
+    match player.age {
+        ..RETIREMENT_AGE => return 0,
+        MANDATORY_RETIREMENT_AGE.. => return 6,
+        _ => {}
+    }
+    let age_target = player.age - RETIREMENT_AGE + 1;
+    let tier_mod = match player.get_tier() {
+        Tier::S(_) | Tier::A(_) => -1,
+        Tier::B(_) | Tier::C(_) => 0,
+        Tier::D(_) | Tier::F(_) => 1,
+    };
+    let value_mod = match (player.trade_value as f32) < average_trade_value {
+        true => 1,
+        false => 0,
+    };
+    age_target + tier_mod + value_mod
+}
+
+/// Rolls to see if a player retires.
+pub fn should_retire<R: Rng>(player: &Player, average_trade_value: f32, thread: &mut R) -> bool {
+    thread.gen_range(1..=6) <= retirement_target(player, average_trade_value)
+}
+
+/// Generates a rookie to replace a retired player, with the same position and the quality used for new teams at that team spot.
+fn new_replacement<R: Rng>(
+    retired: &Player,
+    team_spot: TeamSpot,
+    gender: PlayerGender,
+    era: Era,
+    thread: &mut R,
+) -> Result<Player, EditLeagueError> {
+    let pos_value = handle_serde_error(serde_json::to_value(&retired.pos))?;
+    let mut player = match team_spot {
+        TeamSpot::StartingLineup => {
+            Player::new(pos_value, gender, BatterQuality::TopProspect, thread, era)
+        }
+        TeamSpot::BenchHitter => {
+            Player::new(pos_value, gender, BatterQuality::Farmhand, thread, era)
+        }
+        TeamSpot::StartingPitcher | TeamSpot::Bullpen => {
+            Player::new(pos_value, gender, PitcherQuality::TopProspect, thread, era)
+        }
+    };
+    player.age = AgeCat::Rookie.new_age(thread);
+    Ok(player)
+}
+
+/// Saves a player to the retired_players table, and removes them from the players table.
+fn save_retired_player(
+    conn: &Connection,
+    league_id: i64,
+    team_id: i64,
+    team_spot: TeamSpot,
+    player: &Player,
+) -> Result<(), EditLeagueError> {
+    let now = Local::now();
+    let date_retired = format!("{}_{}_{}", now.year(), now.month(), now.day());
+    let pos = handle_serde_error(serde_json::to_string(&player.pos))?;
+    let spot = handle_serde_error(serde_json::to_string(&team_spot))?;
+    let player_data = handle_serde_error(serde_json::to_string(player))?;
+    let mut stmt = handle_sql_error(conn.prepare_cached(
+        "INSERT INTO retired_players(league_id, team_id, player_id, player_name, age, pos, team_spot, tier, trade_value, date_retired, player_data)
+        VALUES(:league_id, :team_id, :player_id, :player_name, :age, :pos, :team_spot, :tier, :trade_value, :date_retired, :player_data)",
+    ))?;
+    handle_sql_error(stmt.execute(named_params! {
+        ":league_id": league_id,
+        ":team_id": team_id,
+        ":player_id": player.player_id,
+        ":player_name": player.name,
+        ":age": player.age,
+        ":pos": pos,
+        ":team_spot": spot,
+        ":tier": player.get_tier().to_string(),
+        ":trade_value": player.trade_value,
+        ":date_retired": date_retired,
+        ":player_data": player_data,
+    }))?;
+    handle_sql_error(conn.execute(
+        "DELETE FROM players WHERE player_id = ?1",
+        [player.player_id],
+    ))?;
+    Ok(())
+}
+
+/// Rolls for retirement for every player on a team, and replaces the players that retire.
+/// Returns a description of each retirement.
+fn team_retirements<R: Rng>(
+    conn: &mut Connection,
+    team: &mut Team,
+    league_id: i64,
+    gender: PlayerGender,
+    era: Era,
+    thread: &mut R,
+) -> Result<Vec<String>, EditLeagueError> {
+    let mut retirements = Vec::new();
+    let team_id = team.team_id;
+    let pools = [
+        (TeamSpot::StartingLineup, &mut team.lineup),
+        (TeamSpot::BenchHitter, &mut team.bench),
+        (TeamSpot::StartingPitcher, &mut team.starting_pitching),
+    ];
+    let bullpen = team.bullpen.iter_mut().map(|pen| (TeamSpot::Bullpen, pen));
+    for (team_spot, pool) in pools.into_iter().chain(bullpen) {
+        let total_value: i32 = pool.iter().map(|player| player.trade_value).sum();
+        let average_trade_value = total_value as f32 / pool.len().max(1) as f32;
+        for player in pool.iter_mut() {
+            if !should_retire(player, average_trade_value, thread) {
+                continue;
+            }
+            save_retired_player(conn, league_id, team_id, team_spot, player)?;
+            let mut replacement = new_replacement(player, team_spot, gender, era, thread)?;
+            replacement.save_sql(conn, team_id, team_spot)?;
+            retirements.push(format!(
+                "{} ({}, {}) retired, replaced by {} ({}, {})",
+                player.name,
+                player.pos,
+                player.age,
+                replacement.name,
+                replacement.pos,
+                replacement.age
+            ));
+            *player = replacement;
+        }
+    }
+    team.calc_team_score();
+    Ok(retirements)
+}
+
+/// Rolls for retirement for every player in a league, replacing each player that retires. Every change is saved in a single transaction.
+/// Returns the retirements for each team, in the same order as the league's teams.
+pub fn run_retirements<R: Rng>(
+    conn: &mut Connection,
+    league: &mut League,
+    thread: &mut R,
+) -> Result<Vec<Vec<String>>, EditLeagueError> {
+    let (league_id, gender, era) = (league.league_id, league.gender, league.era);
+    in_transaction(conn, |conn| {
+        league
+            .teams
+            .iter_mut()
+            .map(|team| team_retirements(conn, team, league_id, gender, era, thread))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::league::load_teams_from_sql;
+    use crate::offseason::run_offseason_with_retirements;
+    use crate::{player_pool_test, test_league};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn player_retirement() {
+        let (mut conn, mut league) = test_league(Era::Modern, PlayerGender::Female, 8);
+        // Every player will be 38 after the offseason, so every player retires.
+        for team in league.teams.iter_mut() {
+            let pools = [
+                &mut team.lineup,
+                &mut team.bench,
+                &mut team.starting_pitching,
+            ];
+            for pool in pools.into_iter().chain(team.bullpen.iter_mut()) {
+                pool.iter_mut().for_each(|player| player.age = 37);
+            }
+        }
+        let count = |conn: &Connection, sql: &str| -> i64 {
+            conn.query_row(sql, [], |row| row.get(0)).unwrap()
+        };
+        let player_count = count(&conn, "SELECT COUNT(*) FROM players");
+        let mut seeded = StdRng::seed_from_u64(3);
+        let reports = run_offseason_with_retirements(&mut conn, &mut league, &mut seeded).unwrap();
+        let retired: usize = reports.iter().map(|report| report.retired.len()).sum();
+        assert_eq!(retired as i64, player_count);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM players"), player_count);
+        assert_eq!(
+            count(&conn, "SELECT COUNT(*) FROM retired_players"),
+            player_count
+        );
+        assert_eq!(
+            count(
+                &conn,
+                "SELECT COUNT(*) FROM retired_players WHERE age != 38"
+            ),
+            0
+        );
+        let mut loaded = League::new(
+            &league.name,
+            league.gender,
+            league.era,
+            league.league_id,
+            None,
+        );
+        load_teams_from_sql(league.league_id, &mut loaded, &mut conn).unwrap();
+        for (team, report) in loaded.teams.iter().zip(reports.iter()) {
+            assert!(team.lineup.iter().all(|player| player.age < 28));
+            assert_eq!(team.team_score, report.new_team_score);
+            assert_eq!(team.bullpen.as_ref().unwrap().len(), 7);
+            player_pool_test(&team.lineup, team.team_id, false);
+            player_pool_test(&team.starting_pitching, team.team_id, true);
+        }
+    }
+}
@@ -0,0 +1,666 @@
+use core::fmt;
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::b_traits::BTraits;
+use crate::edit_league_error::handle_serde_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::in_transaction;
+use crate::inquire_check;
+use crate::league::load_teams_from_sql;
+use crate::league::save_league;
+use crate::league::League;
+use crate::pd::PD;
+use crate::player::Player;
+use crate::player_row::PlayerRow;
+use crate::settings::output_path;
+use crate::team::TeamSpot;
+use crate::traits::PitcherTrait;
+use inquire::validator::MinLengthValidator;
+use inquire::{Select, Text};
+use rusqlite::types::ValueRef;
+use rusqlite::Connection;
+use serde_json::Value;
+
+/* Rosters can be exported to a CSV file, so they can be used in a spreadsheet, and imported again after they have been edited.
+The columns match the players table, along with the name of each player's team. Columns that are saved as JSON in the database, such as pos and pd, are written as plain text.
+When a CSV is imported, rows with a player_id update that player, and rows without one create a new player. A player's team is found via the team_name column, which can be a team's name or abbreviation.
+A player's obt and trade value are calculated from the rest of the row, so a row with an obt that does not match bt + obt_mod is rejected, and the trade_value column is only written for reference.
+Once a CSV is imported, the league is loaded from the database again, so any hand or position that is not valid can be fixed the same way as a player that was edited in the database. Pitch dice must be valid, as the trade value is based off them.*/
+
+/// The columns in a roster CSV, in the order they are written.
+const COLUMNS: [&str; 20] = [
+    "player_id",
+    "team_id",
+    "team_name",
+    "team_spot",
+    "player_name",
+    "age",
+    "pos",
+    "hand",
+    "bt",
+    "obt_mod",
+    "obt",
+    "pd",
+    "pitcher_trait",
+    "contact",
+    "defense",
+    "power",
+    "speed",
+    "toughness",
+    "trade_value",
+    "player_note",
+];
+
+/// Columns that are saved as JSON in the players table.
+const JSON_COLUMNS: [&str; 11] = [
+    "team_spot",
+    "pos",
+    "hand",
+    "pd",
+    "pitcher_trait",
+    "contact",
+    "defense",
+    "power",
+    "speed",
+    "toughness",
+    "player_note",
+];
+
+/// Columns a CSV must have to be imported.
+const REQUIRED_COLUMNS: [&str; 8] = [
+    "team_name",
+    "team_spot",
+    "player_name",
+    "age",
+    "pos",
+    "hand",
+    "bt",
+    "obt_mod",
+];
+
+/// Wraps a value in quotes if it contains a comma, quote or new line.
+fn csv_field(value: &str) -> String {
+    match value.contains([',', '"', '\n', '\r']) {
+        true => format!("\"{}\"", value.replace('"', "\"\"")),
+        false => value.to_string(),
+    }
+}
+
+/// Splits the text of a CSV file into rows of fields. Fields may be wrapped in quotes, with two quotes in a row used for a quote inside a field.
+pub fn parse_csv(text: &str) -> Result<Vec<Vec<String>>, String> {
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (in_quotes, c) {
+            (true, '"') => match chars.peek() {
+                Some('"') => {
+                    field.push('"');
+                    chars.next();
+                }
+                _ => in_quotes = false,
+            },
+            (true, _) => field.push(c),
+            (false, '"') => in_quotes = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\r') => {}
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, _) => field.push(c),
+        }
+    }
+    if in_quotes {
+        return Err("The file ends inside of a quoted field.".to_string());
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Converts a value from the players table into the text written to the CSV.
+fn sql_to_field(value: ValueRef, is_json: bool) -> String {
+    match value {
+        ValueRef::Null => String::new(),
+        ValueRef::Integer(num) => num.to_string(),
+        ValueRef::Real(num) => num.to_string(),
+        ValueRef::Text(bytes) | ValueRef::Blob(bytes) => {
+            let text = String::from_utf8_lossy(bytes).to_string();
+            match (is_json, serde_json::from_str::<Value>(&text)) {
+                (true, Ok(Value::String(inner))) => inner,
+                (true, Ok(Value::Null)) => String::new(),
+                _ => text,
+            }
+        }
+    }
+}
+
+/// Converts a field from a CSV into the JSON value saved in the players table. Blank fields are saved as null.
+fn field_to_json(field: &str) -> Value {
+    let field = field.trim();
+    if field.is_empty() {
+        return Value::Null;
+    }
+    // Values that are not a plain string, such as a two way player's position, are written as JSON.
+    if field.starts_with('{') {
+        if let Ok(value) = serde_json::from_str(field) {
+            return value;
+        }
+    }
+    Value::String(field.to_string())
+}
+
+/// Returns the roster of a league as CSV text. If a team id is provided, only that team's players are included.
+pub fn export_roster_csv(
+    conn: &Connection,
+    league_id: i64,
+    team_id: Option<i64>,
+) -> Result<String, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT players.player_id, players.team_id, teams.team_name, players.team_spot, players.player_name,
+        players.age, players.pos, players.hand, players.bt, players.obt_mod, players.obt, players.PD,
+        players.pitcher_trait, players.contact, players.defense, players.power, players.speed,
+        players.toughness, players.trade_value, players.player_note
+        FROM players
+        INNER JOIN teams ON players.team_id = teams.team_id
+        WHERE teams.league_id = ?1 AND (?2 IS NULL OR teams.team_id = ?2)
+        ORDER BY teams.team_id, players.player_id",
+    )?;
+    let mut rows = stmt.query((league_id, team_id))?;
+    let mut result = COLUMNS.join(",");
+    result.push('\n');
+    while let Some(row) = rows.next()? {
+        let mut fields = Vec::new();
+        for (index, column) in COLUMNS.iter().enumerate() {
+            let is_json = JSON_COLUMNS.contains(column);
+            fields.push(csv_field(&sql_to_field(row.get_ref(index)?, is_json)));
+        }
+        result.push_str(&fields.join(","));
+        result.push('\n');
+    }
+    Ok(result)
+}
+
+/// A row from a roster CSV that has been checked and is ready to be saved.
+struct CsvPlayer {
+    player_id: Option<i64>,
+    team_id: i64,
+    name: String,
+    age: i32,
+    pos: Value,
+    hand: Value,
+    bt: i32,
+    obt_mod: i32,
+    obt: i32,
+    pd: Value,
+    pitcher_trait: Value,
+    team_spot: String,
+    contact: Value,
+    defense: Value,
+    power: Value,
+    speed: Value,
+    toughness: Value,
+    trade_value: i32,
+    note: Value,
+}
+
+impl CsvPlayer {
+    fn get_row(&self) -> PlayerRow<'_> {
+        PlayerRow {
+            team_id: self.team_id,
+            player_name: &self.name,
+            age: self.age,
+            pos: self.pos.clone(),
+            hand: self.hand.clone(),
+            bt: self.bt.to_string(),
+            obt_mod: self.obt_mod.to_string(),
+            obt: self.obt.to_string(),
+            pd: self.pd.clone(),
+            pitcher_trait: self.pitcher_trait.clone(),
+            team_spot: self.team_spot.to_string(),
+            contact: self.contact.clone(),
+            defense: self.defense.clone(),
+            power: self.power.clone(),
+            speed: self.speed.clone(),
+            toughness: self.toughness.clone(),
+            trade_value: self.trade_value,
+            note: self.note.clone(),
+        }
+    }
+}
+
+/// How many players were updated and created by an import.
+#[derive(Debug, PartialEq)]
+pub struct CsvImportSummary {
+    pub updated: usize,
+    pub created: usize,
+}
+
+impl fmt::Display for CsvImportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} players updated, {} players created.",
+            self.updated, self.created
+        )
+    }
+}
+
+/// Calculates the trade value of the player in a row. Traits that are not valid count as no trait, the same as when the player is loaded.
+fn row_trade_value(
+    bt: i32,
+    pd: Option<PD>,
+    pitcher_trait: Option<PitcherTrait>,
+    traits: [&Value; 5],
+) -> i32 {
+    let [contact, defense, power, speed, toughness] = traits;
+    let player = Player {
+        bt,
+        pd,
+        pitcher_trait,
+        b_traits: BTraits {
+            contact: serde_json::from_value(contact.clone()).unwrap_or_default(),
+            defense: serde_json::from_value(defense.clone()).unwrap_or_default(),
+            power: serde_json::from_value(power.clone()).unwrap_or_default(),
+            speed: serde_json::from_value(speed.clone()).unwrap_or_default(),
+            toughness: serde_json::from_value(toughness.clone()).unwrap_or_default(),
+        },
+        ..Player::default()
+    };
+    player.calc_trade_value()
+}
+
+/// Returns the ids of every player in a league.
+fn get_league_player_ids(conn: &Connection, league_id: i64) -> Result<Vec<i64>, rusqlite::Error> {
+    let mut stmt = conn.prepare(
+        "SELECT players.player_id FROM players INNER JOIN teams ON players.team_id = teams.team_id WHERE teams.league_id = ?1",
+    )?;
+    let rows = stmt.query_map([league_id], |row| row.get(0))?;
+    rows.collect()
+}
+
+/// Checks every row in a CSV and converts them into players. If any row has a problem, an ImportError listing every problem is returned.
+fn read_csv_players(
+    conn: &Connection,
+    league: &League,
+    text: &str,
+) -> Result<Vec<CsvPlayer>, EditLeagueError> {
+    let rows = parse_csv(text).map_err(EditLeagueError::ImportError)?;
+    let mut rows = rows.into_iter();
+    let header: HashMap<String, usize> = match rows.next() {
+        Some(header) => header
+            .iter()
+            .enumerate()
+            .map(|(index, name)| (name.trim().to_lowercase(), index))
+            .collect(),
+        None => {
+            return Err(EditLeagueError::ImportError(
+                "The file is empty.".to_string(),
+            ))
+        }
+    };
+    let missing: Vec<&str> = REQUIRED_COLUMNS
+        .iter()
+        .filter(|column| !header.contains_key(**column))
+        .copied()
+        .collect();
+    if !missing.is_empty() {
+        return Err(EditLeagueError::ImportError(format!(
+            "The file is missing the following columns: {}",
+            missing.join(", ")
+        )));
+    }
+    let player_ids = handle_sql_error(get_league_player_ids(conn, league.league_id))?;
+    let mut team_ids = HashMap::new();
+    for team in league.teams.iter() {
+        team_ids.insert(team.name.to_lowercase(), team.team_id);
+        team_ids.insert(team.abrv.to_lowercase(), team.team_id);
+    }
+    let mut players = Vec::new();
+    let mut problems = Vec::new();
+    // The header is line 1, so the first player is on line 2.
+    for (line, row) in (2..).zip(rows) {
+        if row.iter().all(|field| field.trim().is_empty()) {
+            continue;
+        }
+        let field = |column: &str| -> &str {
+            match header.get(column).and_then(|index| row.get(*index)) {
+                Some(value) => value.trim(),
+                None => "",
+            }
+        };
+        let mut line_problems = Vec::new();
+        let mut number = |column: &str, default: Option<i64>| -> i64 {
+            match (
+                field(column).parse::<i64>(),
+                field(column).is_empty(),
+                default,
+            ) {
+                (Ok(value), _, _) => value,
+                (Err(_), true, Some(value)) => value,
+                _ => {
+                    line_problems.push(format!("{} must be a whole number", column));
+                    0
+                }
+            }
+        };
+        let player_id = match field("player_id").is_empty() {
+            true => None,
+            false => Some(number("player_id", None)),
+        };
+        let age = number("age", None) as i32;
+        let bt = number("bt", None) as i32;
+        let obt_mod = number("obt_mod", None) as i32;
+        // A blank obt is calculated, while one that was changed without changing bt or obt_mod is a mistake.
+        let obt = number("obt", Some((bt + obt_mod).into())) as i32;
+        if obt != bt + obt_mod {
+            line_problems.push(format!(
+                "obt must be bt + obt_mod, which is {}",
+                bt + obt_mod
+            ));
+        }
+        if let Some(id) = player_id {
+            if !player_ids.contains(&id) {
+                line_problems.push(format!(
+                    "there is no player with id {} in {}",
+                    id, league.name
+                ));
+            }
+        }
+        let team_id = match team_ids.get(&field("team_name").to_lowercase()) {
+            Some(team_id) => *team_id,
+            None => {
+                line_problems.push(format!(
+                    "{} is not a team in {}",
+                    field("team_name"),
+                    league.name
+                ));
+                0
+            }
+        };
+        let team_spot = match serde_json::from_value::<TeamSpot>(field_to_json(field("team_spot")))
+        {
+            Ok(spot) => handle_serde_error(serde_json::to_string(&spot))?,
+            Err(_) => {
+                line_problems.push(format!(
+                    "{} is not a team spot, please use StartingLineup, BenchHitter, StartingPitcher or Bullpen",
+                    field("team_spot")
+                ));
+                String::new()
+            }
+        };
+        let pd = field_to_json(field("pd"));
+        let pd_value = match serde_json::from_value::<Option<PD>>(pd.clone()) {
+            Ok(pd_value) => pd_value,
+            Err(_) => {
+                line_problems.push(format!("{} is not a pitch die", field("pd")));
+                None
+            }
+        };
+        let pitcher_trait = field_to_json(field("pitcher_trait"));
+        let trait_value =
+            match serde_json::from_value::<Option<PitcherTrait>>(pitcher_trait.clone()) {
+                Ok(trait_value) => trait_value,
+                Err(_) => {
+                    line_problems
+                        .push(format!("{} is not a pitcher trait", field("pitcher_trait")));
+                    None
+                }
+            };
+        let traits = [
+            field_to_json(field("contact")),
+            field_to_json(field("defense")),
+            field_to_json(field("power")),
+            field_to_json(field("speed")),
+            field_to_json(field("toughness")),
+        ];
+        let trade_value = row_trade_value(bt, pd_value, trait_value, traits.each_ref());
+        let [contact, defense, power, speed, toughness] = traits;
+        let name = field("player_name").to_string();
+        if name.is_empty() {
+            line_problems.push("player_name can not be blank".to_string());
+        }
+        let note = match field("player_note") {
+            "" => Value::Null,
+            text => Value::String(text.to_string()),
+        };
+        match line_problems.is_empty() {
+            false => problems.push(format!("Line {}: {}.", line, line_problems.join(", "))),
+            true => players.push(CsvPlayer {
+                player_id,
+                team_id,
+                name,
+                age,
+                pos: field_to_json(field("pos")),
+                hand: field_to_json(field("hand")),
+                bt,
+                obt_mod,
+                obt,
+                pd,
+                pitcher_trait,
+                team_spot,
+                contact,
+                defense,
+                power,
+                speed,
+                toughness,
+                trade_value,
+                note,
+            }),
+        }
+    }
+    match problems.is_empty() {
+        true => Ok(players),
+        false => Err(EditLeagueError::ImportError(format!(
+            "The file can not be imported:\n{}",
+            problems.join("\n")
+        ))),
+    }
+}
+
+/// Imports a roster CSV into a league. Nothing is saved unless every row is valid.
+pub fn import_roster_csv(
+    conn: &mut Connection,
+    league: &League,
+    text: &str,
+) -> Result<CsvImportSummary, EditLeagueError> {
+    let players = read_csv_players(conn, league, text)?;
+    in_transaction(conn, |conn| {
+        let mut summary = CsvImportSummary {
+            updated: 0,
+            created: 0,
+        };
+        for player in players.iter() {
+            let row = player.get_row();
+            match player.player_id {
+                Some(player_id) => {
+                    handle_sql_error(row.update_sql(conn, player_id))?;
+                    summary.updated += 1;
+                }
+                None => {
+                    handle_sql_error(row.save_to_sql(conn))?;
+                    summary.created += 1;
+                }
+            }
+        }
+        Ok(summary)
+    })
+}
+
+/// The choices a user has when exporting a roster.
+enum ExportChoice {
+    League,
+    Team(i64, String),
+}
+
+impl fmt::Display for ExportChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::League => write!(f, "Every team in the league"),
+            Self::Team(_, name) => write!(f, "{}", name),
+        }
+    }
+}
+
+/// Writes a roster CSV to the output folder, and returns the path it was written to.
+pub fn save_roster_csv(
+    conn: &Connection,
+    league: &League,
+    team: Option<(i64, &str)>,
+) -> Result<PathBuf, EditLeagueError> {
+    let (team_id, file_name) = match team {
+        Some((team_id, team_name)) => (
+            Some(team_id),
+            format!("{}_{}_Roster.csv", league.name, team_name),
+        ),
+        None => (None, format!("{}_Roster.csv", league.name)),
+    };
+    let text = handle_sql_error(export_roster_csv(conn, league.league_id, team_id))?;
+    let path = output_path(&file_name);
+    fs::write(&path, text).map_err(|message| {
+        EditLeagueError::ImportError(format!(
+            "Unable to save {}. The error was {}",
+            path.display(),
+            message
+        ))
+    })?;
+    Ok(path)
+}
+
+/// Prompts the user to export the roster of a whole league or a single team to a CSV file.
+pub fn export_roster_from_input(league: &League, conn: &Connection) -> Result<(), EditLeagueError> {
+    let mut stmt = handle_sql_error(
+        conn.prepare("SELECT team_id, team_name FROM teams WHERE league_id = ?1"),
+    )?;
+    let mut options = vec![ExportChoice::League];
+    let teams: Result<Vec<ExportChoice>, rusqlite::Error> =
+        handle_sql_error(stmt.query_map([league.league_id], |row| {
+            Ok(ExportChoice::Team(row.get(0)?, row.get(1)?))
+        }))?
+        .collect();
+    options.extend(handle_sql_error(teams)?);
+    let choice = match Select::new("Which roster would you like to export?", options).prompt() {
+        Ok(choice) => choice,
+        Err(message) => return inquire_check(message),
+    };
+    let team = match &choice {
+        ExportChoice::League => None,
+        ExportChoice::Team(team_id, name) => Some((*team_id, name.as_str())),
+    };
+    let path = save_roster_csv(conn, league, team)?;
+    println!("Roster saved to {}", path.display());
+    Ok(())
+}
+
+/// Reads a roster CSV and imports it into a league. Afterwards the league is loaded again, which lets the user fix any hand, pitch die or position that is not valid, and is saved to it's folder.
+pub fn import_roster_file(
+    conn: &mut Connection,
+    league: &League,
+    path: &str,
+) -> Result<(), EditLeagueError> {
+    let text = fs::read_to_string(path).map_err(|message| {
+        EditLeagueError::ImportError(format!(
+            "Unable to read {}. The error was {}",
+            path, message
+        ))
+    })?;
+    let summary = import_roster_csv(conn, league, &text)?;
+    println!("{}", summary);
+    let mut reloaded = League::new(
+        &league.name,
+        league.gender,
+        league.era,
+        league.league_id,
+        league.seed,
+    );
+    reloaded.note = league.note.clone();
+    load_teams_from_sql(league.league_id, &mut reloaded, conn)?;
+    save_league(&reloaded);
+    Ok(())
+}
+
+/// Prompts the user for the path of a roster CSV, and imports it into a league.
+pub fn import_roster_from_input(
+    league: &League,
+    conn: &mut Connection,
+) -> Result<(), EditLeagueError> {
+    let path = Text::new("Please enter the path of the CSV file to import.")
+        .with_validator(MinLengthValidator::new(1))
+        .prompt();
+    match path {
+        Ok(path) => import_roster_file(conn, league, &path),
+        Err(message) => inquire_check(message),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::era::Era;
+    use crate::player::PlayerGender;
+    use crate::test_league;
+
+    #[test]
+    fn roster_csv_round_trip() {
+        let rows = parse_csv("a,\"b, \"\"c\"\"\"\r\n\nd").unwrap();
+        assert_eq!(rows[0], vec!["a".to_string(), "b, \"c\"".to_string()]);
+        assert_eq!(rows.len(), 3);
+        let (mut conn, league) = test_league(Era::Ancient, PlayerGender::Female, 11);
+        let exported = export_roster_csv(&conn, league.league_id, None).unwrap();
+        let summary = import_roster_csv(&mut conn, &league, &exported).unwrap();
+        assert_eq!(summary.created, 0);
+        assert_eq!(summary.updated, exported.lines().count() - 1);
+        assert_eq!(
+            export_roster_csv(&conn, league.league_id, None).unwrap(),
+            exported
+        );
+        // A row without a player_id creates a new player.
+        let new_row = exported.lines().nth(1).unwrap().split_once(',').unwrap().1;
+        let text = format!("{}\n,{}", exported.lines().next().unwrap(), new_row);
+        let summary = import_roster_csv(&mut conn, &league, &text).unwrap();
+        assert_eq!(summary.created, 1);
+        // The trade value is calculated from the row, while an obt that does not match bt + obt_mod is rejected.
+        let header = exported.lines().next().unwrap();
+        let mut fields: Vec<String> = exported
+            .lines()
+            .nth(1)
+            .unwrap()
+            .split(',')
+            .map(String::from)
+            .collect();
+        let column = |name: &str| COLUMNS.iter().position(|column| *column == name).unwrap();
+        let player_id: i64 = fields[column("player_id")].parse().unwrap();
+        let expected: i32 = fields[column("trade_value")].parse().unwrap();
+        fields[column("trade_value")] = "0".to_string();
+        import_roster_csv(
+            &mut conn,
+            &league,
+            &format!("{}\n{}", header, fields.join(",")),
+        )
+        .unwrap();
+        let saved: i32 = conn
+            .query_row(
+                "SELECT trade_value FROM players WHERE player_id = ?1",
+                [player_id],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(saved, expected);
+        let obt: i32 = fields[column("obt")].parse().unwrap();
+        fields[column("obt")] = (obt + 1).to_string();
+        let result = import_roster_csv(
+            &mut conn,
+            &league,
+            &format!("{}\n{}", header, fields.join(",")),
+        );
+        assert!(matches!(result, Err(EditLeagueError::ImportError(_))));
+    }
+}
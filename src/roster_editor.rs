@@ -0,0 +1,447 @@
+use core::fmt;
+
+use crate::edit_league_error::handle_inquire_error;
+use crate::edit_league_error::handle_serde_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::era::Era;
+use crate::in_transaction;
+use crate::inquire_check;
+use crate::league::save_league;
+use crate::league::League;
+use crate::minor_leaguer::push_to_roster;
+use crate::minor_leaguer::CALL_UP_POSITIONS;
+use crate::player::Player;
+use crate::position::PlayerPosition;
+use crate::team::Team;
+use crate::team::TeamSpot;
+use crate::trade::roster_problems;
+use crate::trade::roster_with_spots;
+use crate::trade::take_player;
+use inquire::{Confirm, Select};
+use rusqlite::Connection;
+
+/* The roster editor changes the team spot and position of players on a team.
+Two players can swap team spots, which is used to promote a bench player into the lineup in place of a starter, or to move a reliever into the rotation in place of a starter on a Modern Era team.
+When batters swap, they also swap positions, so the player promoted to the lineup takes over the position of the starter they replace.
+When pitchers swap, they become an SP in the rotation or an RP in the bullpen. The position of any batter can also be changed directly.
+Each team must keep the roster shape of it's era: 8 starters covering every position in the field, 4 bench players and 5 starting pitchers in the Ancient Era, and 5 bench players, 5 starting pitchers and 7 relievers in the Modern Era.
+An edit is not made if it would leave a team's roster out of shape in a way it was not before.*/
+
+/// A single change to a team's roster.
+pub enum RosterMove {
+    /// Two players swap team spots.
+    Swap(i64, i64),
+    /// A batter is given a new position.
+    SetPosition(i64, PlayerPosition),
+}
+
+/// Parses a batter position, such as SS or INF.
+pub fn parse_batter_pos(pos: &str) -> Result<PlayerPosition, EditLeagueError> {
+    handle_serde_error(serde_json::from_value(pos.into()))
+}
+
+/// Returns a position without the quotes it is displayed with.
+fn pos_name(pos: &PlayerPosition) -> String {
+    pos.to_string().trim_matches('"').to_string()
+}
+
+/// Returns the position a pitcher has after moving to a new team spot. Modern Era pitchers are SP in the rotation and RP in the bullpen.
+fn pitcher_pos(player: &Player, team_spot: TeamSpot, era: Era) -> PlayerPosition {
+    match (&player.pos, era, team_spot) {
+        (PlayerPosition::TwoWay(_), _, _) | (_, Era::Ancient, _) => player.pos.clone(),
+        (_, Era::Modern, TeamSpot::Bullpen) => PlayerPosition::RP,
+        (_, Era::Modern, _) => PlayerPosition::SP,
+    }
+}
+
+/// Swaps the team spots of two players on a team.
+fn swap_players(team: &mut Team, first: i64, second: i64, era: Era) -> Result<(), EditLeagueError> {
+    let mut taken = Vec::new();
+    for player_id in [first, second] {
+        match take_player(team, player_id) {
+            Some(player) => taken.push(player),
+            None => {
+                return Err(EditLeagueError::RosterError(format!(
+                    "There is no player with the id {} on {}.",
+                    player_id, team.name
+                )))
+            }
+        }
+    }
+    let (mut second_player, second_spot) = taken.pop().unwrap();
+    let (mut first_player, first_spot) = taken.pop().unwrap();
+    if first_player.is_pitcher() != second_player.is_pitcher() {
+        return Err(EditLeagueError::RosterError(format!(
+            "{} and {} can not swap places, as only batters can swap with batters and pitchers with pitchers.",
+            first_player.name, second_player.name
+        )));
+    }
+    if first_spot == second_spot {
+        return Err(EditLeagueError::RosterError(format!(
+            "{} and {} are both in the {}.",
+            first_player.name, second_player.name, first_spot
+        )));
+    }
+    match first_player.is_pitcher() {
+        true => {
+            first_player.pos = pitcher_pos(&first_player, second_spot, era);
+            second_player.pos = pitcher_pos(&second_player, first_spot, era);
+        }
+        false => std::mem::swap(&mut first_player.pos, &mut second_player.pos),
+    }
+    push_to_roster(team, first_player, second_spot);
+    push_to_roster(team, second_player, first_spot);
+    Ok(())
+}
+
+/// Gives a batter on a team a new position.
+fn set_position(
+    team: &mut Team,
+    player_id: i64,
+    pos: PlayerPosition,
+) -> Result<(), EditLeagueError> {
+    let player = team
+        .lineup
+        .iter_mut()
+        .chain(team.bench.iter_mut())
+        .find(|player| player.player_id == player_id);
+    match player {
+        Some(player) if matches!(player.pos, PlayerPosition::TwoWay(_)) => {
+            Err(EditLeagueError::RosterError(format!(
+                "{} is a two way player, and keeps their position.",
+                player.name
+            )))
+        }
+        Some(player) => {
+            player.pos = pos;
+            Ok(())
+        }
+        None => Err(EditLeagueError::RosterError(format!(
+            "There is no batter with the id {} on {}.",
+            player_id, team.name
+        ))),
+    }
+}
+
+/// A planned edit to a team's roster, along with the roster the team would have afterwards.
+pub struct RosterEdit {
+    team_index: usize,
+    old_team: Team,
+    new_team: Team,
+    pub problems: Vec<String>,
+}
+
+impl RosterEdit {
+    /// Returns each player whose team spot or position changes, along with their old and new team spots.
+    fn changes(&self) -> Vec<(&Player, &Player, TeamSpot, TeamSpot)> {
+        let old_roster = roster_with_spots(&self.old_team);
+        roster_with_spots(&self.new_team)
+            .into_iter()
+            .filter_map(|(new_spot, new_player)| {
+                old_roster
+                    .iter()
+                    .find(|(_, old_player)| old_player.player_id == new_player.player_id)
+                    .filter(|(old_spot, old_player)| {
+                        *old_spot != new_spot || old_player.pos != new_player.pos
+                    })
+                    .map(|(old_spot, old_player)| (*old_player, new_player, *old_spot, new_spot))
+            })
+            .collect()
+    }
+
+    /// Returns an error listing every roster problem the edit would cause.
+    pub fn check(&self) -> Result<(), EditLeagueError> {
+        match self.problems.is_empty() {
+            true => Ok(()),
+            false => Err(EditLeagueError::RosterError(format!(
+                "The roster can not be changed.\n{}",
+                self.problems.join("\n")
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for RosterEdit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Changes to {}:", self.new_team.name)?;
+        for (old_player, new_player, old_spot, new_spot) in self.changes() {
+            writeln!(
+                f,
+                "    {}: {} {} -> {} {}",
+                new_player.name,
+                old_spot,
+                pos_name(&old_player.pos),
+                new_spot,
+                pos_name(&new_player.pos)
+            )?;
+        }
+        write!(f, "New Team Score: {}", self.new_team.team_score)
+    }
+}
+
+/// Plans a set of moves for a team, which are made in order.
+/// Any roster problems the moves would cause that the team did not already have are saved in the edit's problems.
+pub fn plan_roster_edit(
+    league: &League,
+    team_index: usize,
+    moves: &[RosterMove],
+) -> Result<RosterEdit, EditLeagueError> {
+    let old_team = league.teams[team_index].clone();
+    let mut new_team = old_team.clone();
+    for roster_move in moves {
+        match roster_move {
+            RosterMove::Swap(first, second) => {
+                swap_players(&mut new_team, *first, *second, league.era)?
+            }
+            RosterMove::SetPosition(player_id, pos) => {
+                set_position(&mut new_team, *player_id, pos.clone())?
+            }
+        }
+    }
+    new_team.calc_team_score();
+    let old_problems = roster_problems(&old_team, league.era);
+    let problems = roster_problems(&new_team, league.era)
+        .into_iter()
+        .filter(|problem| !old_problems.contains(problem))
+        .collect();
+    Ok(RosterEdit {
+        team_index,
+        old_team,
+        new_team,
+        problems,
+    })
+}
+
+/// Saves the new team spot and position of each player changed by an edit to the database, and updates the league's team.
+pub fn apply_roster_edit(
+    conn: &mut Connection,
+    league: &mut League,
+    edit: RosterEdit,
+) -> Result<(), EditLeagueError> {
+    edit.check()?;
+    let team_id = edit.new_team.team_id;
+    in_transaction(conn, |conn| {
+        for (_, player, _, new_spot) in edit.changes() {
+            let team_spot = handle_serde_error(serde_json::to_string(&new_spot))?;
+            let pos = handle_serde_error(serde_json::to_string(&player.pos))?;
+            handle_sql_error(conn.execute(
+                "UPDATE players SET team_spot = ?1, pos = ?2 WHERE player_id = ?3 AND team_id = ?4",
+                (team_spot, pos, player.player_id, team_id),
+            ))?;
+        }
+        Ok(())
+    })?;
+    league.teams[edit.team_index] = edit.new_team;
+    Ok(())
+}
+
+/// The actions that can be taken in the roster editor.
+#[derive(Debug, Clone, Copy)]
+enum EditorAction {
+    Swap,
+    SetPosition,
+    Finish,
+}
+
+impl fmt::Display for EditorAction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let chars = match self {
+            Self::Swap => {
+                "Swap two players between the lineup and bench, or the rotation and bullpen."
+            }
+            Self::SetPosition => "Change a batter's position.",
+            Self::Finish => "Finish editing the roster.",
+        };
+        write!(f, "{}", chars)
+    }
+}
+
+/// Prompts the user to choose a player from a list, and returns their id.
+fn select_player(message: &str, players: Vec<(TeamSpot, &Player)>) -> Result<i64, EditLeagueError> {
+    let options: Vec<String> = players
+        .iter()
+        .map(|(spot, player)| format!("{} {} ({})", player.name, pos_name(&player.pos), spot))
+        .collect();
+    let choice = handle_inquire_error(
+        Select::new(message, options)
+            .with_page_size(10)
+            .raw_prompt(),
+    )?;
+    Ok(players[choice.index].1.player_id)
+}
+
+/// Prompts the user for a single move on a team's roster as it currently stands.
+fn move_from_input(team: &Team, action: EditorAction) -> Result<RosterMove, EditLeagueError> {
+    let roster = roster_with_spots(team);
+    match action {
+        EditorAction::Swap => {
+            let first = select_player("Which player would you like to move?", roster.clone())?;
+            let (first_spot, first_pitcher) =
+                match roster.iter().find(|(_, player)| player.player_id == first) {
+                    Some((spot, player)) => (*spot, player.is_pitcher()),
+                    None => {
+                        return Err(EditLeagueError::RosterError(format!(
+                            "There is no player with the id {} on {}.",
+                            first, team.name
+                        )))
+                    }
+                };
+            let others: Vec<(TeamSpot, &Player)> = roster
+                .into_iter()
+                .filter(|(spot, player)| {
+                    player.is_pitcher() == first_pitcher && *spot != first_spot
+                })
+                .collect();
+            let second = select_player("Which player will they swap places with?", others)?;
+            Ok(RosterMove::Swap(first, second))
+        }
+        _ => {
+            let batters: Vec<(TeamSpot, &Player)> = roster
+                .into_iter()
+                .filter(|(_, player)| !player.is_pitcher())
+                .collect();
+            let player_id =
+                select_player("Which batter's position would you like to change?", batters)?;
+            let pos = handle_inquire_error(
+                Select::new(
+                    "What is the batter's new position?",
+                    CALL_UP_POSITIONS.to_vec(),
+                )
+                .prompt(),
+            )?;
+            Ok(RosterMove::SetPosition(player_id, parse_batter_pos(pos)?))
+        }
+    }
+}
+
+/// Prompts the user to choose a team and make changes to it's roster, then saves the changes if they keep the roster in shape.
+pub fn roster_editor_from_input(
+    league: &mut League,
+    conn: &mut Connection,
+) -> Result<(), EditLeagueError> {
+    let team_names: Vec<String> = league.teams.iter().map(|team| team.name.clone()).collect();
+    let team_index =
+        match Select::new("Which team's roster would you like to edit?", team_names).raw_prompt() {
+            Ok(choice) => choice.index,
+            Err(message) => return inquire_check(message),
+        };
+    let mut moves = Vec::new();
+    let edit = loop {
+        let edit = plan_roster_edit(league, team_index, &moves)?;
+        println!("{}", edit);
+        for problem in edit.problems.iter() {
+            println!("Warning: {}", problem);
+        }
+        let actions = vec![
+            EditorAction::Swap,
+            EditorAction::SetPosition,
+            EditorAction::Finish,
+        ];
+        let action = match Select::new("What would you like to do?", actions).prompt() {
+            Ok(action) => action,
+            Err(message) => return inquire_check(message),
+        };
+        if let EditorAction::Finish = action {
+            break edit;
+        }
+        match move_from_input(&edit.new_team, action) {
+            Ok(roster_move) => {
+                moves.push(roster_move);
+                // A move that can not be made is dropped, so the user can try another.
+                if let Err(message) = plan_roster_edit(league, team_index, &moves) {
+                    println!("{}", message);
+                    moves.pop();
+                }
+            }
+            Err(EditLeagueError::Inquire(message)) => return inquire_check(message),
+            Err(message) => return Err(message),
+        }
+    };
+    if moves.is_empty() {
+        return Ok(());
+    }
+    edit.check()?;
+    match Confirm::new("Would you like to save these changes?").prompt() {
+        Ok(true) => {}
+        Ok(false) => return Ok(()),
+        Err(message) => return inquire_check(message),
+    }
+    apply_roster_edit(conn, league, edit)?;
+    println!("The roster has been saved.");
+    save_league(league);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::league::load_teams_from_sql;
+    use crate::player::PlayerGender;
+    use crate::test_league;
+
+    #[test]
+    fn roster_editor() {
+        let (mut conn, mut league) = test_league(Era::Modern, PlayerGender::Male, 23);
+        let team = &league.teams[0];
+        let shortstop = team
+            .lineup
+            .iter()
+            .find(|x| x.pos == PlayerPosition::SS)
+            .unwrap()
+            .player_id;
+        let infielder = team
+            .bench
+            .iter()
+            .find(|x| x.pos == PlayerPosition::INF)
+            .unwrap()
+            .player_id;
+        let starter = team.starting_pitching[0].player_id;
+        let reliever = team.bullpen.as_ref().unwrap()[0].player_id;
+        // Batters and pitchers can not swap places.
+        let mixed = plan_roster_edit(&league, 0, &[RosterMove::Swap(shortstop, starter)]);
+        assert!(matches!(mixed, Err(EditLeagueError::RosterError(_))));
+        // A change that leaves the lineup without a catcher is not saved.
+        let catcher = team
+            .lineup
+            .iter()
+            .find(|x| x.pos == PlayerPosition::C)
+            .unwrap()
+            .player_id;
+        let moves = [RosterMove::SetPosition(catcher, PlayerPosition::UT)];
+        let edit = plan_roster_edit(&league, 0, &moves).unwrap();
+        assert!(edit.check().is_err());
+        assert!(apply_roster_edit(&mut conn, &mut league, edit).is_err());
+        // The promoted bench player takes over the starter's position, and the reliever joins the rotation as an SP.
+        let moves = [
+            RosterMove::Swap(infielder, shortstop),
+            RosterMove::Swap(reliever, starter),
+        ];
+        let edit = plan_roster_edit(&league, 0, &moves).unwrap();
+        assert!(edit.problems.is_empty());
+        let changes = edit.to_string();
+        assert!(changes.contains("Bench Hitter INF -> Starting Lineup SS"));
+        apply_roster_edit(&mut conn, &mut league, edit).unwrap();
+        let mut loaded = League::new(
+            &league.name,
+            league.gender,
+            league.era,
+            league.league_id,
+            None,
+        );
+        load_teams_from_sql(league.league_id, &mut loaded, &mut conn).unwrap();
+        let team = &loaded.teams[0];
+        let find = |pool: &Vec<Player>, player_id: i64| {
+            let player = pool.iter().find(|x| x.player_id == player_id).unwrap();
+            player.pos.clone()
+        };
+        assert_eq!(find(&team.lineup, infielder), PlayerPosition::SS);
+        assert_eq!(find(&team.bench, shortstop), PlayerPosition::INF);
+        assert_eq!(find(&team.starting_pitching, reliever), PlayerPosition::SP);
+        assert_eq!(
+            find(team.bullpen.as_ref().unwrap(), starter),
+            PlayerPosition::RP
+        );
+        assert!(roster_problems(team, loaded.era).is_empty());
+    }
+}
@@ -1,6 +1,7 @@
 use core::fmt;
 
 use crate::edit_league_error::EditLeagueError;
+use crate::game_result::get_series_games;
 use crate::{inquire_check, league::League};
 use inquire::Select;
 use rusqlite::Connection;
@@ -33,11 +34,11 @@ fn get_round_vec(conn: &Connection, season_id: i64) -> Result<Vec<i64>, rusqlite
 }
 #[derive(Debug)]
 pub struct SeriesWrapper {
-    series_id: i64,
-    home_team_name: String,
-    home_team_id: i64,
-    away_team_name: String,
-    away_team_id: i64,
+    pub series_id: i64,
+    pub home_team_name: String,
+    pub home_team_id: i64,
+    pub away_team_name: String,
+    pub away_team_id: i64,
 }
 
 impl fmt::Display for SeriesWrapper {
@@ -46,7 +47,10 @@ impl fmt::Display for SeriesWrapper {
     }
 }
 
-fn get_series_vec(conn: &Connection, round_id: i64) -> Result<Vec<SeriesWrapper>, rusqlite::Error> {
+pub fn get_series_vec(
+    conn: &Connection,
+    round_id: i64,
+) -> Result<Vec<SeriesWrapper>, rusqlite::Error> {
     let mut series_stmt = conn.prepare(
         "
         WITH home_teams AS(
@@ -101,18 +105,6 @@ fn get_series_vec(conn: &Connection, round_id: i64) -> Result<Vec<SeriesWrapper>
     Ok(result_vec)
 }
 
-fn get_game_vec(conn: &Connection, wrapper: &SeriesWrapper) -> Result<Vec<i64>, rusqlite::Error> {
-    let mut games_stmt =
-        conn.prepare("SELECT games.game_id FROM games WHERE games.series_id = ?1")?;
-    let games_iter = games_stmt.query_map([wrapper.series_id], |row| row.get(0))?;
-    let mut result_vec = Vec::new();
-    for num in games_iter {
-        result_vec.push(num?)
-    }
-
-    Ok(result_vec)
-}
-
 struct RoundChoiceListing {
     index: usize,
     value: i64,
@@ -124,20 +116,29 @@ impl fmt::Display for RoundChoiceListing {
     }
 }
 
-pub fn view_schedule(league: &League, conn: &Connection) -> Result<(), EditLeagueError> {
+/// Prompts the user to choose a season from the league's schedule. Returns None if no schedule has been generated or the user cancels.
+pub fn select_season(league: &League, conn: &Connection) -> Result<Option<i64>, EditLeagueError> {
     let sched_vec = match get_season_vec(league, conn) {
         Ok(vec) => vec,
         Err(message) => return Err(EditLeagueError::DatabaseError(message)),
     };
     if sched_vec.is_empty() {
         println!("No schedule generated");
-        return Ok(());
+        return Ok(None);
     }
     let season_choice = Select::new("Choose a season to view.", sched_vec).prompt();
 
-    let season_id = match season_choice {
-        Ok(num) => num,
-        Err(message) => return inquire_check(message),
+    match season_choice {
+        Ok(num) => Ok(Some(num)),
+        Err(message) => inquire_check(message).map(|_| None),
+    }
+}
+
+/// Prompts the user to choose a season, and then a round from that season.
+pub fn select_round(league: &League, conn: &Connection) -> Result<Option<i64>, EditLeagueError> {
+    let season_id = match select_season(league, conn)? {
+        Some(num) => num,
+        None => return Ok(None),
     };
     //println!("{:?}",sched_vec?);
     let init_rounds = match get_round_vec(conn, season_id) {
@@ -153,9 +154,20 @@ pub fn view_schedule(league: &League, conn: &Connection) -> Result<(), EditLeagu
     //println!("{:?}",round_vec);
     let round_choice = Select::new("Choose a round to view.", round_vec).prompt();
 
-    let round_id = match round_choice {
-        Ok(listing) => listing.value,
-        Err(message) => return inquire_check(message),
+    match round_choice {
+        Ok(listing) => Ok(Some(listing.value)),
+        Err(message) => inquire_check(message).map(|_| None),
+    }
+}
+
+/// Prompts the user to choose a season, round, and finally a series from the league's schedule.
+pub fn select_series(
+    league: &League,
+    conn: &Connection,
+) -> Result<Option<SeriesWrapper>, EditLeagueError> {
+    let round_id = match select_round(league, conn)? {
+        Some(num) => num,
+        None => return Ok(None),
     };
     let series_vec = match get_series_vec(conn, round_id) {
         Ok(vec) => vec,
@@ -163,16 +175,25 @@ pub fn view_schedule(league: &League, conn: &Connection) -> Result<(), EditLeagu
     };
     //println!("{:?}",series_vec);
     let series_choice = Select::new("Choose a series from the round", series_vec).prompt();
-    let choosed_wrapper = match series_choice {
-        Ok(wrapper) => wrapper,
-        Err(message) => return inquire_check(message),
+    match series_choice {
+        Ok(wrapper) => Ok(Some(wrapper)),
+        Err(message) => inquire_check(message).map(|_| None),
+    }
+}
+
+pub fn view_schedule(league: &League, conn: &Connection) -> Result<(), EditLeagueError> {
+    let choosed_wrapper = match select_series(league, conn)? {
+        Some(wrapper) => wrapper,
+        None => return Ok(()),
     };
 
-    match get_game_vec(conn, &choosed_wrapper) {
-        Ok(game_vec) => {
-            println!("{:?}", game_vec);
-            Ok(())
-        }
-        Err(message) => Err(EditLeagueError::DatabaseError(message)),
+    println!("{}", choosed_wrapper);
+    let games = match get_series_games(conn, choosed_wrapper.series_id) {
+        Ok(vec) => vec,
+        Err(message) => return Err(EditLeagueError::DatabaseError(message)),
+    };
+    for game in games.iter() {
+        println!("{}", game);
     }
+    Ok(())
 }
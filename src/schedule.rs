@@ -1,14 +1,11 @@
+use crate::edit_league_error::handle_sql_error;
 use crate::edit_league_error::EditLeagueError;
+use crate::in_transaction;
 use crate::inquire_check;
 use crate::league::{save_league, League};
 use crate::team::Team;
-use itertools::Itertools;
-use std::collections::HashMap;
 //use serde::ser;
 use inquire::{CustomType, InquireError};
-use rand::prelude::IteratorRandom;
-use rand::seq::SliceRandom;
-use rand::thread_rng;
 use rusqlite::Connection;
 //use inquire::validator::{I, Validation};
 //Ok, to generate a whole season, we will start with the smaller elements and build up from there.
@@ -35,13 +32,6 @@ pub struct Series {
     away_team_id: i64,
 }
 
-impl Series {
-    /// When given a vector of ints, this function returns true if the series home team and away team id are no in the vector.
-    fn is_valid(&self, forbidden_numbers: &HashMap<i64, bool>) -> bool {
-        (forbidden_numbers.get(&self.home_team_id).is_none())
-            & (forbidden_numbers.get(&self.away_team_id).is_none())
-    }
-}
 fn new_series(home_team_id: i64, away_team_id: i64, series_length: i32) -> Series {
     Series {
         games: vec![new_game(home_team_id, away_team_id); series_length as usize],
@@ -55,111 +45,86 @@ pub struct Round {
     series: Vec<Series>,
 }
 
-/// SeriesListing is a struct that contains a refrence to a series, as well as an index field that marks under what position the series is in a vector.
-#[derive(Debug)]
-struct SeriesListing<'a> {
-    series: &'a Series,
-    index: usize,
+impl Round {
+    #[cfg(test)]
+    /// Returns a tuple of the home and away team id for each series in the round.
+    pub fn get_matchups(&self) -> Vec<(i64, i64)> {
+        self.series
+            .iter()
+            .map(|series| (series.home_team_id, series.away_team_id))
+            .collect()
+    }
 }
 
-// Generates a schedule based off a vec of series
-pub fn new_round_generator(mut all_series: Vec<Series>, _series_per_round: i32) -> Vec<Round> {
+/* Schedules are generated via the circle method.
+Every team is placed in a slot around a circle, with the first slot staying in place while every other slot rotates one spot each round.
+Each round, the team in the first slot plays the team in the last slot, the second slot plays the second to last, and so on, so each team plays every other team once over a cycle.
+If there are an odd number of teams, an empty slot is added to the circle, and whichever team is paired with the empty slot has a bye that round.
+*/
+
+/// Returns each round of a single round robin cycle, as a vector of home and away team id's.
+/// Home series are handed out so that no team has more than 1 more home series than any other team in a cycle.
+fn round_robin_cycle(ids: &[i64]) -> Vec<Vec<(i64, i64)>> {
+    let mut slots: Vec<Option<i64>> = ids.iter().map(|id| Some(*id)).collect();
+    // The empty slot is kept in the fixed position, so the bye rotates through every team.
+    if slots.len() % 2 == 1 {
+        slots.insert(0, None);
+    }
+    let slot_count = slots.len();
     let mut result = vec![];
+    for round in 0..slot_count.saturating_sub(1) {
+        let mut matchups = vec![];
+        for i in 0..slot_count / 2 {
+            let (first, second) = match (slots[i], slots[slot_count - 1 - i]) {
+                (Some(first), Some(second)) => (first, second),
+                // A team paired with the empty slot has a bye.
+                _ => continue,
+            };
+            // The team in the fixed slot alternates between home and away each round, while the other pairings alternate by position.
+            let swap = match i {
+                0 => round % 2 == 1,
+                _ => i % 2 == 1,
+            };
+            match swap {
+                true => matchups.push((second, first)),
+                false => matchups.push((first, second)),
+            }
+        }
+        result.push(matchups);
+        // We rotate every slot but the first.
+        slots[1..].rotate_right(1);
+    }
+    result
+}
 
-    // We loop untill the length of all series is 0
-    while !all_series.is_empty() {
-        // We create a vector for the new round.
-        let mut new_round_vec = vec![];
-        //We create a vector of forbidden team id's that have already been used.
-        let mut forbidden_numbers = HashMap::new();
-
-        //First, we choose a random series from all series
-        let (i, _) = all_series
-            .iter()
-            .enumerate()
-            .choose(&mut thread_rng())
-            .unwrap();
-        // And rempove it from alll series.
-        let first_series = all_series.remove(i);
-        // And add the series home team and away team id to the forbidden numbers.
-        forbidden_numbers.insert(first_series.home_team_id, true);
-        forbidden_numbers.insert(first_series.away_team_id, true);
-        // we then add the series to the round vec
-        new_round_vec.push(first_series);
-
-        //We loops from 1 to matchup_per_round. This previously checked the length of the new round, however this casued bugs that loopingl ike this might fix
-        loop {
-            // If we havce enough series in the round, we brea
-
-            let filtered_series_listing: Vec<SeriesListing> = all_series
+/// Generates a schedule from a slice of team id's. Each team plays every other team series_per_matchup times, and every team plays once per round, unless the league has an odd number of teams, in which case one team has a bye.
+/// Every other cycle flips the home and away team of each series, so with an even number of series per matchup each pair of teams hosts the same number of series.
+pub fn new_schedule_from_ids(
+    ids: &[i64],
+    series_length: i32,
+    series_per_matchup: i32,
+) -> Vec<Round> {
+    let cycle = round_robin_cycle(ids);
+    let mut result = Vec::new();
+    for cycle_number in 0..series_per_matchup {
+        let flip = cycle_number % 2 == 1;
+        for matchups in cycle.iter() {
+            let series = matchups
                 .iter()
-                // We set the iter to enumerate, as we need the index to generate a SeriesListing.
-                .enumerate()
-                //From the clone we map to create a series listing. The listing contains an index, which represents the index of the series in all series.
-                .map(|(index, series)| SeriesListing { series, index })
-                // We then filter for only series listing that series is valid based off the current forbidden numbers
-                .filter(|x| x.series.is_valid(&forbidden_numbers))
-                // And we collect the new vector.
+                .map(|(home, away)| match flip {
+                    true => new_series(*away, *home, series_length),
+                    false => new_series(*home, *away, series_length),
+                })
                 .collect();
-
-            //If there are no valid series listing, we panic.
-            if filtered_series_listing.is_empty() {
-                println!("Round Created with {} series", new_round_vec.len());
-                break;
-            }
-            // We choose a series listing from filtered series listing
-            let (_i, current_series_listing) = filtered_series_listing
-                .iter()
-                .enumerate()
-                .choose(&mut thread_rng())
-                .unwrap();
-
-            // We get the current series from all series based off the current_series_listing_index.
-            let current_series = all_series.remove(current_series_listing.index);
-
-            // We add the series home and away team ids to the forbidden numbers.
-            forbidden_numbers.insert(current_series.home_team_id, true);
-            forbidden_numbers.insert(current_series.away_team_id, true);
-            // And we add the current series to the round
-            new_round_vec.push(current_series);
+            result.push(Round { series });
         }
-        // Once the vector is full, we use it to create a new round.
-        let new_round = Round {
-            series: new_round_vec,
-        };
-        // We then add the round to the result.
-        result.push(new_round);
     }
-
     result
 }
 
 pub fn new_schedule(teams: &[Team], series_length: i32, series_per_matchup: i32) -> Vec<Round> {
     let ids: Vec<i64> = teams.iter().map(|team| team.team_id).collect();
-    let series_per_round = (teams.len() / 2) as i32;
-    let home_series = series_per_matchup / 2;
-    println!("home series = {}", home_series);
-
-    let all_series: Vec<Series> = ids
-        .into_iter()
-        // We create a permutation of home team and away id.
-        .permutations(2)
-        .map(|vec| new_series(vec[0], vec[1], series_length))
-        .collect();
-    for series in all_series.iter() {
-        println!("{} @ {}", series.home_team_id, series.away_team_id)
-    }
-    // println!("home_matchups_created = {}",home_matchups_created );
-    //println!("Total Series Created = {}",all_series.len());
-
-    let mut result: Vec<Round> = Vec::new();
-    for _ in 0..(series_per_matchup / 2) {
-        let series_clone = all_series.clone();
-        let mut rounds = new_round_generator(series_clone, series_per_round);
-
-        rounds.shuffle(&mut thread_rng());
-        result.append(&mut rounds);
-    }
+    let result = new_schedule_from_ids(&ids, series_length, series_per_matchup);
     println!("Total Rounds generated = {}", result.len());
     result
 }
@@ -206,19 +171,20 @@ pub fn schedule_to_sql(
     let league_id = league.league_id;
     conn.execute("INSERT INTO seasons(league_id) VALUES(?1)", [league_id])?;
     let season_id = conn.last_insert_rowid();
+    // A schedule can have thousands of games, so each statement is only prepared once.
+    let mut round_stmt = conn.prepare_cached("INSERT INTO rounds(season_id) VALUES(?1)")?;
+    let mut series_stmt = conn.prepare_cached(
+        "INSERT INTO series(round_id,home_team_id,away_team_id) VALUES(?1, ?2, ?3)",
+    )?;
+    let mut game_stmt = conn.prepare_cached("INSERT INTO games(series_id) VALUES(?1)")?;
     for round in sched {
-        conn.execute("INSERT INTO rounds(season_id) VALUES(?1)", [season_id])?;
-        let round_id = conn.last_insert_rowid();
+        let round_id = round_stmt.insert([season_id])?;
         for series in round.series {
             let home_id = series.home_team_id;
             let away_id = series.away_team_id;
-            conn.execute(
-                "INSERT INTO series(round_id,home_team_id,away_team_id) VALUES(?1, ?2, ?3)",
-                [round_id, home_id, away_id],
-            )?;
-            let series_id = conn.last_insert_rowid();
+            let series_id = series_stmt.insert([round_id, home_id, away_id])?;
             for _game in series.games {
-                conn.execute("INSERT INTO games(series_id) VALUES(?1)", [series_id])?;
+                game_stmt.execute([series_id])?;
             }
         }
     }
@@ -232,9 +198,10 @@ pub fn save_schedule_sql(conn: &mut Connection, league: &League) -> Result<(), E
         Ok(rounds) => rounds,
         Err(message) => return inquire_check(message),
     };
-    if let Err(message) = schedule_to_sql(conn, league, sched) {
-        return Err(EditLeagueError::DatabaseError(message));
-    }
+    // The schedule is saved in a single transaction, so a season is never saved with only part of its games.
+    in_transaction(conn, |conn| {
+        handle_sql_error(schedule_to_sql(conn, league, sched))
+    })?;
     save_league(league);
     Ok(())
 }
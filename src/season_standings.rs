@@ -0,0 +1,238 @@
+use core::fmt;
+use std::fs::File;
+use std::io::Write;
+
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::game_result::get_played_season_games;
+use crate::game_result::GameRecord;
+use crate::injury::injury_report;
+use crate::league::League;
+use crate::sched_view::select_season;
+use crate::settings::output_path;
+use rusqlite::Connection;
+
+/// How many games are counted when showing a team's recent record.
+const RECENT_GAMES: usize = 10;
+
+/// A team's record for a season, built from the results in the games table.
+#[derive(Debug, Clone)]
+pub struct TeamStanding {
+    pub team_id: i64,
+    pub name: String,
+    pub wins: i32,
+    pub losses: i32,
+    pub home_wins: i32,
+    pub home_losses: i32,
+    pub away_wins: i32,
+    pub away_losses: i32,
+    pub runs_scored: i32,
+    pub runs_allowed: i32,
+    /// The result of each game the team played in order, with true marking a win.
+    results: Vec<bool>,
+}
+
+impl TeamStanding {
+    fn new(team_id: i64, name: String) -> TeamStanding {
+        TeamStanding {
+            team_id,
+            name,
+            wins: 0,
+            losses: 0,
+            home_wins: 0,
+            home_losses: 0,
+            away_wins: 0,
+            away_losses: 0,
+            runs_scored: 0,
+            runs_allowed: 0,
+            results: Vec::new(),
+        }
+    }
+
+    /// Adds the result of a game to the standing.
+    fn add_game(&mut self, runs_scored: i32, runs_allowed: i32, is_home: bool) {
+        let won = runs_scored > runs_allowed;
+        match (won, is_home) {
+            (true, true) => self.home_wins += 1,
+            (false, true) => self.home_losses += 1,
+            (true, false) => self.away_wins += 1,
+            (false, false) => self.away_losses += 1,
+        };
+        match won {
+            true => self.wins += 1,
+            false => self.losses += 1,
+        };
+        self.runs_scored += runs_scored;
+        self.runs_allowed += runs_allowed;
+        self.results.push(won);
+    }
+
+    /// Returns the percentage of games the team has won. A team that has not played is treated as .000.
+    pub fn win_pct(&self) -> f32 {
+        let games = self.wins + self.losses;
+        match games {
+            0 => 0.0,
+            _ => self.wins as f32 / games as f32,
+        }
+    }
+
+    pub fn run_differential(&self) -> i32 {
+        self.runs_scored - self.runs_allowed
+    }
+
+    /// Returns how many games the team is behind another team.
+    pub fn games_behind(&self, leader: &TeamStanding) -> f32 {
+        ((leader.wins - self.wins) + (self.losses - leader.losses)) as f32 / 2.0
+    }
+
+    /// Returns a tuple of the wins and losses in the team's most recent games.
+    pub fn recent_record(&self) -> (i32, i32) {
+        let start = self.results.len().saturating_sub(RECENT_GAMES);
+        let wins = self.results[start..].iter().filter(|won| **won).count() as i32;
+        (wins, (self.results.len() - start) as i32 - wins)
+    }
+
+    /// Returns the team's current winning or losing streak, E.G W3 or L1.
+    pub fn streak(&self) -> String {
+        let last = match self.results.last() {
+            Some(won) => *won,
+            None => return "-".to_string(),
+        };
+        let length = self
+            .results
+            .iter()
+            .rev()
+            .take_while(|won| **won == last)
+            .count();
+        match last {
+            true => format!("W{}", length),
+            false => format!("L{}", length),
+        }
+    }
+}
+
+/// The standings of every team in a league for a season.
+pub struct SeasonStandings {
+    pub season_id: i64,
+    pub standings: Vec<TeamStanding>,
+}
+
+/// Takes a tuple of each team's id and name, as well as the games that have been played, and creates the standings.
+/// Teams are ranked by win percentage, with run differential breaking ties.
+pub fn compute_standings(teams: &[(i64, String)], games: &[GameRecord]) -> Vec<TeamStanding> {
+    let mut standings: Vec<TeamStanding> = teams
+        .iter()
+        .map(|(team_id, name)| TeamStanding::new(*team_id, name.to_string()))
+        .collect();
+    for game in games.iter().filter(|game| game.is_played()) {
+        for standing in standings.iter_mut() {
+            if standing.team_id == game.home_team_id {
+                standing.add_game(game.home_score, game.away_score, true);
+            } else if standing.team_id == game.away_team_id {
+                standing.add_game(game.away_score, game.home_score, false);
+            }
+        }
+    }
+    standings.sort_by(|a, b| {
+        b.win_pct()
+            .total_cmp(&a.win_pct())
+            .then(b.run_differential().cmp(&a.run_differential()))
+    });
+    standings
+}
+
+impl fmt::Display for SeasonStandings {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Season {} Standings", self.season_id)?;
+        writeln!(
+            f,
+            "{:<25} {:>4} {:>4} {:>6} {:>5} {:>7} {:>7} {:>7} {:>6} {:>5}",
+            "Team", "W", "L", "PCT", "GB", "HOME", "AWAY", "L10", "STRK", "DIFF"
+        )?;
+        let leader = match self.standings.first() {
+            Some(standing) => standing,
+            None => return Ok(()),
+        };
+        for standing in self.standings.iter() {
+            let games_behind = match standing.games_behind(leader) {
+                gb if gb <= 0.0 => "-".to_string(),
+                gb => format!("{:.1}", gb),
+            };
+            let (recent_wins, recent_losses) = standing.recent_record();
+            writeln!(
+                f,
+                "{:<25} {:>4} {:>4} {:>6.3} {:>5} {:>7} {:>7} {:>7} {:>6} {:>+5}",
+                standing.name,
+                standing.wins,
+                standing.losses,
+                standing.win_pct(),
+                games_behind,
+                format!("{}-{}", standing.home_wins, standing.home_losses),
+                format!("{}-{}", standing.away_wins, standing.away_losses),
+                format!("{}-{}", recent_wins, recent_losses),
+                standing.streak(),
+                standing.run_differential()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns a tuple of the id and name of each team in a league.
+fn get_league_teams(
+    conn: &Connection,
+    league_id: i64,
+) -> Result<Vec<(i64, String)>, rusqlite::Error> {
+    let mut stmt = conn
+        .prepare("SELECT teams.team_id, teams.team_name FROM teams WHERE teams.league_id = ?1")?;
+    let team_iter = stmt.query_map([league_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+    let mut result_vec = Vec::new();
+    for team in team_iter {
+        result_vec.push(team?)
+    }
+    Ok(result_vec)
+}
+
+/// Builds the standings for a season from the results saved in the database.
+pub fn load_season_standings(
+    conn: &Connection,
+    league_id: i64,
+    season_id: i64,
+) -> Result<SeasonStandings, rusqlite::Error> {
+    let teams = get_league_teams(conn, league_id)?;
+    let games = get_played_season_games(conn, season_id)?;
+    Ok(SeasonStandings {
+        season_id,
+        standings: compute_standings(&teams, &games),
+    })
+}
+
+/// Saves the standings as a text file next to the league's folder, followed by the league's injury report.
+fn save_standings_to_file(league: &League, standings: &SeasonStandings) -> std::io::Result<()> {
+    let file_name = output_path(&format!(
+        "{}_Season_{}_Standings.txt",
+        league.name, standings.season_id
+    ));
+    let mut file = File::create(&file_name)?;
+    let report = format!("{}\n{}", standings, injury_report(league));
+    file.write_all(report.as_bytes())?;
+    println!("Standings saved to {}", file_name.display());
+    Ok(())
+}
+
+/// Prompts the user to choose a season, and then displays the standings for that season and saves them to a file.
+pub fn view_season_standings(league: &League, conn: &Connection) -> Result<(), EditLeagueError> {
+    let season_id = match select_season(league, conn)? {
+        Some(num) => num,
+        None => return Ok(()),
+    };
+    let standings = handle_sql_error(load_season_standings(conn, league.league_id, season_id))?;
+    println!("{}\n{}", standings, injury_report(league));
+    if let Err(message) = save_standings_to_file(league, &standings) {
+        println!(
+            "Unable to save the standings to a file.\nThe error was {}",
+            message
+        );
+    }
+    Ok(())
+}
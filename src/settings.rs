@@ -0,0 +1,292 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::RwLock;
+
+use crate::edit_league_error::handle_inquire_error;
+use crate::edit_league_error::handle_serde_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::inquire_check;
+use crate::load_database;
+use crate::Deserialize;
+use crate::Serialize;
+use core::fmt;
+use inquire::validator::MinLengthValidator;
+use inquire::Select;
+use inquire::Text;
+use rusqlite::Connection;
+
+/* By default, the program saves the database as deadball.db and writes league folders and files to the folder it is run in.
+Both can be changed, with the following order deciding which setting is used:
+    1. A command line flag, --db PATH or --output DIR.
+    2. An environment variable, DEADBALL_DB or DEADBALL_OUTPUT.
+    3. The active profile in the config file.
+    4. The default.
+The config file is deadball_config.json, unless the DEADBALL_CONFIG environment variable points to a different file.
+Profiles let users keep a separate database for each tabletop group, and switch between them from the main menu.*/
+
+const DEFAULT_DATABASE: &str = "deadball.db";
+const DEFAULT_OUTPUT_DIR: &str = ".";
+const DEFAULT_CONFIG: &str = "deadball_config.json";
+
+/// The folder that league folders and files are written to.
+static OUTPUT_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// The path of the database the program is using.
+static DATABASE_PATH: RwLock<Option<String>> = RwLock::new(None);
+
+/// A named database, along with the folder that the files for it's leagues are written to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Profile {
+    pub database: String,
+    #[serde(default = "default_output_dir")]
+    pub output_dir: String,
+}
+
+fn default_output_dir() -> String {
+    DEFAULT_OUTPUT_DIR.to_string()
+}
+
+/// The contents of the config file.
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct Config {
+    #[serde(default)]
+    pub active_profile: Option<String>,
+    #[serde(default)]
+    pub profiles: BTreeMap<String, Profile>,
+}
+
+/// Settings passed to the program via command line flags or environment variables.
+#[derive(Debug, Default)]
+pub struct GlobalOptions {
+    pub database: Option<String>,
+    pub output_dir: Option<String>,
+    pub profile: Option<String>,
+}
+
+/// The settings the program runs with.
+#[derive(Debug, PartialEq)]
+pub struct Settings {
+    pub profile_name: Option<String>,
+    pub database: String,
+    pub output_dir: String,
+}
+
+/// Removes the --db, --output and --profile flags from the arguments passed to the program, and combines them with the environment variables.
+/// Returns the options, and the remaining arguments, or an error if a flag is missing its value.
+pub fn take_global_options(
+    args: Vec<String>,
+) -> Result<(GlobalOptions, Vec<String>), EditLeagueError> {
+    let mut options = GlobalOptions {
+        database: env::var("DEADBALL_DB").ok(),
+        output_dir: env::var("DEADBALL_OUTPUT").ok(),
+        profile: env::var("DEADBALL_PROFILE").ok(),
+    };
+    let mut remaining = Vec::new();
+    let mut iter = args.into_iter();
+    while let Some(arg) = iter.next() {
+        let slot = match arg.as_str() {
+            "--db" => &mut options.database,
+            "--output" => &mut options.output_dir,
+            "--profile" => &mut options.profile,
+            _ => {
+                remaining.push(arg);
+                continue;
+            }
+        };
+        // A flag passed on the command line takes priority over the environment variable.
+        match iter.next() {
+            Some(value) if !value.starts_with("--") => *slot = Some(value),
+            _ => {
+                return Err(EditLeagueError::CommandError(format!(
+                    "{} must be followed by a value.",
+                    arg
+                )))
+            }
+        }
+    }
+    Ok((options, remaining))
+}
+
+fn config_path() -> PathBuf {
+    match env::var("DEADBALL_CONFIG") {
+        Ok(path) => PathBuf::from(path),
+        Err(_) => PathBuf::from(DEFAULT_CONFIG),
+    }
+}
+
+/// Loads the config file. If the file does not exist, an empty config is returned.
+pub fn load_config() -> Result<Config, EditLeagueError> {
+    match fs::read_to_string(config_path()) {
+        Ok(contents) => handle_serde_error(serde_json::from_str(&contents)),
+        Err(_) => Ok(Config::default()),
+    }
+}
+
+fn save_config(config: &Config) -> Result<(), EditLeagueError> {
+    let contents = handle_serde_error(serde_json::to_string_pretty(config))?;
+    if let Err(message) = fs::write(config_path(), contents) {
+        println!("Unable to save the config file.\nThe error was {}", message);
+    }
+    Ok(())
+}
+
+/// Decides which settings to use based off the options and config file.
+/// Returns an error if the chosen profile is not in the config file.
+pub fn resolve_settings(
+    options: &GlobalOptions,
+    config: &Config,
+) -> Result<Settings, EditLeagueError> {
+    let profile_name = options
+        .profile
+        .clone()
+        .or_else(|| config.active_profile.clone());
+    let profile = match &profile_name {
+        Some(name) => match config.profiles.get(name) {
+            Some(profile) => Some(profile),
+            None => {
+                let names: Vec<&str> = config.profiles.keys().map(|x| x.as_str()).collect();
+                let existing = match names.is_empty() {
+                    true => "No profiles have been created yet.".to_string(),
+                    false => format!("The profiles are: {}.", names.join(", ")),
+                };
+                return Err(EditLeagueError::CommandError(format!(
+                    "There is no profile named {}. {}",
+                    name, existing
+                )));
+            }
+        },
+        None => None,
+    };
+    let database = options
+        .database
+        .clone()
+        .or_else(|| profile.map(|profile| profile.database.clone()))
+        .unwrap_or_else(|| DEFAULT_DATABASE.to_string());
+    let output_dir = options
+        .output_dir
+        .clone()
+        .or_else(|| profile.map(|profile| profile.output_dir.clone()))
+        .unwrap_or_else(default_output_dir);
+    Ok(Settings {
+        profile_name,
+        database,
+        output_dir,
+    })
+}
+
+/// Sets the folder that league folders and files are written to, creating it if it does not exist.
+pub fn set_output_dir(dir: &str) {
+    let path = PathBuf::from(dir);
+    if let Err(message) = fs::create_dir_all(&path) {
+        println!(
+            "Unable to create the output folder {}.\nThe error was {}",
+            dir, message
+        );
+    }
+    if let Ok(mut output_dir) = OUTPUT_DIR.write() {
+        *output_dir = Some(path);
+    }
+}
+
+/// Saves the path of the database the program is using, so that error messages name the right file after switching profiles.
+pub fn set_database_path(path: &str) {
+    if let Ok(mut database_path) = DATABASE_PATH.write() {
+        *database_path = Some(path.to_string());
+    }
+}
+
+/// Returns the path of the database the program is using.
+pub fn database_path() -> String {
+    let path = match DATABASE_PATH.read() {
+        Ok(database_path) => database_path.clone(),
+        Err(_) => None,
+    };
+    path.unwrap_or_else(|| DEFAULT_DATABASE.to_string())
+}
+
+/// Returns the path a file or folder should be written to inside the output folder.
+pub fn output_path(name: &str) -> PathBuf {
+    let dir = match OUTPUT_DIR.read() {
+        Ok(output_dir) => output_dir.clone(),
+        Err(_) => None,
+    };
+    dir.unwrap_or_else(|| PathBuf::from(DEFAULT_OUTPUT_DIR))
+        .join(name)
+}
+
+/// The choices a user has when switching databases.
+enum ProfileChoice {
+    Existing(String, Profile),
+    New,
+}
+
+impl fmt::Display for ProfileChoice {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Existing(name, profile) => write!(
+                f,
+                "{} ({}, files saved to {})",
+                name, profile.database, profile.output_dir
+            ),
+            Self::New => write!(f, "Create a new profile."),
+        }
+    }
+}
+
+/// Prompts the user to create a new profile.
+fn new_profile_from_input() -> Result<(String, Profile), EditLeagueError> {
+    let name = handle_inquire_error(
+        Text::new("Please enter a name for the profile.")
+            .with_validator(MinLengthValidator::new(1))
+            .prompt(),
+    )?;
+    let default_database = format!("{}.db", name);
+    let database = handle_inquire_error(
+        Text::new("Please enter the path of the database for the profile.")
+            .with_default(&default_database)
+            .prompt(),
+    )?;
+    let output_dir = handle_inquire_error(
+        Text::new("Please enter the folder that league files should be saved to.")
+            .with_default(&name)
+            .prompt(),
+    )?;
+    Ok((
+        name,
+        Profile {
+            database,
+            output_dir,
+        },
+    ))
+}
+
+/// Prompts the user to choose a profile or create a new one, and then switches the connection to that profile's database.
+/// The choice is saved as the active profile in the config file.
+pub fn switch_profile(conn: &mut Connection) -> Result<(), EditLeagueError> {
+    let mut config = load_config()?;
+    let mut options: Vec<ProfileChoice> = config
+        .profiles
+        .iter()
+        .map(|(name, profile)| ProfileChoice::Existing(name.to_string(), profile.clone()))
+        .collect();
+    options.push(ProfileChoice::New);
+    let (name, profile) =
+        match Select::new("Which database would you like to use?", options).prompt() {
+            Ok(ProfileChoice::Existing(name, profile)) => (name, profile),
+            Ok(ProfileChoice::New) => match new_profile_from_input() {
+                Ok(value) => value,
+                Err(EditLeagueError::Inquire(message)) => return inquire_check(message),
+                Err(message) => return Err(message),
+            },
+            Err(message) => return inquire_check(message),
+        };
+    *conn = load_database(&profile.database)?;
+    set_database_path(&profile.database);
+    set_output_dir(&profile.output_dir);
+    println!("Now using {}.", profile.database);
+    config.profiles.insert(name.to_string(), profile);
+    config.active_profile = Some(name);
+    save_config(&config)
+}
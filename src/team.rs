@@ -2,6 +2,9 @@ use crate::b_traits::BTraits;
 use crate::edit_league_error::handle_serde_error;
 use crate::edit_league_error::handle_sql_error;
 use crate::edit_league_error::EditLeagueError;
+use crate::injury::injured_list_string;
+use crate::injury::load_injuries;
+use crate::injury::Injury;
 use crate::inquire_check;
 use crate::note::Notable;
 use crate::note::Note;
@@ -35,14 +38,14 @@ use crate::Player;
 use crate::PlayerGender;
 use crate::PlayerQuality;
 use crate::Serialize;
-use crate::ThreadRng;
 use core::fmt;
+use rand::Rng;
 
 use std::fmt::Write;
 
 /* A teams consists of a name, a vector for the starting lineup, bench, pitching rotation, and an option for the bullpen.
 Team's also have a team score, which is used in Deadball to simulate a game with only a few dice rolls.' */
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Team {
     pub abrv: String,
     pub name: String,
@@ -51,20 +54,26 @@ pub struct Team {
     pub starting_pitching: Vec<Player>,
     pub bullpen: Option<Vec<Player>>,
 
+    #[serde(default)]
     pub team_score: i32,
+    #[serde(default)]
     pub wins: i32,
+    #[serde(default)]
     pub losses: i32,
+    #[serde(default)]
     pub team_id: i64,
     pub note: Note,
+    #[serde(default)]
+    pub injured_list: Vec<Injury>,
 }
 
 impl Team {
-    pub fn new(
+    pub fn new<R: Rng>(
         abrv: &String,
         name: &String,
         gender: PlayerGender,
         era: Era,
-        thread: &mut ThreadRng,
+        thread: &mut R,
     ) -> Team {
         let mut new_team = Team {
             team_id: 0,
@@ -78,6 +87,7 @@ impl Team {
             wins: 0,
             losses: 0,
             note: None,
+            injured_list: Vec::new(),
         };
 
         new_team.calc_team_score();
@@ -150,6 +160,10 @@ impl fmt::Display for Team {
             ),
             None => non_bullpen_string,
         };
+        let chars = match injured_list_string(self) {
+            Some(injured_list) => format!("{}{}\n", chars, injured_list),
+            None => chars,
+        };
 
         write!(f, "{}", chars)
     }
@@ -340,14 +354,15 @@ pub fn load_team(conn: &mut Connection, mut team: Team, era: Era) -> Result<Team
         }
     }
     team.calc_team_score();
+    team.injured_list = load_injuries(conn, team.team_id)?;
     Ok(team)
 }
 
 // This function is used to create vectors of players, based off a vector of strings.
-fn new_player_vec<T: Copy + PlayerQuality>(
+fn new_player_vec<T: Copy + PlayerQuality, R: Rng>(
     vec: Vec<&str>,
     gender: PlayerGender,
-    thread: &mut ThreadRng,
+    thread: &mut R,
     quality: T,
     era: Era,
 ) -> Vec<Player> {
@@ -358,13 +373,13 @@ fn new_player_vec<T: Copy + PlayerQuality>(
 
 // A starting lineup consists of 8 players, one for each position on the field
 
-fn new_starting_lineup(gender: PlayerGender, thread: &mut ThreadRng, era: Era) -> Vec<Player> {
+fn new_starting_lineup<R: Rng>(gender: PlayerGender, thread: &mut R, era: Era) -> Vec<Player> {
     let base = vec!["C", "1B", "2B", "3B", "SS", "LF", "CF", "RF"];
     new_player_vec(base, gender, thread, BatterQuality::TopProspect, era)
 }
 
 // The bench consist of all non starters. The Ancient and Modern era's have different quantities and positions on the bench, so we use the Era enum to keep track.
-fn new_bench(gender: PlayerGender, thread: &mut ThreadRng, era: Era) -> Vec<Player> {
+fn new_bench<R: Rng>(gender: PlayerGender, thread: &mut R, era: Era) -> Vec<Player> {
     let base = match era {
         Era::Ancient => vec!["C", "INF", "OF", "UT"],
         Era::Modern => vec!["C", "INF", "INF", "OF", "OF"],
@@ -375,7 +390,7 @@ fn new_bench(gender: PlayerGender, thread: &mut ThreadRng, era: Era) -> Vec<Play
 
 // The ancient and modern era have different definitions of what a pitcher is. Modern era pitchers can be either starters or relievers, while the ancient era does not make the distinction.
 //Thus, Modern pitchers in the rotation are marked as SP, while ancient are just P
-fn new_rotation(gender: PlayerGender, thread: &mut ThreadRng, era: Era) -> Vec<Player> {
+fn new_rotation<R: Rng>(gender: PlayerGender, thread: &mut R, era: Era) -> Vec<Player> {
     let base = match era {
         Era::Ancient => vec!["P", "P", "P", "P", "P"],
         Era::Modern => vec!["SP", "SP", "SP", "SP", "SP"],
@@ -384,7 +399,7 @@ fn new_rotation(gender: PlayerGender, thread: &mut ThreadRng, era: Era) -> Vec<P
     new_player_vec(base, gender, thread, PitcherQuality::TopProspect, era)
 }
 // Ancient Era teams do not have a bullpen, so a bullpen is wrapped in an option.
-fn new_bullpen(gender: PlayerGender, thread: &mut ThreadRng, era: Era) -> Option<Vec<Player>> {
+fn new_bullpen<R: Rng>(gender: PlayerGender, thread: &mut R, era: Era) -> Option<Vec<Player>> {
     match era {
         Era::Ancient => None,
         Era::Modern => {
@@ -412,7 +427,7 @@ fn get_pitcher_info_string(desc: String, vec: &[Player]) -> String {
 }
 
 // Declare if a player is in hte starting lineup, on the bench, in the starting rotation, or in the bullpen.
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TeamSpot {
     StartingLineup,
     BenchHitter,
@@ -436,7 +451,6 @@ impl fmt::Display for TeamSpot {
 pub fn add_team_check(
     league: &mut League,
     conn: &mut Connection,
-    thread: &mut ThreadRng,
     league_id: i64,
 ) -> Result<(), EditLeagueError> {
     let ans = Confirm::new("Would you like to create another team?")
@@ -445,7 +459,7 @@ pub fn add_team_check(
 
     match ans {
         // If the user selects true, the user adds another team, however we note that this is not the first team created for the league.
-        Ok(true) => add_new_team(league, thread, conn, league_id, false)?,
+        Ok(true) => add_new_team(league, conn, league_id, false)?,
         //If not, we save the league.
         Ok(false) => {
             save_league(league);
@@ -458,7 +472,6 @@ pub fn add_team_check(
 // Prompts the user to create a new team, while also ensure that the user does not use the same name or abbreviation for a team in the same league more than once.
 pub fn add_new_team(
     league: &mut League,
-    thread: &mut ThreadRng,
     conn: &mut Connection,
     league_id: i64,
     first_team: bool,
@@ -508,7 +521,9 @@ pub fn add_new_team(
             There is also a check to see if there is an error adding the team to the database, and returns an error if it does.
             Otherwise, the function will return OK.
         */
-        match league.new_team(&abrv, &team_name, thread, league_id, conn) {
+        // Each team is generated with it's own random number generator, so a seeded league always creates the same teams.
+        let mut team_thread = league.team_rng();
+        match league.new_team(&abrv, &team_name, &mut team_thread, league_id, conn) {
             Err(message) => {
                 match message {
                     EditLeagueError::AbrvTaken => println!(
@@ -524,7 +539,7 @@ pub fn add_new_team(
             }
             // If the league returns OK, we ask the user if they would like to create a new team.
             Ok(()) => {
-                result = match add_team_check(league, conn, thread, league_id) {
+                result = match add_team_check(league, conn, league_id) {
                     Ok(()) => Ok(()),
                     Err(message) => Err(message),
                 };
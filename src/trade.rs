@@ -0,0 +1,495 @@
+use core::fmt;
+
+use crate::db_audit::check_team_positions;
+use crate::db_audit::LINEUP_POSITIONS;
+use crate::edit_league_error::handle_inquire_error;
+use crate::edit_league_error::handle_serde_error;
+use crate::edit_league_error::handle_sql_error;
+use crate::edit_league_error::EditLeagueError;
+use crate::era::Era;
+use crate::in_transaction;
+use crate::inquire_check;
+use crate::league::save_league;
+use crate::league::League;
+use crate::minor_leaguer::push_to_roster;
+use crate::player::Player;
+use crate::position::PlayerPosition;
+use crate::team::Team;
+use crate::team::TeamSpot;
+use chrono::{Datelike, Local};
+use inquire::{Confirm, MultiSelect, Select};
+use rusqlite::Connection;
+
+/* Players can be traded between two teams in the same league.
+Traded players fill the same role on their new team when possible. Batters start if their new team's lineup is missing their position, otherwise they join the bench,
+and pitchers join the rotation if it is short a starter, otherwise they join the bullpen, or the rotation for Ancient Era teams.
+Before a trade is made, both teams are checked to make sure they still have the roster their era requires, and each trade is saved in the trades and trade_players tables.*/
+
+/// The size of a starting rotation.
+const ROTATION_SIZE: usize = 5;
+
+/// Returns the number of bench players, starting pitchers and relievers a team in an era must have.
+pub fn roster_sizes(era: Era) -> (usize, usize, usize) {
+    match era {
+        Era::Ancient => (4, ROTATION_SIZE, 0),
+        Era::Modern => (5, ROTATION_SIZE, 7),
+    }
+}
+
+/// Returns every player on a team, along with their team spot.
+pub fn roster_with_spots(team: &Team) -> Vec<(TeamSpot, &Player)> {
+    let pools = [
+        (TeamSpot::StartingLineup, &team.lineup),
+        (TeamSpot::BenchHitter, &team.bench),
+        (TeamSpot::StartingPitcher, &team.starting_pitching),
+    ];
+    let bullpen = team.bullpen.iter().map(|pen| (TeamSpot::Bullpen, pen));
+    pools
+        .into_iter()
+        .chain(bullpen)
+        .flat_map(|(spot, pool)| pool.iter().map(move |player| (spot, player)))
+        .collect()
+}
+
+/// Returns a description of each way a team's roster does not match the shape required by it's era.
+/// This is shared by trades, the roster editor and the farm system, so every roster change follows the same rule.
+pub fn roster_problems(team: &Team, era: Era) -> Vec<String> {
+    let mut problems = Vec::new();
+    let spots: Vec<(TeamSpot, PlayerPosition)> = roster_with_spots(team)
+        .into_iter()
+        .map(|(spot, player)| (spot, player.pos.clone()))
+        .collect();
+    if let Some(missing) = check_team_positions(&team.name, &spots) {
+        problems.push(missing);
+    }
+    let (bench, rotation, bullpen) = roster_sizes(era);
+    let bullpen_len = team.bullpen.as_ref().map_or(0, |pen| pen.len());
+    let counts = [
+        (team.lineup.len(), LINEUP_POSITIONS.len(), "starters"),
+        (team.bench.len(), bench, "bench players"),
+        (team.starting_pitching.len(), rotation, "starting pitchers"),
+        (bullpen_len, bullpen, "relievers"),
+    ];
+    for (actual, required, description) in counts {
+        if actual != required {
+            problems.push(format!(
+                "{} has {} {}, but must have {}",
+                team.name, actual, description, required
+            ));
+        }
+    }
+    problems
+}
+
+/// Returns the position a batter plays in the field.
+pub fn fielding_pos(player: &Player) -> &PlayerPosition {
+    match &player.pos {
+        PlayerPosition::TwoWay(info) => info.get_fielder_type(),
+        pos => pos,
+    }
+}
+
+/// Returns the team spot a player joins on their new team, based off the spot they had on their old team.
+pub fn incoming_spot(team: &Team, player: &Player, old_spot: TeamSpot, era: Era) -> TeamSpot {
+    match old_spot {
+        TeamSpot::StartingLineup | TeamSpot::BenchHitter => {
+            let pos = fielding_pos(player);
+            let needed = LINEUP_POSITIONS.contains(pos)
+                && !team
+                    .lineup
+                    .iter()
+                    .any(|starter| fielding_pos(starter) == pos);
+            match needed {
+                true => TeamSpot::StartingLineup,
+                false => TeamSpot::BenchHitter,
+            }
+        }
+        TeamSpot::StartingPitcher | TeamSpot::Bullpen => {
+            match (team.starting_pitching.len() < ROTATION_SIZE, era) {
+                (true, _) | (_, Era::Ancient) => TeamSpot::StartingPitcher,
+                (false, Era::Modern) => TeamSpot::Bullpen,
+            }
+        }
+    }
+}
+
+/// Removes a player from a team, and returns the player along with the spot they had.
+pub fn take_player(team: &mut Team, player_id: i64) -> Option<(Player, TeamSpot)> {
+    let pools = [
+        (TeamSpot::StartingLineup, &mut team.lineup),
+        (TeamSpot::BenchHitter, &mut team.bench),
+        (TeamSpot::StartingPitcher, &mut team.starting_pitching),
+    ];
+    let bullpen = team.bullpen.iter_mut().map(|pen| (TeamSpot::Bullpen, pen));
+    for (spot, pool) in pools.into_iter().chain(bullpen) {
+        if let Some(index) = pool.iter().position(|x| x.player_id == player_id) {
+            return Some((pool.remove(index), spot));
+        }
+    }
+    None
+}
+
+/// A player that moves to a new team in a trade.
+pub struct TradedPlayer {
+    pub player_id: i64,
+    pub name: String,
+    pub pos: String,
+    pub trade_value: i32,
+    pub from_team_id: i64,
+    pub to_team_id: i64,
+    pub old_spot: TeamSpot,
+    pub new_spot: TeamSpot,
+}
+
+/// A proposed trade between two teams in a league, along with the rosters both teams would have afterwards.
+pub struct Trade {
+    team_a: usize,
+    team_b: usize,
+    new_a: Team,
+    new_b: Team,
+    pub moves: Vec<TradedPlayer>,
+    pub problems: Vec<String>,
+}
+
+impl Trade {
+    /// Returns the total trade value of the players a team gives up in the trade.
+    pub fn value_given(&self, team_id: i64) -> i32 {
+        self.moves
+            .iter()
+            .filter(|x| x.from_team_id == team_id)
+            .map(|x| x.trade_value)
+            .sum()
+    }
+
+    /// Returns an error listing every roster problem the trade would cause.
+    pub fn check(&self) -> Result<(), EditLeagueError> {
+        match self.problems.is_empty() {
+            true => Ok(()),
+            false => Err(EditLeagueError::RosterError(format!(
+                "The trade can not be made.\n{}",
+                self.problems.join("\n")
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Trade {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (team, other) in [(&self.new_a, &self.new_b), (&self.new_b, &self.new_a)] {
+            writeln!(f, "{} receive:", team.name)?;
+            for traded in self.moves.iter().filter(|x| x.to_team_id == team.team_id) {
+                writeln!(
+                    f,
+                    "    {} {}, Trade Value {}, {} -> {}",
+                    traded.name, traded.pos, traded.trade_value, traded.old_spot, traded.new_spot
+                )?;
+            }
+            writeln!(
+                f,
+                "    Total Trade Value: {}",
+                self.value_given(other.team_id)
+            )?;
+        }
+        let balance = self.value_given(self.new_b.team_id) - self.value_given(self.new_a.team_id);
+        match balance {
+            0 => writeln!(f, "The trade is even."),
+            _ => {
+                let winner = match balance > 0 {
+                    true => &self.new_a.name,
+                    false => &self.new_b.name,
+                };
+                writeln!(f, "{} gain {} in trade value.", winner, balance.abs())
+            }
+        }
+    }
+}
+
+/// Builds a trade where team_a sends the players in a_ids to team_b, and team_b sends the players in b_ids to team_a.
+/// Any roster problems the trade would cause are saved in the trade's problems.
+pub fn propose_trade(
+    league: &League,
+    team_a: usize,
+    team_b: usize,
+    a_ids: &[i64],
+    b_ids: &[i64],
+) -> Result<Trade, EditLeagueError> {
+    if team_a == team_b {
+        return Err(EditLeagueError::RosterError(
+            "A team can not make a trade with itself.".to_string(),
+        ));
+    }
+    if a_ids.is_empty() && b_ids.is_empty() {
+        return Err(EditLeagueError::RosterError(
+            "A trade must include at least one player.".to_string(),
+        ));
+    }
+    let mut new_a = league.teams[team_a].clone();
+    let mut new_b = league.teams[team_b].clone();
+    let mut outgoing = Vec::new();
+    for (team, ids) in [(&mut new_a, a_ids), (&mut new_b, b_ids)] {
+        let mut players = Vec::new();
+        for player_id in ids.iter() {
+            match take_player(team, *player_id) {
+                Some(taken) => players.push(taken),
+                None => {
+                    return Err(EditLeagueError::RosterError(format!(
+                        "There is no player with the id {} on {}.",
+                        player_id, team.name
+                    )))
+                }
+            }
+        }
+        outgoing.push(players);
+    }
+    let b_outgoing = outgoing.pop().unwrap_or_default();
+    let a_outgoing = outgoing.pop().unwrap_or_default();
+    let mut moves = Vec::new();
+    let (a_id, b_id) = (new_a.team_id, new_b.team_id);
+    for (players, from_team_id, to) in [
+        (a_outgoing, a_id, &mut new_b),
+        (b_outgoing, b_id, &mut new_a),
+    ] {
+        for (mut player, old_spot) in players {
+            let new_spot = incoming_spot(to, &player, old_spot, league.era);
+            moves.push(TradedPlayer {
+                player_id: player.player_id,
+                name: player.name.clone(),
+                pos: player.pos.to_string(),
+                trade_value: player.trade_value,
+                from_team_id,
+                to_team_id: to.team_id,
+                old_spot,
+                new_spot,
+            });
+            player.team_id = to.team_id;
+            push_to_roster(to, player, new_spot);
+        }
+    }
+    // Injured players stay injured with their new team.
+    for traded in moves.iter() {
+        let (from, to) = match traded.from_team_id == a_id {
+            true => (&mut new_a, &mut new_b),
+            false => (&mut new_b, &mut new_a),
+        };
+        let injured = from
+            .injured_list
+            .iter()
+            .position(|x| x.player_id == traded.player_id);
+        if let Some(index) = injured {
+            to.injured_list.push(from.injured_list.remove(index));
+        }
+    }
+    new_a.calc_team_score();
+    new_b.calc_team_score();
+    let mut problems = roster_problems(&new_a, league.era);
+    problems.extend(roster_problems(&new_b, league.era));
+    Ok(Trade {
+        team_a,
+        team_b,
+        new_a,
+        new_b,
+        moves,
+        problems,
+    })
+}
+
+/// Makes a trade, moving each player to their new team in the database and saving the trade to the trade log.
+/// A trade that would leave either team with an invalid roster is not made.
+pub fn execute_trade(
+    conn: &mut Connection,
+    league: &mut League,
+    trade: Trade,
+) -> Result<(), EditLeagueError> {
+    trade.check()?;
+    let team_a_value = trade.value_given(trade.new_a.team_id);
+    let team_b_value = trade.value_given(trade.new_b.team_id);
+    in_transaction(conn, |conn| {
+        let now = Local::now();
+        let date_made = format!("{}_{}_{}", now.year(), now.month(), now.day());
+        handle_sql_error(conn.execute(
+            "INSERT INTO trades(league_id, date_made, team_a_id, team_b_id, team_a_value, team_b_value) VALUES(?1, ?2, ?3, ?4, ?5, ?6)",
+            (
+                league.league_id,
+                date_made,
+                trade.new_a.team_id,
+                trade.new_b.team_id,
+                team_a_value,
+                team_b_value,
+            ),
+        ))?;
+        let trade_id = conn.last_insert_rowid();
+        for traded in trade.moves.iter() {
+            let new_spot = handle_serde_error(serde_json::to_string(&traded.new_spot))?;
+            let updated = handle_sql_error(conn.execute(
+                "UPDATE players SET team_id = ?1, team_spot = ?2 WHERE player_id = ?3 AND team_id = ?4",
+                (traded.to_team_id, new_spot, traded.player_id, traded.from_team_id),
+            ))?;
+            if updated != 1 {
+                return Err(EditLeagueError::RosterError(format!(
+                    "{} could not be found in the database, so the trade was not made.",
+                    traded.name
+                )));
+            }
+            let mut stmt = handle_sql_error(conn.prepare_cached(
+                "INSERT INTO trade_players(trade_id, player_id, player_name, from_team_id, to_team_id, trade_value, old_team_spot, new_team_spot)
+                VALUES(?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            ))?;
+            handle_sql_error(stmt.execute((
+                trade_id,
+                traded.player_id,
+                &traded.name,
+                traded.from_team_id,
+                traded.to_team_id,
+                traded.trade_value,
+                traded.old_spot.to_string(),
+                traded.new_spot.to_string(),
+            )))?;
+        }
+        Ok(())
+    })?;
+    let Trade {
+        team_a,
+        team_b,
+        new_a,
+        new_b,
+        ..
+    } = trade;
+    league.teams[team_a] = new_a;
+    league.teams[team_b] = new_b;
+    Ok(())
+}
+
+/// Prompts the user to choose players from a team's roster, and returns their ids.
+fn select_players(team: &Team, message: &str) -> Result<Vec<i64>, EditLeagueError> {
+    let roster = roster_with_spots(team);
+    let options: Vec<String> = roster
+        .iter()
+        .map(|(spot, player)| {
+            format!(
+                "{} {} ({}), Trade Value {}",
+                player.name, player.pos, spot, player.trade_value
+            )
+        })
+        .collect();
+    let chosen = handle_inquire_error(
+        MultiSelect::new(message, options)
+            .with_page_size(10)
+            .raw_prompt(),
+    )?;
+    Ok(chosen
+        .into_iter()
+        .map(|choice| roster[choice.index].1.player_id)
+        .collect())
+}
+
+/// Prompts the user to choose two teams and the players each team gives up, shows the trade, and makes it if the user confirms.
+pub fn trade_from_input(league: &mut League, conn: &mut Connection) -> Result<(), EditLeagueError> {
+    let team_names: Vec<String> = league.teams.iter().map(|team| team.name.clone()).collect();
+    let choose_team = |message: &str, names: Vec<String>| {
+        Select::new(message, names)
+            .raw_prompt()
+            .map(|choice| choice.index)
+    };
+    let team_a = match choose_team("Choose the first team in the trade.", team_names.clone()) {
+        Ok(index) => index,
+        Err(message) => return inquire_check(message),
+    };
+    let team_b = match choose_team("Choose the second team in the trade.", team_names) {
+        Ok(index) => index,
+        Err(message) => return inquire_check(message),
+    };
+    let chosen = select_players(
+        &league.teams[team_a],
+        &format!("Which players will {} trade?", league.teams[team_a].name),
+    )
+    .and_then(|a_ids| {
+        let message = format!("Which players will {} trade?", league.teams[team_b].name);
+        select_players(&league.teams[team_b], &message).map(|b_ids| (a_ids, b_ids))
+    });
+    let (a_ids, b_ids) = match chosen {
+        Ok(ids) => ids,
+        Err(EditLeagueError::Inquire(message)) => return inquire_check(message),
+        Err(message) => return Err(message),
+    };
+    let trade = propose_trade(league, team_a, team_b, &a_ids, &b_ids)?;
+    println!("{}", trade);
+    trade.check()?;
+    match Confirm::new("Would you like to make this trade?").prompt() {
+        Ok(true) => {}
+        Ok(false) => return Ok(()),
+        Err(message) => return inquire_check(message),
+    }
+    execute_trade(conn, league, trade)?;
+    println!("The trade has been made.");
+    save_league(league);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::league::load_teams_from_sql;
+    use crate::player::PlayerGender;
+    use crate::{player_pool_test, test_league};
+
+    #[test]
+    fn player_trade() {
+        let (mut conn, mut league) = test_league(Era::Modern, PlayerGender::Male, 8);
+        let lineup_id = |team: &Team, pos: PlayerPosition| {
+            team.lineup.iter().find(|x| x.pos == pos).unwrap().player_id
+        };
+        let (angels, rainiers) = (&league.teams[0], &league.teams[1]);
+        assert!(
+            propose_trade(&league, 0, 0, &[lineup_id(angels, PlayerPosition::SS)], &[]).is_err()
+        );
+        // Trading a shortstop for a catcher leaves both teams missing a position.
+        let bad_trade = propose_trade(
+            &league,
+            0,
+            1,
+            &[lineup_id(angels, PlayerPosition::SS)],
+            &[lineup_id(rainiers, PlayerPosition::C)],
+        )
+        .unwrap();
+        assert!(!bad_trade.problems.is_empty());
+        assert!(execute_trade(&mut conn, &mut league, bad_trade).is_err());
+        let (angels, rainiers) = (&league.teams[0], &league.teams[1]);
+        let a_ids = [
+            lineup_id(angels, PlayerPosition::SS),
+            angels.bullpen.as_ref().unwrap()[0].player_id,
+        ];
+        let b_ids = [
+            lineup_id(rainiers, PlayerPosition::SS),
+            rainiers.bullpen.as_ref().unwrap()[0].player_id,
+        ];
+        let trade = propose_trade(&league, 0, 1, &a_ids, &b_ids).unwrap();
+        assert!(trade.problems.is_empty());
+        let a_value: i32 = [&angels.lineup, angels.bullpen.as_ref().unwrap()]
+            .iter()
+            .flat_map(|pool| pool.iter())
+            .filter(|x| a_ids.contains(&x.player_id))
+            .map(|x| x.trade_value)
+            .sum();
+        assert_eq!(trade.value_given(angels.team_id), a_value);
+        execute_trade(&mut conn, &mut league, trade).unwrap();
+        let count = |sql: &str| -> i64 { conn.query_row(sql, [], |row| row.get(0)).unwrap() };
+        assert_eq!(count("SELECT COUNT(*) FROM trades"), 1);
+        assert_eq!(count("SELECT COUNT(*) FROM trade_players"), 4);
+        let mut loaded = League::new(
+            &league.name,
+            league.gender,
+            league.era,
+            league.league_id,
+            None,
+        );
+        load_teams_from_sql(league.league_id, &mut loaded, &mut conn).unwrap();
+        for (index, team) in loaded.teams.iter().enumerate() {
+            assert_eq!(team.lineup.len(), 8);
+            assert_eq!(team.bullpen.as_ref().unwrap().len(), 7);
+            assert_eq!(team.team_score, league.teams[index].team_score);
+            player_pool_test(&team.lineup, team.team_id, false);
+        }
+        assert_eq!(lineup_id(&loaded.teams[1], PlayerPosition::SS), a_ids[0]);
+        assert_eq!(lineup_id(&loaded.teams[0], PlayerPosition::SS), b_ids[0]);
+    }
+}
@@ -328,6 +328,12 @@ impl fmt::Display for Contact {
     }
 }
 
+impl UpdatePlayerDb for Contact {
+    fn get_column_name(&self) -> &str {
+        "contact"
+    }
+}
+
 #[derive(Copy, Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum Defense {
     #[serde(rename = "D+")]
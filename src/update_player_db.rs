@@ -1,5 +1,5 @@
 use rusqlite::Connection;
-use serde::Serialize;
+use serde::{Serialize, Serializer};
 
 fn update_player_column(
     conn: &mut Connection,
@@ -57,3 +57,28 @@ pub fn update_player_db_option<T: UpdatePlayerDb + Serialize + Default>(
     let input_value = serde_json::to_value(&field_opt).unwrap();
     update_player_column(conn, column_name, player_id, input_value)
 }
+
+/// A value saved in a column of the players table that does not have it's own type, such as a player's bt or note.
+/// The value is saved as JSON, the same as the other columns.
+pub struct PlayerColumn<T: Serialize> {
+    column_name: &'static str,
+    value: T,
+}
+
+impl<T: Serialize> PlayerColumn<T> {
+    pub fn new(column_name: &'static str, value: T) -> Self {
+        PlayerColumn { column_name, value }
+    }
+}
+
+impl<T: Serialize> Serialize for PlayerColumn<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<T: Serialize> UpdatePlayerDb for PlayerColumn<T> {
+    fn get_column_name(&self) -> &str {
+        self.column_name
+    }
+}